@@ -0,0 +1,40 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Runtime API definition for the enclave bridge pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use enclave_bridge_primitives::{ProcessedParentchainBlock, ShardIdentifier};
+use parity_scale_codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait EnclaveBridgeApi<AccountId, BlockNumber>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// The account holding the funds and assets shielded to `shard`. It is a sub account of the
+		/// pallet id of enclave-bridge, seeded with the blake2_256 hash of the shard.
+		fn shard_vault(shard: ShardIdentifier) -> AccountId;
+
+		/// The latest parentchain block `shard` has confirmed to have processed.
+		fn last_processed_parentchain_block(
+			shard: ShardIdentifier,
+		) -> Option<ProcessedParentchainBlock<BlockNumber>>;
+	}
+}
@@ -17,13 +17,13 @@
 
 //! Teerex pallet benchmarking
 
-#![cfg(any(test, feature = "runtime-benchmarks"))]
+#![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
 use frame_benchmarking::{benchmarks, v2::*};
 use frame_system::RawOrigin;
 use pallet_teerex::Pallet as Teerex;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::Encode;
 use sp_runtime::traits::{Bounded, Hash, StaticLookup};
 use sp_std::vec;
 use teerex_primitives::{MultiEnclave, SgxEnclave};
@@ -41,25 +41,43 @@ benchmarks! {
 
 	where_clause {  where T::AccountId: From<[u8; 32]>, T::Hash: From<[u8; 32]>,}
 
-	// Benchmark `call_worker`. There are no worst conditions. The benchmark showed that
-	// execution time is constant irrespective of cyphertext size.
+	// Benchmark `invoke` parametrized with the cyphertext size. The worst case is the request
+	// being stored in the inbox and the sender being charged by `RequestFee`.
 	invoke {
-		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
-		let req = Request { shard:H256::from_slice(&TEST4_SETUP.mrenclave), cyphertext: vec![1u8; 2000]};
-	}: _(RawOrigin::Signed(accounts[0].clone()), req)
+		let l in 0 .. T::MaxRequestSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() >> 2);
+		let req = Request { shard: H256::from_slice(&TEST4_SETUP.mrenclave), cyphertext: vec![1u8; l as usize] };
+	}: _(RawOrigin::Signed(caller), req)
 
 	// Benchmark `confirm_processed_parentchain_block` with the worst possible conditions:
 	// * sender enclave is registered
+	// * all other signers of a full shard status are evicted for inactivity
+	// * the shard and the sender have processed a parentchain block before
+	// * the hash of the confirmed block is known
 	confirm_processed_parentchain_block {
 		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
 		add_sovereign_enclaves_to_registry::<T>(&accounts);
+		let shard = ShardIdentifier::from(EnclaveFingerprint::default());
+		add_inactive_signers_to_shard_status::<T>(shard, MAX_SHARD_STATUS_SIGNER_COUNT - 1);
 
 		let block_hash: H256 = [2; 32].into();
 		let merkle_root: H256 = [4; 32].into();
-		let block_number: u32 = 0;
-		let shard = ShardIdentifier::from(EnclaveFingerprint::default());
-
-	}: _(RawOrigin::Signed(accounts[0].clone()), shard, block_hash, block_number.into(), merkle_root)
+		let block_number = frame_system::Pallet::<T>::block_number();
+
+		frame_system::Pallet::<T>::set_block_number(block_number.saturating_add(1u32.into()));
+		<frame_system::BlockHash<T>>::insert(block_number, T::Hash::from(block_hash.0));
+		<LastProcessedParentchainBlock<T>>::insert(shard, ProcessedParentchainBlock {
+			block_number,
+			block_hash,
+			trusted_calls_merkle_root: merkle_root,
+		});
+		<LastProcessedParentchainBlockNumber<T>>::insert(shard, &accounts[0], block_number);
+	}: _(RawOrigin::Signed(accounts[0].clone()), shard, block_hash, block_number, merkle_root)
+	verify {
+		assert_eq!(Pallet::<T>::shard_status(shard).unwrap().len(), 1);
+	}
 
 	// worst case: assuming the shard's bonding account doesn't exist yet
 	shield_funds {
@@ -68,7 +86,7 @@ benchmarks! {
 		let amount = BalanceOf::<T>::max_value() >> 2 ;
 		T::Currency::make_free_balance_be(&caller, amount << 1);
 		let shard = ShardIdentifier::default();
-		let bonding_account = T::AccountId::decode(&mut shard.encode().as_ref()).unwrap();
+		let bonding_account = Pallet::<T>::shard_vault(shard);
 		let incognito_account_encrypted = [0u8; 4096].to_vec();
 	}: _(RawOrigin::Signed(caller.clone()), shard, incognito_account_encrypted, amount)
 	verify {
@@ -78,7 +96,8 @@ benchmarks! {
 		assert_eq!(amount, T::Currency::free_balance(&bonding_account));
 	}
 
-	// worst case: assuming the beneficiary account doesn't exist yet
+	// worst case: assuming the beneficiary account doesn't exist yet and all other signers of a
+	// full shard status are evicted for inactivity
 	unshield_funds {
 		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
 		let beneficiary = accounts[0].clone();
@@ -89,10 +108,12 @@ benchmarks! {
 
 		let amount = BalanceOf::<T>::max_value() >> 2 ;
 		let shard = ShardIdentifier::default();
-		let bonding_account = T::AccountId::decode(&mut shard.encode().as_ref()).unwrap();
+		add_inactive_signers_to_shard_status::<T>(shard, MAX_SHARD_STATUS_SIGNER_COUNT - 1);
+		let bonding_account = Pallet::<T>::shard_vault(shard);
 		T::Currency::make_free_balance_be(&bonding_account, amount << 1);
+		let issued_at = frame_system::Pallet::<T>::block_number().saturating_sub(1u32.into());
 
-	}: _(RawOrigin::Signed(caller.clone()), shard, beneficiary.clone(), amount, H256::default())
+	}: _(RawOrigin::Signed(caller.clone()), shard, beneficiary.clone(), amount, H256::default(), issued_at)
 	verify {
 		// Event comparison in an actual node is way too cumbersome as the `RuntimeEvent`
 		// does not implement `PartialEq`. So we only verify that the event is emitted here,
@@ -100,6 +121,113 @@ benchmarks! {
 		assert_eq!(amount, T::Currency::free_balance(&beneficiary));
 	}
 
+	// worst case: assuming the shard's vault doesn't exist yet
+	shield_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		let asset_id = T::BenchmarkHelper::get_registered_asset();
+		let amount: AssetBalanceOf<T> = 1_000_000u32.into();
+		<T::Fungibles as fungibles::Mutate<_>>::mint_into(asset_id.clone(), &caller, amount.saturating_mul(2u32.into())).unwrap();
+		let shard = ShardIdentifier::default();
+		let bonding_account = Pallet::<T>::shard_vault(shard);
+		let incognito_account_encrypted = [0u8; 4096].to_vec();
+	}: _(RawOrigin::Signed(caller), shard, asset_id.clone(), incognito_account_encrypted, amount)
+	verify {
+		assert_eq!(amount, <T::Fungibles as fungibles::Inspect<_>>::balance(asset_id, &bonding_account));
+	}
+
+	// worst case: the confirmation reaches the threshold of a full shard status, hence the
+	// transfer is executed, and the beneficiary account doesn't exist yet
+	unshield_asset {
+		let validateers: Vec<T::AccountId> = generate_accounts::<T>(MAX_SHARD_STATUS_SIGNER_COUNT);
+		add_sovereign_enclaves_to_registry::<T>(&validateers);
+		let (caller, confirmers) = validateers.split_last().unwrap();
+
+		let shard = ShardIdentifier::default();
+		let issued_at = frame_system::Pallet::<T>::block_number();
+		let block_number = issued_at.saturating_add(1u32.into());
+		frame_system::Pallet::<T>::set_block_number(block_number);
+		add_signers_to_shard_status::<T>(shard, &validateers, block_number);
+		<ShardConfigRegistry<T>>::insert(shard, UpgradableShardConfig::from(
+			ShardConfig::new(shard).with_unshield_threshold(MAX_SHARD_STATUS_SIGNER_COUNT)
+		));
+
+		let beneficiary: T::AccountId = account("beneficiary", 0, 0);
+		let asset_id = T::BenchmarkHelper::get_registered_asset();
+		let amount: AssetBalanceOf<T> = 1_000_000u32.into();
+		let bonding_account = Pallet::<T>::shard_vault(shard);
+		<T::Fungibles as fungibles::Mutate<_>>::mint_into(asset_id.clone(), &bonding_account, amount.saturating_mul(2u32.into())).unwrap();
+
+		let call_hash = H256::default();
+		<PendingAssetUnshieldCalls<T>>::insert(call_hash, (asset_id.clone(), PendingAssetUnshield::<T> {
+			shard,
+			beneficiary: beneficiary.clone(),
+			amount,
+			issued_at,
+			confirmed_by: confirmers.to_vec().try_into().unwrap(),
+			expires_at: block_number.saturating_add(T::UnshieldConfirmationTimeout::get()),
+		}));
+	}: _(RawOrigin::Signed(caller.clone()), shard, asset_id.clone(), beneficiary.clone(), amount, call_hash, issued_at)
+	verify {
+		assert_eq!(amount, <T::Fungibles as fungibles::Inspect<_>>::balance(asset_id, &beneficiary));
+	}
+
+	// worst case: all signers of a full shard status are evicted for inactivity
+	purge_inactive_enclaves_from_shard_status {
+		let caller: T::AccountId = whitelisted_caller();
+		let shard = ShardIdentifier::default();
+		add_inactive_signers_to_shard_status::<T>(shard, MAX_SHARD_STATUS_SIGNER_COUNT);
+	}: _(RawOrigin::Signed(caller), shard)
+	verify {
+		assert!(Pallet::<T>::shard_status(shard).unwrap().is_empty());
+	}
+
+	// Benchmark `acknowledge_processed_requests` parametrized with the number of requests it
+	// removes from the inbox.
+	acknowledge_processed_requests {
+		let n in 0 .. T::MaxInboxLength::get();
+
+		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
+		add_sovereign_enclaves_to_registry::<T>(&accounts);
+		let shard = ShardIdentifier::from(EnclaveFingerprint::default());
+		// the request with id 0 has been acknowledged before, hence `n` may be zero
+		for request_id in 1..=n as u64 {
+			<RequestInbox<T>>::insert(shard, request_id, vec![1u8; T::MaxRequestSize::get() as usize]);
+		}
+		<RequestInboxStatuses<T>>::insert(shard, RequestInboxStatus {
+			next_request_id: n as u64 + 1,
+			next_unacknowledged_id: 1,
+		});
+	}: _(RawOrigin::Signed(accounts[0].clone()), shard, n as u64)
+	verify {
+		assert!(Pallet::<T>::request_inbox_status(shard).is_empty());
+	}
+
+	// worst case: the shard's vault doesn't exist yet
+	sweep_legacy_shard_vault {
+		let caller: T::AccountId = whitelisted_caller();
+		let shard = ShardIdentifier::from([1u8; 32]);
+		let legacy_vault = Pallet::<T>::legacy_shard_vault(shard).unwrap();
+		let amount = BalanceOf::<T>::max_value() >> 2;
+		T::Currency::make_free_balance_be(&legacy_vault, amount);
+		let vault = Pallet::<T>::shard_vault(shard);
+	}: _(RawOrigin::Signed(caller), shard)
+	verify {
+		assert_eq!(amount, T::Currency::free_balance(&vault));
+	}
+
+	// worst case: the shard's vault doesn't hold the asset yet
+	sweep_legacy_shard_vault_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		let shard = ShardIdentifier::from([1u8; 32]);
+		let legacy_vault = Pallet::<T>::legacy_shard_vault(shard).unwrap();
+		let asset_id = T::BenchmarkHelper::get_registered_asset();
+		let amount: AssetBalanceOf<T> = 1_000_000u32.into();
+		<T::Fungibles as fungibles::Mutate<_>>::mint_into(asset_id.clone(), &legacy_vault, amount).unwrap();
+		let vault = Pallet::<T>::shard_vault(shard);
+	}: _(RawOrigin::Signed(caller), shard, asset_id.clone())
+	verify {
+		assert_eq!(amount, <T::Fungibles as fungibles::Inspect<_>>::balance(asset_id, &vault));
+	}
 
 	// Benchmark `publish_hash` with the worst possible conditions:
 	// * sender enclave is registered
@@ -157,6 +285,35 @@ fn add_sovereign_enclaves_to_registry<T: Config>(accounts: &[T::AccountId]) {
 	}
 }
 
+fn add_signers_to_shard_status<T: Config>(
+	shard: ShardIdentifier,
+	signers: &[T::AccountId],
+	last_activity: BlockNumberFor<T>,
+) {
+	let signer_statuses: Vec<ShardSignerStatus<T>> = signers
+		.iter()
+		.map(|signer| ShardSignerStatus::<T> {
+			signer: signer.clone(),
+			fingerprint: EnclaveFingerprint::default(),
+			last_activity,
+		})
+		.collect();
+	<ShardStatus<T>>::insert(shard, ShardSignerStatusVec::<T>::try_from(signer_statuses).unwrap());
+}
+
+/// Fills the shard status with `count` signers and advances the current block until they have
+/// exceeded the `ShardSignerInactivityTimeout`.
+fn add_inactive_signers_to_shard_status<T: Config>(shard: ShardIdentifier, count: u32) {
+	let inactive_since = frame_system::Pallet::<T>::block_number();
+	let signers: Vec<T::AccountId> = (0..count).map(|n| account("inactive", n, n)).collect();
+	add_signers_to_shard_status::<T>(shard, &signers, inactive_since);
+	frame_system::Pallet::<T>::set_block_number(
+		inactive_since
+			.saturating_add(T::ShardSignerInactivityTimeout::get())
+			.saturating_add(1u32.into()),
+	);
+}
+
 fn get_data(x: u32) -> Vec<u8> {
 	vec![0u8; x.try_into().unwrap()]
 }
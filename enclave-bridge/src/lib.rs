@@ -19,29 +19,55 @@
 
 pub use crate::weights::WeightInfo;
 use enclave_bridge_primitives::{
-	Request, ShardConfig, ShardIdentifier, ShardSignerStatus as ShardSignerStatusGeneric,
-	UpgradableShardConfig, ENCLAVE_BRIDGE, MAX_SHARD_STATUS_SIGNER_COUNT,
+	ExecutedUnshield, PendingUnshield as PendingUnshieldGeneric, ProcessedParentchainBlock,
+	Request, RequestInboxStatus, ShardConfig, ShardIdentifier,
+	ShardSignerStatus as ShardSignerStatusGeneric, UpgradableShardConfig, ENCLAVE_BRIDGE,
+	MAX_SHARD_STATUS_SIGNER_COUNT,
 };
 use frame_support::{
 	dispatch::{DispatchErrorWithPostInfo, DispatchResult, DispatchResultWithPostInfo},
 	ensure,
-	pallet_prelude::ConstU32,
-	traits::{Currency, ExistenceRequirement},
+	pallet_prelude::{ConstU32, StorageVersion},
+	storage::{PrefixIterator, StoragePrefixedMap},
+	traits::{
+		tokens::{fungibles, Fortitude, Preservation},
+		Currency, ExistenceRequirement, Get, OnUnbalanced, WithdrawReasons,
+	},
+	weights::Weight,
+	PalletId,
 };
 use frame_system::{self, ensure_signed, pallet_prelude::BlockNumberFor};
 use pallet_teerex::Pallet as Teerex;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, DecodeAll, Encode};
 use sp_core::{bounded::BoundedVec, H256};
-use sp_runtime::traits::{SaturatedConversion, Saturating};
-use sp_std::{prelude::*, str, vec};
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+	DispatchError,
+};
+use sp_std::{marker::PhantomData, prelude::*, str, vec};
+use staging_xcm::latest::Location;
 use teerex_primitives::{EnclaveFingerprint, MultiEnclave};
+use xcm_primitives::AssetLocationGetter;
 // Disambiguate associated types
 pub type AccountId<T> = <T as frame_system::Config>::AccountId;
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountId<T>>>::Balance;
+pub type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<AccountId<T>>>::NegativeImbalance;
 pub type ShardSignerStatus<T> =
 	ShardSignerStatusGeneric<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
 pub type ShardSignerStatusVec<T> =
 	BoundedVec<ShardSignerStatus<T>, ConstU32<MAX_SHARD_STATUS_SIGNER_COUNT>>;
+pub type PendingUnshield<T> =
+	PendingUnshieldGeneric<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+pub type AssetIdOf<T> = <<T as Config>::Fungibles as fungibles::Inspect<AccountId<T>>>::AssetId;
+pub type AssetBalanceOf<T> =
+	<<T as Config>::Fungibles as fungibles::Inspect<AccountId<T>>>::Balance;
+pub type PendingAssetUnshield<T> = PendingUnshieldGeneric<
+	<T as frame_system::Config>::AccountId,
+	AssetBalanceOf<T>,
+	BlockNumberFor<T>,
+>;
 
 pub use pallet::*;
 
@@ -50,6 +76,59 @@ const TOPICS_LIMIT: usize = 5;
 /// Maximum number of bytes for the `data` in the `publish_hash` call.
 const DATA_LENGTH_LIMIT: usize = 100;
 
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AssetId> {
+	/// An asset with a known location which can be minted.
+	fn get_registered_asset() -> AssetId;
+}
+
+/// Operations on a shard which are restricted while the shard is in maintenance mode, see
+/// `Pallet::is_allowed_during_maintenance`.
+#[derive(Clone, Copy, PartialEq, Eq, sp_core::RuntimeDebug)]
+pub enum ShardOperation {
+	Invoke,
+	Shield,
+	Unshield,
+	ConfirmParentchainBlock,
+	AcknowledgeRequests,
+	ConfirmSidechainBlock,
+}
+
+/// Charges the sender of an indirect invocation before it is registered.
+pub trait IndirectInvocationFee<AccountId> {
+	fn charge(who: &AccountId, shard: ShardIdentifier, request_size: u32) -> DispatchResult;
+}
+
+/// Indirect invocations are only paid for by the transaction fee.
+impl<AccountId> IndirectInvocationFee<AccountId> for () {
+	fn charge(_who: &AccountId, _shard: ShardIdentifier, _request_size: u32) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// Withdraws `FeePerByte` for each byte of the request cyphertext from the sender and passes it
+/// to `OnFee`, e.g. a treasury. Fees are kept apart from the funds shielded to the shard vaults.
+pub struct PerByteFee<T, FeePerByte, OnFee>(PhantomData<(T, FeePerByte, OnFee)>);
+impl<T, FeePerByte, OnFee> IndirectInvocationFee<T::AccountId> for PerByteFee<T, FeePerByte, OnFee>
+where
+	T: Config,
+	FeePerByte: Get<BalanceOf<T>>,
+	OnFee: OnUnbalanced<NegativeImbalanceOf<T>>,
+{
+	fn charge(who: &T::AccountId, _shard: ShardIdentifier, request_size: u32) -> DispatchResult {
+		let fee = FeePerByte::get().saturating_mul(request_size.into());
+		if fee.is_zero() {
+			return Ok(())
+		}
+		let imbalance =
+			T::Currency::withdraw(who, fee, WithdrawReasons::FEE, ExistenceRequirement::KeepAlive)?;
+		OnFee::on_unbalanced(imbalance);
+		Ok(())
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -57,19 +136,64 @@ pub mod pallet {
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::prune_unshield_calls(n, remaining_weight)
+		}
+	}
 
 	#[pallet::config]
 	pub trait Config:
 		frame_system::Config + pallet_timestamp::Config + pallet_teerex::Config
 	{
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The vault of each shard is a sub account of this id.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
 		type Currency: Currency<<Self as frame_system::Config>::AccountId>;
+		/// Assets which can be shielded in addition to the native `Currency`.
+		type Fungibles: fungibles::Mutate<<Self as frame_system::Config>::AccountId>;
+		/// Resolves the location of an asset. Only assets with a known location can be shielded.
+		type AssetLocationGetter: AssetLocationGetter<AssetIdOf<Self>>;
 		type WeightInfo: WeightInfo;
+
+		/// Confirmations of an unshielding which don't reach the threshold within this many
+		/// blocks after the first one are discarded.
+		#[pallet::constant]
+		type UnshieldConfirmationTimeout: Get<BlockNumberFor<Self>>;
+
+		/// Signers which haven't been active on a shard for this many blocks are purged from its
+		/// shard status.
+		#[pallet::constant]
+		type ShardSignerInactivityTimeout: Get<BlockNumberFor<Self>>;
+
+		/// The maximum size of the cyphertext of a `Request` in bytes.
+		#[pallet::constant]
+		type MaxRequestSize: Get<u32>;
+
+		/// Charges senders of indirect invocations, e.g. depending on the shard and request size.
+		type RequestFee: IndirectInvocationFee<Self::AccountId>;
+
+		/// The maximum number of unacknowledged requests kept in the inbox of a shard. Requests
+		/// are not stored on chain if it is zero. Requests to a full inbox are only registered
+		/// by event, like all requests before the inbox existed, along with
+		/// `IndirectInvocationNotQueued`.
+		#[pallet::constant]
+		type MaxInboxLength: Get<u32>;
+
+		/// Unshield calls issued by L2 more than this many blocks ago are rejected, hence the
+		/// record of an executed unshield call is only kept for this many blocks.
+		#[pallet::constant]
+		type UnshieldRetentionPeriod: Get<BlockNumberFor<Self>>;
+
+		/// Helper trait for benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<AssetIdOf<Self>>;
 	}
 
 	#[pallet::event]
@@ -77,6 +201,21 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// an indirect invocation has been registered for execution on L2
 		IndirectInvocationRegistered(ShardIdentifier),
+		/// an indirect invocation has been stored in the request inbox of the shard
+		IndirectInvocationQueued {
+			shard: ShardIdentifier,
+			request_id: u64,
+		},
+		/// an indirect invocation has not been stored because the request inbox of the shard is
+		/// full. It is only registered by event
+		IndirectInvocationNotQueued {
+			shard: ShardIdentifier,
+		},
+		/// L2 acknowledged processing all requests of the inbox up to and including `request_id`
+		ProcessedRequestsAcknowledged {
+			shard: ShardIdentifier,
+			request_id: u64,
+		},
 		/// funds have been shielded to L2
 		ShieldFunds {
 			shard: ShardIdentifier,
@@ -89,6 +228,30 @@ pub mod pallet {
 			beneficiary: T::AccountId,
 			amount: BalanceOf<T>,
 		},
+		/// assets have been shielded to L2
+		ShieldedAsset {
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+			location: Location,
+			encrypted_beneficiary: Vec<u8>,
+			amount: AssetBalanceOf<T>,
+		},
+		/// assets have been unshielded from L2 back to L1. The location is `None` if the asset
+		/// has been unregistered since it was shielded.
+		UnshieldedAsset {
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+			location: Option<Location>,
+			beneficiary: T::AccountId,
+			amount: AssetBalanceOf<T>,
+		},
+		/// a validateer has confirmed an unshielding which has not reached its threshold yet
+		UnshieldConfirmed {
+			shard: ShardIdentifier,
+			call_hash: H256,
+			confirmations: u32,
+			threshold: u32,
+		},
 		/// L2 confirmed processing of a parentchain block
 		ProcessedParentchainBlock {
 			shard: ShardIdentifier,
@@ -108,6 +271,17 @@ pub mod pallet {
 			shard: ShardIdentifier,
 			subject: T::AccountId,
 		},
+		/// funds have been moved from the legacy vault of a shard to its vault
+		SweptLegacyShardVault {
+			shard: ShardIdentifier,
+			amount: BalanceOf<T>,
+		},
+		/// assets have been moved from the legacy vault of a shard to its vault
+		SweptLegacyShardVaultAsset {
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+			amount: AssetBalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -124,6 +298,43 @@ pub mod pallet {
 		EnclaveNotFoundInShardStatus,
 		/// Shard not found
 		ShardNotFound,
+		/// The enclave has already confirmed this unshielding
+		UnshieldAlreadyConfirmed,
+		/// The unshielding parameters differ from the ones confirmed before for this call hash
+		UnshieldParametersMismatch,
+		/// The asset has no known location and can't be shielded
+		UnknownAssetLocation,
+		/// No enclave in the shard status has exceeded the inactivity timeout
+		NoInactiveEnclaveInShardStatus,
+		/// The enclave is not among the authorities of the shard config
+		EnclaveNotAuthorizedForShard,
+		/// The shard status has reached the `max_instances` of the shard config
+		TooManyInstancesForShard,
+		/// Indirect invocations are rejected while the shard is in maintenance mode
+		InvocationDuringMaintenance,
+		/// Shielding is rejected while the shard is in maintenance mode
+		ShieldingDuringMaintenance,
+		/// Unshielding is rejected while the shard is in maintenance mode
+		UnshieldingDuringMaintenance,
+		/// The confirmed parentchain block is older than the last one the enclave has confirmed
+		/// for the shard
+		ParentchainBlockNumberRegression,
+		/// The confirmed block hash doesn't match the parentchain block with that number
+		ParentchainBlockHashMismatch,
+		/// The confirmed parentchain block is not older than the current block
+		UnknownParentchainBlock,
+		/// The cyphertext of the request exceeds `MaxRequestSize`
+		RequestTooLarge,
+		/// The acknowledged request has not been queued yet
+		UnknownRequestId,
+		/// The unshield threshold exceeds `max_instances` or the maximum shard status size
+		InvalidUnshieldThreshold,
+		/// The unshield call has been issued more than `UnshieldRetentionPeriod` blocks ago
+		UnshieldCallExpired,
+		/// Shards have no legacy vault if account ids are not 32 bytes long
+		NoLegacyShardVault,
+		/// The legacy vault of the shard holds nothing to be moved
+		LegacyShardVaultEmpty,
 	}
 
 	#[pallet::storage]
@@ -148,7 +359,68 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn confirmed_calls)]
 	pub type ExecutedUnshieldCalls<T: Config> =
-		StorageMap<_, Blake2_128Concat, H256, u64, ValueQuery>;
+		StorageMap<_, Blake2_128Concat, H256, ExecutedUnshield<BlockNumberFor<T>>, OptionQuery>;
+
+	/// The raw storage key of the executed or pending unshield call the last pruning sweep
+	/// stopped at.
+	#[pallet::storage]
+	pub type UnshieldPruneCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// The latest parentchain block any validateer of each shard has confirmed to have processed.
+	#[pallet::storage]
+	#[pallet::getter(fn last_processed_parentchain_block)]
+	pub type LastProcessedParentchainBlock<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		ShardIdentifier,
+		ProcessedParentchainBlock<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The number of the latest parentchain block each validateer has confirmed to have processed
+	/// for a shard. Validateers of a shard may lag behind each other, hence regressions are checked
+	/// per validateer.
+	#[pallet::storage]
+	#[pallet::getter(fn last_processed_parentchain_block_of)]
+	pub type LastProcessedParentchainBlockNumber<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ShardIdentifier,
+		Blake2_128Concat,
+		T::AccountId,
+		BlockNumberFor<T>,
+		OptionQuery,
+	>;
+
+	/// Requests which haven't been acknowledged by L2 yet, by shard and request id.
+	#[pallet::storage]
+	#[pallet::getter(fn inbox_request)]
+	pub type RequestInbox<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ShardIdentifier,
+		Twox64Concat,
+		u64,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn request_inbox_status)]
+	pub type RequestInboxStatuses<T: Config> =
+		StorageMap<_, Blake2_128Concat, ShardIdentifier, RequestInboxStatus, ValueQuery>;
+
+	/// Unshieldings which are waiting for more confirmations, by call hash.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_unshield)]
+	pub type PendingUnshieldCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, H256, PendingUnshield<T>, OptionQuery>;
+
+	/// Asset unshieldings which are waiting for more confirmations, by call hash.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_asset_unshield)]
+	pub type PendingAssetUnshieldCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, H256, (AssetIdOf<T>, PendingAssetUnshield<T>), OptionQuery>;
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T>
@@ -158,10 +430,26 @@ pub mod pallet {
 		<T as frame_system::Config>::Hash: From<[u8; 32]>,
 	{
 		#[pallet::call_index(0)]
-		#[pallet::weight((<T as Config>::WeightInfo::invoke(), DispatchClass::Normal, Pays::Yes))]
+		#[pallet::weight((<T as Config>::WeightInfo::invoke(request.cyphertext.len().saturated_into()), DispatchClass::Normal, Pays::Yes))]
 		pub fn invoke(origin: OriginFor<T>, request: Request) -> DispatchResult {
-			let _sender = ensure_signed(origin)?;
+			let sender = ensure_signed(origin)?;
+			let request_size: u32 = request.cyphertext.len().saturated_into();
+			ensure!(request_size <= T::MaxRequestSize::get(), Error::<T>::RequestTooLarge);
+			ensure!(
+				Self::is_allowed_during_maintenance(
+					request.shard,
+					ShardOperation::Invoke,
+					<frame_system::Pallet<T>>::block_number()
+				),
+				Error::<T>::InvocationDuringMaintenance
+			);
+			T::RequestFee::charge(&sender, request.shard, request_size)?;
 			log::info!(target: ENCLAVE_BRIDGE, "invoke with {:?}", request);
+			if T::MaxInboxLength::get() > 0 &&
+				Self::queue_request(request.shard, request.cyphertext).is_none()
+			{
+				Self::deposit_event(Event::IndirectInvocationNotQueued { shard: request.shard });
+			}
 			Self::deposit_event(Event::IndirectInvocationRegistered(request.shard));
 			Ok(())
 		}
@@ -177,11 +465,29 @@ pub mod pallet {
 			trusted_calls_merkle_root: H256,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			Self::get_sovereign_enclave_and_touch_shard(
-				&sender,
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			Self::get_sovereign_enclave_and_touch_shard(&sender, shard, current_block_number)?;
+			Self::ensure_valid_parentchain_block(
 				shard,
-				<frame_system::Pallet<T>>::block_number(),
+				&sender,
+				block_number,
+				block_hash,
+				current_block_number,
 			)?;
+			<LastProcessedParentchainBlockNumber<T>>::insert(shard, &sender, block_number);
+			// lagging validateers don't set the shard back
+			if Self::last_processed_parentchain_block(shard)
+				.map_or(true, |last| block_number >= last.block_number)
+			{
+				<LastProcessedParentchainBlock<T>>::insert(
+					shard,
+					ProcessedParentchainBlock {
+						block_number,
+						block_hash,
+						trusted_calls_merkle_root,
+					},
+				);
+			}
 
 			log::debug!(
 				"Processed parentchain block confirmed by sovereign enclave {:?} for shard {:}, block hash {:?}",
@@ -198,9 +504,9 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Sent by a client who requests to get shielded funds managed by an enclave. For this on-chain balance is sent to the bonding_account of the enclave.
-		/// The bonding_account does not have a private key as the balance on this account is exclusively managed from withing the pallet_teerex.
-		/// Note: The bonding_account is bit-equivalent to the worker shard.
+		/// Sent by a client who requests to get shielded funds managed by an enclave. For this on-chain balance is sent to the bonding_account of the shard.
+		/// The bonding_account does not have a private key as the balance on this account is exclusively managed from within the pallet_enclave_bridge.
+		/// Note: The bonding_account is the `shard_vault` derived from `PalletId` and the shard.
 		#[pallet::call_index(2)]
 		#[pallet::weight((<T as Config>::WeightInfo::shield_funds(), DispatchClass::Normal, Pays::Yes))]
 		pub fn shield_funds(
@@ -210,8 +516,15 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			let bonding_account = T::AccountId::decode(&mut shard.encode().as_ref())
-				.expect("always possible to decode [u8;32]");
+			ensure!(
+				Self::is_allowed_during_maintenance(
+					shard,
+					ShardOperation::Shield,
+					<frame_system::Pallet<T>>::block_number()
+				),
+				Error::<T>::ShieldingDuringMaintenance
+			);
+			let bonding_account = Self::shard_vault(shard);
 			T::Currency::transfer(
 				&sender,
 				&bonding_account,
@@ -227,6 +540,12 @@ pub mod pallet {
 		}
 
 		/// Sent by enclaves only as a result of an `unshield` request from a client to an enclave.
+		///
+		/// The transfer is executed once `unshield_threshold` distinct enclaves of the shard have
+		/// confirmed the same `call_hash` within `UnshieldConfirmationTimeout` blocks.
+		/// `issued_at` is the latest parentchain block L2 had processed when it issued the call.
+		/// Calls issued more than `UnshieldRetentionPeriod` blocks ago are rejected, which
+		/// prevents replays once the record of their execution has been pruned.
 		#[pallet::call_index(3)]
 		#[pallet::weight((<T as Config>::WeightInfo::unshield_funds(), DispatchClass::Normal, Pays::Yes))]
 		pub fn unshield_funds(
@@ -235,34 +554,52 @@ pub mod pallet {
 			beneficiary: T::AccountId,
 			amount: BalanceOf<T>,
 			call_hash: H256,
+			issued_at: BlockNumberFor<T>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			Self::get_sovereign_enclave_and_touch_shard(
-				&sender,
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				Self::is_allowed_during_maintenance(
+					shard,
+					ShardOperation::Unshield,
+					current_block_number
+				),
+				Error::<T>::UnshieldingDuringMaintenance
+			);
+			Self::ensure_unexpired_unshield_call(issued_at, current_block_number)?;
+			let (_, shard_status) =
+				Self::get_sovereign_enclave_and_touch_shard(&sender, shard, current_block_number)?;
+			if Self::note_executed_unshield_confirmation(call_hash) {
+				return Ok(().into())
+			}
+
+			let (pending, threshold_reached) = Self::confirm_unshield(
+				Self::pending_unshield(call_hash),
+				sender,
 				shard,
-				<frame_system::Pallet<T>>::block_number(),
+				&beneficiary,
+				amount,
+				call_hash,
+				issued_at,
+				&shard_status,
+				current_block_number,
 			)?;
-			let bonding_account = T::AccountId::decode(&mut shard.encode().as_ref())
-				.expect("always possible to decode [u8;32]");
-			if !<ExecutedUnshieldCalls<T>>::contains_key(call_hash) {
-				log::info!(target: ENCLAVE_BRIDGE, "Executing unshielding call: {:?}", call_hash);
-				T::Currency::transfer(
-					&bonding_account,
-					&beneficiary,
-					amount,
-					ExistenceRequirement::AllowDeath,
-				)?;
-				<ExecutedUnshieldCalls<T>>::insert(call_hash, 0);
-				Self::deposit_event(Event::UnshieldedFunds { shard, beneficiary, amount });
-			} else {
-				log::info!(
-					target: ENCLAVE_BRIDGE,
-					"Already executed unshielding call: {:?}",
-					call_hash
-				);
+			if !threshold_reached {
+				<PendingUnshieldCalls<T>>::insert(call_hash, pending);
+				return Ok(().into())
 			}
 
-			<ExecutedUnshieldCalls<T>>::mutate(call_hash, |confirmations| *confirmations += 1);
+			log::info!(target: ENCLAVE_BRIDGE, "Executing unshielding call: {:?}", call_hash);
+			let bonding_account = Self::shard_vault(shard);
+			T::Currency::transfer(
+				&bonding_account,
+				&beneficiary,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			<PendingUnshieldCalls<T>>::remove(call_hash);
+			Self::note_executed_unshield(call_hash, &pending, current_block_number);
+			Self::deposit_event(Event::UnshieldedFunds { shard, beneficiary, amount });
 			Ok(().into())
 		}
 
@@ -321,6 +658,13 @@ pub mod pallet {
 			enactment_delay: BlockNumberFor<T>,
 		) -> DispatchResultWithPostInfo {
 			let maybe_sender = ensure_signed_or_root(origin)?;
+			if let Some(threshold) = shard_config.unshield_threshold {
+				ensure!(
+					threshold <= MAX_SHARD_STATUS_SIGNER_COUNT &&
+						shard_config.max_instances.map_or(true, |max| threshold <= max),
+					Error::<T>::InvalidUnshieldThreshold
+				);
+			}
 
 			let current_block_number = <frame_system::Pallet<T>>::block_number();
 			let new_upgradable_shard_config: UpgradableShardConfig<
@@ -373,7 +717,8 @@ pub mod pallet {
 			Ok(().into())
 		}
 		/// Purge enclave from shard status
-		/// this is a root call to be used for maintenance. Shall eventually be replaced by a lazy timeout
+		/// this is a root call to be used for maintenance. Inactive enclaves are purged lazily
+		/// after `ShardSignerInactivityTimeout`, see `purge_inactive_enclaves_from_shard_status`
 		#[pallet::call_index(6)]
 		#[pallet::weight((<T as Config>::WeightInfo::purge_enclave_from_shard_status(), DispatchClass::Normal, Pays::No))]
 		pub fn purge_enclave_from_shard_status(
@@ -393,6 +738,7 @@ pub mod pallet {
 				.expect("can only become smaller by filtering");
 
 			<crate::pallet::ShardStatus<T>>::insert(shard, new_status);
+			<LastProcessedParentchainBlockNumber<T>>::remove(shard, &subject);
 
 			log::info!(
 				target: ENCLAVE_BRIDGE,
@@ -406,6 +752,214 @@ pub mod pallet {
 			});
 			Ok(().into())
 		}
+
+		/// Sent by a client who requests to get assets shielded to L2. Like `shield_funds`, but
+		/// for any asset of `Fungibles` with a known location. All assets of a shard are kept in
+		/// the same bonding account.
+		#[pallet::call_index(7)]
+		#[pallet::weight((<T as Config>::WeightInfo::shield_asset(), DispatchClass::Normal, Pays::Yes))]
+		pub fn shield_asset(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+			incognito_account_encrypted: Vec<u8>,
+			amount: AssetBalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				Self::is_allowed_during_maintenance(
+					shard,
+					ShardOperation::Shield,
+					<frame_system::Pallet<T>>::block_number()
+				),
+				Error::<T>::ShieldingDuringMaintenance
+			);
+			let location = T::AssetLocationGetter::get_asset_location(asset_id.clone())
+				.ok_or(Error::<T>::UnknownAssetLocation)?;
+			let bonding_account = Self::shard_vault(shard);
+			<T::Fungibles as fungibles::Mutate<_>>::transfer(
+				asset_id.clone(),
+				&sender,
+				&bonding_account,
+				amount,
+				Preservation::Expendable,
+			)?;
+			Self::deposit_event(Event::ShieldedAsset {
+				shard,
+				asset_id,
+				location,
+				encrypted_beneficiary: incognito_account_encrypted,
+				amount,
+			});
+			Ok(().into())
+		}
+
+		/// Sent by enclaves only as a result of an `unshield` request for an asset from a client
+		/// to an enclave. The same confirmation threshold and expiry as for `unshield_funds` apply.
+		#[pallet::call_index(8)]
+		#[pallet::weight((<T as Config>::WeightInfo::unshield_asset(), DispatchClass::Normal, Pays::Yes))]
+		pub fn unshield_asset(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+			beneficiary: T::AccountId,
+			amount: AssetBalanceOf<T>,
+			call_hash: H256,
+			issued_at: BlockNumberFor<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				Self::is_allowed_during_maintenance(
+					shard,
+					ShardOperation::Unshield,
+					current_block_number
+				),
+				Error::<T>::UnshieldingDuringMaintenance
+			);
+			Self::ensure_unexpired_unshield_call(issued_at, current_block_number)?;
+			let (_, shard_status) =
+				Self::get_sovereign_enclave_and_touch_shard(&sender, shard, current_block_number)?;
+			if Self::note_executed_unshield_confirmation(call_hash) {
+				return Ok(().into())
+			}
+
+			let maybe_pending = match Self::pending_asset_unshield(call_hash) {
+				Some((pending_asset_id, pending)) => {
+					ensure!(pending_asset_id == asset_id, Error::<T>::UnshieldParametersMismatch);
+					Some(pending)
+				},
+				None => None,
+			};
+			let (pending, threshold_reached) = Self::confirm_unshield(
+				maybe_pending,
+				sender,
+				shard,
+				&beneficiary,
+				amount,
+				call_hash,
+				issued_at,
+				&shard_status,
+				current_block_number,
+			)?;
+			if !threshold_reached {
+				<PendingAssetUnshieldCalls<T>>::insert(call_hash, (asset_id, pending));
+				return Ok(().into())
+			}
+
+			log::info!(target: ENCLAVE_BRIDGE, "Executing asset unshielding call: {:?}", call_hash);
+			let bonding_account = Self::shard_vault(shard);
+			<T::Fungibles as fungibles::Mutate<_>>::transfer(
+				asset_id.clone(),
+				&bonding_account,
+				&beneficiary,
+				amount,
+				Preservation::Expendable,
+			)?;
+			<PendingAssetUnshieldCalls<T>>::remove(call_hash);
+			Self::note_executed_unshield(call_hash, &pending, current_block_number);
+			Self::deposit_event(Event::UnshieldedAsset {
+				shard,
+				location: T::AssetLocationGetter::get_asset_location(asset_id.clone()),
+				asset_id,
+				beneficiary,
+				amount,
+			});
+			Ok(().into())
+		}
+
+		/// Purge all enclaves from a shard status which haven't been active for more than
+		/// `ShardSignerInactivityTimeout` blocks. Anyone may call this.
+		#[pallet::call_index(9)]
+		#[pallet::weight((<T as Config>::WeightInfo::purge_inactive_enclaves_from_shard_status(), DispatchClass::Normal, Pays::Yes))]
+		pub fn purge_inactive_enclaves_from_shard_status(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let signer_statuses = Self::shard_status(shard).ok_or(Error::<T>::ShardNotFound)?;
+			let signer_count = signer_statuses.len();
+			let new_status: ShardSignerStatusVec<T> = Self::evict_inactive_signers(
+				shard,
+				signer_statuses.to_vec(),
+				<frame_system::Pallet<T>>::block_number(),
+			)
+			.try_into()
+			.expect("can only become smaller by filtering");
+			ensure!(new_status.len() < signer_count, Error::<T>::NoInactiveEnclaveInShardStatus);
+
+			<ShardStatus<T>>::insert(shard, new_status);
+			Ok(().into())
+		}
+
+		/// Sent by enclaves to acknowledge that they have processed all requests of the inbox up
+		/// to and including `request_id`. Acknowledged requests are removed from the inbox.
+		#[pallet::call_index(10)]
+		#[pallet::weight((<T as Config>::WeightInfo::acknowledge_processed_requests(T::MaxInboxLength::get()), DispatchClass::Normal, Pays::Yes))]
+		pub fn acknowledge_processed_requests(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			request_id: u64,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::get_sovereign_enclave_and_touch_shard(
+				&sender,
+				shard,
+				<frame_system::Pallet<T>>::block_number(),
+			)?;
+
+			let mut status = Self::request_inbox_status(shard);
+			ensure!(request_id < status.next_request_id, Error::<T>::UnknownRequestId);
+			let mut pruned = 0u32;
+			while status.next_unacknowledged_id <= request_id {
+				<RequestInbox<T>>::remove(shard, status.next_unacknowledged_id);
+				status.next_unacknowledged_id += 1;
+				pruned += 1;
+			}
+			<RequestInboxStatuses<T>>::insert(shard, status);
+
+			log::debug!(
+				target: ENCLAVE_BRIDGE,
+				"{:?} acknowledged requests up to {} for shard {:?}",
+				sender,
+				request_id,
+				shard
+			);
+			Self::deposit_event(Event::ProcessedRequestsAcknowledged { shard, request_id });
+			Ok(Some(<T as Config>::WeightInfo::acknowledge_processed_requests(pruned)).into())
+		}
+
+		/// Moves the funds in the legacy vault of a shard to its `shard_vault`. The v3 migration
+		/// only moved the funds of shards with a config or status, hence anyone may move the
+		/// funds of any other shard, e.g. one which has been purged or never been configured.
+		#[pallet::call_index(11)]
+		#[pallet::weight((<T as Config>::WeightInfo::sweep_legacy_shard_vault(), DispatchClass::Normal, Pays::Yes))]
+		pub fn sweep_legacy_shard_vault(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let amount = Self::move_legacy_shard_vault_funds(shard)?;
+			ensure!(!amount.is_zero(), Error::<T>::LegacyShardVaultEmpty);
+			Self::deposit_event(Event::SweptLegacyShardVault { shard, amount });
+			Ok(().into())
+		}
+
+		/// Like `sweep_legacy_shard_vault`, but for an asset of `Fungibles`.
+		#[pallet::call_index(12)]
+		#[pallet::weight((<T as Config>::WeightInfo::sweep_legacy_shard_vault_asset(), DispatchClass::Normal, Pays::Yes))]
+		pub fn sweep_legacy_shard_vault_asset(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			asset_id: AssetIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let amount = Self::move_legacy_shard_vault_asset(shard, asset_id.clone())?;
+			ensure!(!amount.is_zero(), Error::<T>::LegacyShardVaultEmpty);
+			Self::deposit_event(Event::SweptLegacyShardVaultAsset { shard, asset_id, amount });
+			Ok(().into())
+		}
 	}
 }
 
@@ -452,7 +1006,139 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// The account holding the funds and assets shielded to `shard`. Sub accounts keep only 20
+	/// bytes of their seed, hence it is derived from the hash of the shard rather than the shard.
+	pub fn shard_vault(shard: ShardIdentifier) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating(blake2_256(&shard.encode()))
+	}
+
+	/// The account which held the funds of `shard` before vaults were derived from `PalletId`. It
+	/// is bit-equivalent to the shard, hence it only exists for 32-byte account ids.
+	pub fn legacy_shard_vault(shard: ShardIdentifier) -> Option<T::AccountId> {
+		T::AccountId::decode_all(&mut shard.as_bytes()).ok()
+	}
+
+	/// Moves the free native balance of the legacy vault of `shard` to its vault and returns the
+	/// moved amount.
+	pub fn move_legacy_shard_vault_funds(
+		shard: ShardIdentifier,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let legacy_vault = Self::legacy_shard_vault(shard).ok_or(Error::<T>::NoLegacyShardVault)?;
+		let free = T::Currency::free_balance(&legacy_vault);
+		if !free.is_zero() {
+			T::Currency::transfer(
+				&legacy_vault,
+				&Self::shard_vault(shard),
+				free,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		}
+		Ok(free)
+	}
+
+	/// Moves the reducible balance of `asset_id` in the legacy vault of `shard` to its vault and
+	/// returns the moved amount.
+	pub fn move_legacy_shard_vault_asset(
+		shard: ShardIdentifier,
+		asset_id: AssetIdOf<T>,
+	) -> Result<AssetBalanceOf<T>, DispatchError> {
+		let legacy_vault = Self::legacy_shard_vault(shard).ok_or(Error::<T>::NoLegacyShardVault)?;
+		let balance = <T::Fungibles as fungibles::Inspect<_>>::reducible_balance(
+			asset_id.clone(),
+			&legacy_vault,
+			Preservation::Expendable,
+			Fortitude::Polite,
+		);
+		if !balance.is_zero() {
+			<T::Fungibles as fungibles::Mutate<_>>::transfer(
+				asset_id,
+				&legacy_vault,
+				&Self::shard_vault(shard),
+				balance,
+				Preservation::Expendable,
+			)?;
+		}
+		Ok(balance)
+	}
+
+	/// Stores `cyphertext` in the request inbox of `shard` and returns its request id. Returns
+	/// `None` if the inbox is full, so that a flood of requests can't block invocations.
+	fn queue_request(shard: ShardIdentifier, cyphertext: Vec<u8>) -> Option<u64> {
+		let mut status = Self::request_inbox_status(shard);
+		if status.len() >= T::MaxInboxLength::get() as u64 {
+			log::warn!(
+				target: ENCLAVE_BRIDGE,
+				"request inbox of shard {:?} is full, registering request by event only",
+				shard
+			);
+			return None
+		}
+		let request_id = status.next_request_id;
+		<RequestInbox<T>>::insert(shard, request_id, cyphertext);
+		status.next_request_id += 1;
+		<RequestInboxStatuses<T>>::insert(shard, status);
+		Self::deposit_event(Event::IndirectInvocationQueued { shard, request_id });
+		Some(request_id)
+	}
+
+	/// Ensures that a confirmed parentchain block is older than the current block, is not older
+	/// than the last one `enclave_signer` has confirmed for `shard` and has the stated hash. The
+	/// hashes of blocks older than `BlockHashCount` are pruned and can't be checked anymore.
+	fn ensure_valid_parentchain_block(
+		shard: ShardIdentifier,
+		enclave_signer: &T::AccountId,
+		block_number: BlockNumberFor<T>,
+		block_hash: H256,
+		current_block_number: BlockNumberFor<T>,
+	) -> DispatchResult {
+		ensure!(block_number < current_block_number, Error::<T>::UnknownParentchainBlock);
+		if let Some(last) = Self::last_processed_parentchain_block_of(shard, enclave_signer) {
+			ensure!(block_number >= last, Error::<T>::ParentchainBlockNumberRegression);
+		}
+		let known_hash = <frame_system::Pallet<T>>::block_hash(block_number);
+		if known_hash != T::Hash::default() {
+			ensure!(
+				known_hash.as_ref() == block_hash.as_bytes(),
+				Error::<T>::ParentchainBlockHashMismatch
+			);
+		}
+		Ok(())
+	}
+
+	/// Whether the shard config of `shard`, including a due update, is in maintenance mode.
+	pub fn is_in_maintenance_mode(
+		shard: ShardIdentifier,
+		current_block_number: BlockNumberFor<T>,
+	) -> bool {
+		Self::get_maybe_updated_shard_config(shard, current_block_number, false)
+			.is_some_and(|config| config.maintenance_mode)
+	}
+
+	/// Whether `operation` may be performed on `shard`. This is the whitelist of the operations
+	/// which remain allowed while the shard is in maintenance mode. Updating the shard config and
+	/// purging the shard status are not restricted at all.
+	pub fn is_allowed_during_maintenance(
+		shard: ShardIdentifier,
+		operation: ShardOperation,
+		current_block_number: BlockNumberFor<T>,
+	) -> bool {
+		if !Self::is_in_maintenance_mode(shard, current_block_number) {
+			return true
+		}
+		match operation {
+			// L2 keeps following the parentchain and working off its inbox, so that it resumes
+			// from an up-to-date state after maintenance
+			ShardOperation::ConfirmParentchainBlock | ShardOperation::AcknowledgeRequests => true,
+			ShardOperation::Invoke |
+			ShardOperation::Shield |
+			ShardOperation::Unshield |
+			ShardOperation::ConfirmSidechainBlock => false,
+		}
+	}
+
 	/// will update the `last_activity` field for `enclave_signer` in a shard's status
+	///
+	/// Respects the `authorities` and `max_instances` of the shard config, if any.
 	pub fn touch_shard(
 		shard: ShardIdentifier,
 		enclave_signer: &T::AccountId,
@@ -465,17 +1151,30 @@ impl<T: Config> Pallet<T> {
 			last_activity: current_block_number,
 		};
 
-		let signer_statuses: Vec<ShardSignerStatus<T>> = Self::shard_status(shard)
-			.map(|status_bvec| {
-				let mut status_vec = status_bvec.to_vec();
-				if let Some(index) = status_vec.iter().position(|i| &i.signer == enclave_signer) {
-					status_vec[index] = new_status.clone();
-				} else {
-					status_vec.push(new_status.clone());
-				}
-				status_vec
-			})
-			.unwrap_or_else(|| vec![new_status]);
+		let shard_config = Self::get_maybe_updated_shard_config(shard, current_block_number, false);
+		if let Some(authorities) = shard_config.as_ref().and_then(|c| c.authorities.as_ref()) {
+			ensure!(authorities.contains(enclave_signer), Error::<T>::EnclaveNotAuthorizedForShard);
+		}
+
+		let mut status_vec = Self::shard_status(shard).map(|b| b.to_vec()).unwrap_or_default();
+		let is_new_signer = match status_vec.iter().position(|i| &i.signer == enclave_signer) {
+			Some(index) => {
+				status_vec[index] = new_status;
+				false
+			},
+			None => {
+				status_vec.push(new_status);
+				true
+			},
+		};
+		let signer_statuses = Self::evict_inactive_signers(shard, status_vec, current_block_number);
+		if let Some(max_instances) = shard_config.and_then(|c| c.max_instances) {
+			// signers which joined before the limit was lowered may stay
+			ensure!(
+				!is_new_signer || signer_statuses.len() as u32 <= max_instances,
+				Error::<T>::TooManyInstancesForShard
+			);
+		}
 
 		let signer_statuses = ShardSignerStatusVec::<T>::try_from(signer_statuses)
 			.map_err(|_| Error::<T>::TooManyEnclaves)?;
@@ -489,6 +1188,280 @@ impl<T: Config> Pallet<T> {
 		Ok(signer_statuses)
 	}
 
+	/// Removes the signers whose last activity on `shard` is more than
+	/// `ShardSignerInactivityTimeout` blocks ago from `signer_statuses`, along with the last
+	/// parentchain block they have confirmed.
+	fn evict_inactive_signers(
+		shard: ShardIdentifier,
+		signer_statuses: Vec<ShardSignerStatus<T>>,
+		current_block_number: BlockNumberFor<T>,
+	) -> Vec<ShardSignerStatus<T>> {
+		let timeout = T::ShardSignerInactivityTimeout::get();
+		let (active, inactive): (Vec<_>, Vec<_>) =
+			signer_statuses.into_iter().partition(|status| {
+				status.last_activity.saturating_add(timeout) >= current_block_number
+			});
+		for status in inactive {
+			<LastProcessedParentchainBlockNumber<T>>::remove(shard, &status.signer);
+			log::info!(
+				target: ENCLAVE_BRIDGE,
+				"evicted inactive {:?} from shard status for {:?}",
+				status.signer,
+				shard,
+			);
+			Self::deposit_event(Event::PurgedEnclaveFromShardConfig {
+				shard,
+				subject: status.signer,
+			});
+		}
+		active
+	}
+
+	/// The number of distinct enclaves which must confirm an unshielding on `shard`. Unless the
+	/// shard config states it, a majority of the signers in the shard status is required.
+	pub fn unshield_threshold(
+		shard: ShardIdentifier,
+		shard_status: &ShardSignerStatusVec<T>,
+		current_block_number: BlockNumberFor<T>,
+	) -> u32 {
+		Self::get_maybe_updated_shard_config(shard, current_block_number, false)
+			.and_then(|config| config.unshield_threshold)
+			.unwrap_or(shard_status.len() as u32 / 2 + 1)
+			.max(1)
+	}
+
+	/// Ensures that an unshield call issued by L2 at parentchain block `issued_at` is neither
+	/// from the future nor older than `UnshieldRetentionPeriod`. Any confirmation of an executed
+	/// call has been issued before its execution, hence its replay is rejected here once the
+	/// record of its execution has been pruned.
+	fn ensure_unexpired_unshield_call(
+		issued_at: BlockNumberFor<T>,
+		current_block_number: BlockNumberFor<T>,
+	) -> DispatchResult {
+		ensure!(issued_at < current_block_number, Error::<T>::UnknownParentchainBlock);
+		ensure!(
+			issued_at.saturating_add(T::UnshieldRetentionPeriod::get()) >= current_block_number,
+			Error::<T>::UnshieldCallExpired
+		);
+		Ok(())
+	}
+
+	/// Counts another confirmation of an unshield call which has already been executed. Returns
+	/// whether `call_hash` has been executed.
+	fn note_executed_unshield_confirmation(call_hash: H256) -> bool {
+		<ExecutedUnshieldCalls<T>>::mutate(call_hash, |executed| match executed {
+			Some(executed) => {
+				log::info!(
+					target: ENCLAVE_BRIDGE,
+					"Already executed unshielding call: {:?}",
+					call_hash
+				);
+				executed.confirmations += 1;
+				true
+			},
+			None => false,
+		})
+	}
+
+	fn note_executed_unshield<Balance>(
+		call_hash: H256,
+		pending: &PendingUnshieldGeneric<T::AccountId, Balance, BlockNumberFor<T>>,
+		current_block_number: BlockNumberFor<T>,
+	) {
+		<ExecutedUnshieldCalls<T>>::insert(
+			call_hash,
+			ExecutedUnshield {
+				executed_at: current_block_number,
+				confirmations: pending.confirmed_by.len() as u64,
+			},
+		);
+	}
+
+	/// Adds the confirmation of `enclave_signer` to the unshielding `call_hash`, starting a new
+	/// one if there is no unexpired `maybe_pending`. Confirmations of signers which have left the
+	/// shard status since are dropped. Returns the updated unshielding and whether it has reached
+	/// the threshold to be executed.
+	#[allow(clippy::too_many_arguments)]
+	#[allow(clippy::type_complexity)]
+	fn confirm_unshield<Balance: PartialEq>(
+		maybe_pending: Option<PendingUnshieldGeneric<T::AccountId, Balance, BlockNumberFor<T>>>,
+		enclave_signer: T::AccountId,
+		shard: ShardIdentifier,
+		beneficiary: &T::AccountId,
+		amount: Balance,
+		call_hash: H256,
+		issued_at: BlockNumberFor<T>,
+		shard_status: &ShardSignerStatusVec<T>,
+		current_block_number: BlockNumberFor<T>,
+	) -> Result<
+		(PendingUnshieldGeneric<T::AccountId, Balance, BlockNumberFor<T>>, bool),
+		DispatchErrorWithPostInfo,
+	> {
+		let mut pending = match maybe_pending.filter(|p| p.expires_at >= current_block_number) {
+			Some(pending) => {
+				ensure!(
+					pending.shard == shard &&
+						&pending.beneficiary == beneficiary &&
+						pending.amount == amount && pending.issued_at == issued_at,
+					Error::<T>::UnshieldParametersMismatch
+				);
+				ensure!(
+					!pending.confirmed_by.contains(&enclave_signer),
+					Error::<T>::UnshieldAlreadyConfirmed
+				);
+				pending
+			},
+			None => PendingUnshieldGeneric {
+				shard,
+				beneficiary: beneficiary.clone(),
+				amount,
+				issued_at,
+				confirmed_by: Default::default(),
+				expires_at: current_block_number
+					.saturating_add(T::UnshieldConfirmationTimeout::get()),
+			},
+		};
+		pending
+			.confirmed_by
+			.retain(|confirmer| shard_status.iter().any(|status| &status.signer == confirmer));
+		pending
+			.confirmed_by
+			.try_push(enclave_signer)
+			.map_err(|_| Error::<T>::TooManyEnclaves)?;
+		let confirmations = pending.confirmed_by.len() as u32;
+		let threshold = Self::unshield_threshold(shard, shard_status, current_block_number);
+		if confirmations < threshold {
+			log::info!(
+				target: ENCLAVE_BRIDGE,
+				"Unshielding call {:?} confirmed {} of {} times",
+				call_hash,
+				confirmations,
+				threshold
+			);
+			Self::deposit_event(Event::UnshieldConfirmed {
+				shard,
+				call_hash,
+				confirmations,
+				threshold,
+			});
+			return Ok((pending, false))
+		}
+		Ok((pending, true))
+	}
+
+	/// Removes the records of executed unshield calls older than `UnshieldRetentionPeriod` and
+	/// expired pending unshield calls. Each sweep resumes where the
+	/// previous one stopped and visits as many entries as `max_weight` allows.
+	pub fn prune_unshield_calls(
+		current_block_number: BlockNumberFor<T>,
+		max_weight: Weight,
+	) -> Weight {
+		let db_weight = T::DbWeight::get();
+		// the cursor is read and written once per sweep, every visited entry may be removed
+		let mut used_weight = db_weight.reads_writes(1, 1);
+		let entry_weight = db_weight.reads_writes(1, 1);
+		if used_weight.saturating_add(entry_weight).any_gt(max_weight) {
+			return Weight::zero()
+		}
+
+		// a sweep visits the executed, pending and pending asset unshield calls in this order
+		let prefixes = [
+			<ExecutedUnshieldCalls<T>>::final_prefix(),
+			<PendingUnshieldCalls<T>>::final_prefix(),
+			<PendingAssetUnshieldCalls<T>>::final_prefix(),
+		];
+		let resumed = <UnshieldPruneCursor<T>>::take().and_then(|cursor| {
+			prefixes
+				.iter()
+				.position(|prefix| cursor.starts_with(prefix))
+				.map(|stage| (stage, cursor))
+		});
+		let stage = resumed.as_ref().map_or(0, |(stage, _)| *stage);
+		// iterating from the bare prefix of a map visits all of its entries
+		let start_key = |i: usize| match &resumed {
+			Some((stage, cursor)) if *stage == i => cursor.clone(),
+			_ => prefixes[i].to_vec(),
+		};
+
+		let retention_period = T::UnshieldRetentionPeriod::get();
+		let mut stopped_at = None;
+		if stage == 0 {
+			stopped_at = Self::prune_entries(
+				<ExecutedUnshieldCalls<T>>::iter_from(start_key(0)),
+				entry_weight,
+				max_weight,
+				&mut used_weight,
+				|call_hash, executed| {
+					let prunable = executed.executed_at.saturating_add(retention_period) <
+						current_block_number;
+					if prunable {
+						<ExecutedUnshieldCalls<T>>::remove(call_hash);
+					}
+					prunable
+				},
+			);
+		}
+		if stage <= 1 && stopped_at.is_none() {
+			stopped_at = Self::prune_entries(
+				<PendingUnshieldCalls<T>>::iter_from(start_key(1)),
+				entry_weight,
+				max_weight,
+				&mut used_weight,
+				|call_hash, pending| {
+					let expired = pending.expires_at < current_block_number;
+					if expired {
+						<PendingUnshieldCalls<T>>::remove(call_hash);
+					}
+					expired
+				},
+			);
+		}
+		if stopped_at.is_none() {
+			stopped_at = Self::prune_entries(
+				<PendingAssetUnshieldCalls<T>>::iter_from(start_key(2)),
+				entry_weight,
+				max_weight,
+				&mut used_weight,
+				|call_hash, (_, pending)| {
+					let expired = pending.expires_at < current_block_number;
+					if expired {
+						<PendingAssetUnshieldCalls<T>>::remove(call_hash);
+					}
+					expired
+				},
+			);
+		}
+		if let Some(cursor) = stopped_at {
+			<UnshieldPruneCursor<T>>::put(cursor);
+		}
+		used_weight
+	}
+
+	/// Visits the entries of `iter` as long as `max_weight` allows and passes them to `prune`,
+	/// which returns whether it has pruned the entry. Returns the raw key to resume from if the
+	/// weight has been exhausted before the end of the map.
+	fn prune_entries<V>(
+		mut iter: PrefixIterator<(H256, V)>,
+		entry_weight: Weight,
+		max_weight: Weight,
+		used_weight: &mut Weight,
+		mut prune: impl FnMut(H256, V) -> bool,
+	) -> Option<Vec<u8>> {
+		let mut pruned = 0u32;
+		while used_weight.saturating_add(entry_weight).all_lte(max_weight) {
+			let Some((call_hash, value)) = iter.next() else {
+				log::debug!(target: ENCLAVE_BRIDGE, "pruned {} unshield calls", pruned);
+				return None
+			};
+			*used_weight = used_weight.saturating_add(entry_weight);
+			if prune(call_hash, value) {
+				pruned += 1;
+			}
+		}
+		log::debug!(target: ENCLAVE_BRIDGE, "pruned {} unshield calls", pruned);
+		Some(iter.last_raw_key().to_vec())
+	}
+
 	pub fn most_recent_shard_update(shard: &ShardIdentifier) -> Option<ShardSignerStatus<T>> {
 		<ShardStatus<T>>::get(shard)
 			.map(|mut statuses| {
@@ -509,8 +1482,9 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
-#[cfg(any(test, feature = "runtime-benchmarks"))]
+#[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
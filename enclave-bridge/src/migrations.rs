@@ -0,0 +1,444 @@
+use super::*;
+
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+
+pub mod v0 {
+	use super::*;
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+	pub struct ShardConfigV0<AccountId> {
+		pub enclave_fingerprint: EnclaveFingerprint,
+		pub max_instances: Option<u32>,
+		pub authorities: Option<Vec<AccountId>>,
+		pub maintenance_mode: bool,
+	}
+
+	impl<AccountId> From<ShardConfigV0<AccountId>> for ShardConfig<AccountId> {
+		fn from(old: ShardConfigV0<AccountId>) -> Self {
+			ShardConfig {
+				enclave_fingerprint: old.enclave_fingerprint,
+				max_instances: old.max_instances,
+				authorities: old.authorities,
+				maintenance_mode: old.maintenance_mode,
+				unshield_threshold: None,
+			}
+		}
+	}
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+	pub struct UpgradableShardConfigV0<AccountId, BlockNumber> {
+		pub active_config: ShardConfigV0<AccountId>,
+		pub pending_upgrade: Option<ShardConfigV0<AccountId>>,
+		pub upgrade_at: Option<BlockNumber>,
+	}
+}
+
+pub mod v1 {
+	use super::*;
+	/// The log target.
+	const TARGET: &str = "enclave_bridge::migration::v1";
+
+	/// Shard configs state the unshield threshold since v1. Existing configs derive it from the
+	/// shard status.
+	pub struct MigrateV0toV1<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config + frame_system::Config> OnRuntimeUpgrade for MigrateV0toV1<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+			ensure!(onchain_version == 0, "only migration from v0 to v1");
+
+			let shard_count = ShardConfigRegistry::<T>::iter_keys().count() as u64;
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV1: {} shard configs will be migrated",
+				shard_count
+			);
+			Ok(shard_count.encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let current_version = StorageVersion::new(1);
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV1: Running migration with current storage version {:?} / onchain {:?}",
+				current_version,
+				onchain_version
+			);
+
+			if onchain_version >= current_version {
+				log::warn!(
+					target: TARGET,
+					"enclaveBridgeV1: skipping on_runtime_upgrade: executed on wrong storage version."
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			ShardConfigRegistry::<T>::translate::<
+				v0::UpgradableShardConfigV0<T::AccountId, BlockNumberFor<T>>,
+				_,
+			>(|_, old| {
+				translated += 1;
+				Some(UpgradableShardConfig {
+					active_config: old.active_config.into(),
+					pending_upgrade: old.pending_upgrade.map(Into::into),
+					upgrade_at: old.upgrade_at,
+				})
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 1, "must upgrade");
+
+			let shard_count: u64 =
+				Decode::decode(&mut &state[..]).expect("pre_upgrade provides a valid state; qed");
+			assert_eq!(
+				ShardConfigRegistry::<T>::iter_values().count() as u64,
+				shard_count,
+				"must keep all shard configs"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+	/// The log target.
+	const TARGET: &str = "enclave_bridge::migration::v2";
+
+	/// Executed unshield calls record the block they were executed in since v2, so that they
+	/// can be pruned. Existing entries are treated as if they had been executed now.
+	pub struct MigrateV1toV2<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config + frame_system::Config> OnRuntimeUpgrade for MigrateV1toV2<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+			ensure!(onchain_version == 1, "only migration from v1 to v2");
+
+			let call_count = ExecutedUnshieldCalls::<T>::iter_keys().count() as u64;
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV2: {} executed unshield calls will be migrated",
+				call_count
+			);
+			Ok(call_count.encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let current_version = StorageVersion::new(2);
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV2: Running migration with current storage version {:?} / onchain {:?}",
+				current_version,
+				onchain_version
+			);
+
+			if onchain_version >= current_version {
+				log::warn!(
+					target: TARGET,
+					"enclaveBridgeV2: skipping on_runtime_upgrade: executed on wrong storage version."
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut translated = 0u64;
+			ExecutedUnshieldCalls::<T>::translate::<u64, _>(|_, confirmations| {
+				translated += 1;
+				Some(ExecutedUnshield { executed_at: now, confirmations })
+			});
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(translated + 2, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 2, "must upgrade");
+
+			let call_count: u64 =
+				Decode::decode(&mut &state[..]).expect("pre_upgrade provides a valid state; qed");
+			assert_eq!(
+				ExecutedUnshieldCalls::<T>::iter_values().count() as u64,
+				call_count,
+				"must keep all executed unshield calls"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+	use sp_runtime::traits::Zero;
+	/// The log target.
+	const TARGET: &str = "enclave_bridge::migration::v3";
+
+	/// Shard vaults are derived from `PalletId` and the hash of the shard since v3. This moves the
+	/// native funds and the `LegacyAssets` of every shard with a config or status from its legacy
+	/// vault to its new one. The legacy vaults of other shards can be swept by anyone with
+	/// `sweep_legacy_shard_vault` and `sweep_legacy_shard_vault_asset`.
+	pub struct MigrateV2toV3<T, LegacyAssets>(sp_std::marker::PhantomData<(T, LegacyAssets)>);
+
+	impl<T, LegacyAssets> MigrateV2toV3<T, LegacyAssets>
+	where
+		T: Config + frame_system::Config,
+		LegacyAssets: Get<Vec<AssetIdOf<T>>>,
+	{
+		fn shards() -> Vec<ShardIdentifier> {
+			let mut shards: Vec<ShardIdentifier> = ShardStatus::<T>::iter_keys()
+				.chain(ShardConfigRegistry::<T>::iter_keys())
+				.collect();
+			shards.sort();
+			shards.dedup();
+			shards
+		}
+	}
+
+	impl<T, LegacyAssets> OnRuntimeUpgrade for MigrateV2toV3<T, LegacyAssets>
+	where
+		T: Config + frame_system::Config,
+		LegacyAssets: Get<Vec<AssetIdOf<T>>>,
+	{
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+			ensure!(onchain_version == 2, "only migration from v2 to v3");
+
+			let shards = Self::shards();
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV3: funds of {} shards will be moved to their new vaults",
+				shards.len()
+			);
+			let balances: Vec<BalanceOf<T>> = shards
+				.iter()
+				.map(|shard| {
+					Pallet::<T>::legacy_shard_vault(*shard)
+						.map(|legacy| T::Currency::total_balance(&legacy))
+						.unwrap_or_default()
+						.saturating_add(T::Currency::total_balance(&Pallet::<T>::shard_vault(
+							*shard,
+						)))
+				})
+				.collect();
+			Ok((shards, balances).encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let current_version = StorageVersion::new(3);
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+
+			log::info!(
+				target: TARGET,
+				"enclaveBridgeV3: Running migration with current storage version {:?} / onchain {:?}",
+				current_version,
+				onchain_version
+			);
+
+			if onchain_version >= current_version {
+				log::warn!(
+					target: TARGET,
+					"enclaveBridgeV3: skipping on_runtime_upgrade: executed on wrong storage version."
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let shards = Self::shards();
+			let assets = LegacyAssets::get();
+			let mut reads = shards.len() as u64 + 1;
+			let mut writes = 1u64;
+			for shard in shards.iter() {
+				if Pallet::<T>::legacy_shard_vault(*shard).is_none() {
+					continue
+				}
+				reads += 1;
+				match Pallet::<T>::move_legacy_shard_vault_funds(*shard) {
+					Ok(free) if free.is_zero() => (),
+					Ok(_) => writes += 2,
+					Err(e) => log::error!(
+						target: TARGET,
+						"enclaveBridgeV3: failed to move funds of shard {:?}: {:?}",
+						shard,
+						e
+					),
+				}
+
+				for asset_id in assets.iter() {
+					reads += 1;
+					match Pallet::<T>::move_legacy_shard_vault_asset(*shard, asset_id.clone()) {
+						Ok(balance) if balance.is_zero() => (),
+						Ok(_) => writes += 2,
+						Err(e) => log::error!(
+							target: TARGET,
+							"enclaveBridgeV3: failed to move asset {:?} of shard {:?}: {:?}",
+							asset_id,
+							shard,
+							e
+						),
+					}
+				}
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 3, "must upgrade");
+
+			let (shards, balances): (Vec<ShardIdentifier>, Vec<BalanceOf<T>>) =
+				Decode::decode(&mut &state[..]).expect("pre_upgrade provides a valid state; qed");
+			for (shard, balance) in shards.iter().zip(balances) {
+				let legacy_balance = Pallet::<T>::legacy_shard_vault(*shard)
+					.map(|legacy| T::Currency::total_balance(&legacy))
+					.unwrap_or_default();
+				assert_eq!(
+					T::Currency::total_balance(&Pallet::<T>::shard_vault(*shard))
+						.saturating_add(legacy_balance),
+					balance,
+					"must not lose funds of shard {:?}",
+					shard
+				);
+			}
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "try-runtime")]
+mod test {
+	use super::*;
+	use frame_support::{assert_ok, parameter_types, storage::unhashed};
+	use mock::{new_test_ext, Assets, Balances, RuntimeOrigin, Test as TestRuntime, USDT_ASSET_ID};
+	use sp_keyring::AccountKeyring;
+
+	parameter_types! {
+		pub LegacyAssets: Vec<u32> = vec![USDT_ASSET_ID];
+	}
+
+	#[allow(deprecated)]
+	#[test]
+	fn migration_v0_to_v1_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(0).put::<Pallet<TestRuntime>>();
+
+			let shard = ShardIdentifier::from([1u8; 32]);
+			let config = v0::ShardConfigV0::<AccountId<TestRuntime>> {
+				enclave_fingerprint: EnclaveFingerprint::from([2u8; 32]),
+				max_instances: Some(3),
+				authorities: None,
+				maintenance_mode: false,
+			};
+			unhashed::put(
+				&ShardConfigRegistry::<TestRuntime>::hashed_key_for(shard),
+				&v0::UpgradableShardConfigV0::<AccountId<TestRuntime>, u32> {
+					active_config: config.clone(),
+					pending_upgrade: Some(config.clone()),
+					upgrade_at: Some(5),
+				},
+			);
+
+			let state = v1::MigrateV0toV1::<TestRuntime>::pre_upgrade().unwrap();
+			let _weight = v1::MigrateV0toV1::<TestRuntime>::on_runtime_upgrade();
+			v1::MigrateV0toV1::<TestRuntime>::post_upgrade(state).unwrap();
+
+			let migrated = ShardConfigRegistry::<TestRuntime>::get(shard).unwrap();
+			assert_eq!(migrated.active_config, ShardConfig::from(config.clone()));
+			assert_eq!(migrated.active_config.unshield_threshold, None);
+			assert_eq!(migrated.pending_upgrade, Some(ShardConfig::from(config)));
+			assert_eq!(migrated.upgrade_at, Some(5));
+		});
+	}
+
+	#[allow(deprecated)]
+	#[test]
+	fn migration_v1_to_v2_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(1).put::<Pallet<TestRuntime>>();
+			frame_system::Pallet::<TestRuntime>::set_block_number(7);
+
+			let call_hash = H256::from([1u8; 32]);
+			unhashed::put(&ExecutedUnshieldCalls::<TestRuntime>::hashed_key_for(call_hash), &3u64);
+
+			let state = v2::MigrateV1toV2::<TestRuntime>::pre_upgrade().unwrap();
+			let _weight = v2::MigrateV1toV2::<TestRuntime>::on_runtime_upgrade();
+			v2::MigrateV1toV2::<TestRuntime>::post_upgrade(state).unwrap();
+
+			assert_eq!(
+				ExecutedUnshieldCalls::<TestRuntime>::get(call_hash),
+				Some(ExecutedUnshield { executed_at: 7, confirmations: 3 })
+			);
+		});
+	}
+
+	#[allow(deprecated)]
+	#[test]
+	fn migration_v2_to_v3_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(2).put::<Pallet<TestRuntime>>();
+			let alice = AccountKeyring::Alice.to_account_id();
+
+			let shard = ShardIdentifier::from([1u8; 32]);
+			ShardConfigRegistry::<TestRuntime>::insert(
+				shard,
+				UpgradableShardConfig::from(ShardConfig::new(EnclaveFingerprint::from([1u8; 32]))),
+			);
+			let legacy_vault = Pallet::<TestRuntime>::legacy_shard_vault(shard).unwrap();
+			assert_ok!(Balances::transfer_allow_death(
+				RuntimeOrigin::signed(alice.clone()),
+				legacy_vault.clone(),
+				100
+			));
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(alice),
+				USDT_ASSET_ID,
+				legacy_vault.clone(),
+				50
+			));
+
+			// a shard which differs only in the last byte must not share the vault
+			let mut last_byte_differs = [1u8; 32];
+			last_byte_differs[31] = 2;
+			let other_shard = ShardIdentifier::from(last_byte_differs);
+			ShardConfigRegistry::<TestRuntime>::insert(
+				other_shard,
+				UpgradableShardConfig::from(ShardConfig::new(EnclaveFingerprint::from([1u8; 32]))),
+			);
+			let other_legacy_vault =
+				Pallet::<TestRuntime>::legacy_shard_vault(other_shard).unwrap();
+			assert_ok!(Balances::transfer_allow_death(
+				RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+				other_legacy_vault.clone(),
+				30
+			));
+
+			let state = v3::MigrateV2toV3::<TestRuntime, LegacyAssets>::pre_upgrade().unwrap();
+			let _weight = v3::MigrateV2toV3::<TestRuntime, LegacyAssets>::on_runtime_upgrade();
+			v3::MigrateV2toV3::<TestRuntime, LegacyAssets>::post_upgrade(state).unwrap();
+
+			let vault = Pallet::<TestRuntime>::shard_vault(shard);
+			assert_eq!(Balances::free_balance(&vault), 100);
+			assert_eq!(Balances::free_balance(&legacy_vault), 0);
+			assert_eq!(Assets::balance(USDT_ASSET_ID, &vault), 50);
+			assert_eq!(Assets::balance(USDT_ASSET_ID, &legacy_vault), 0);
+			let other_vault = Pallet::<TestRuntime>::shard_vault(other_shard);
+			assert_ne!(vault, other_vault);
+			assert_eq!(Balances::free_balance(&other_vault), 30);
+			assert_eq!(Balances::free_balance(&other_legacy_vault), 0);
+		});
+	}
+}
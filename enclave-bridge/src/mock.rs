@@ -18,7 +18,11 @@
 // Creating mock runtime here
 use crate as pallet_enclave_bridge;
 use crate::Config;
-use frame_support::{self, derive_impl, parameter_types};
+use frame_support::{
+	self, derive_impl, parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, Currency, OnUnbalanced},
+	PalletId,
+};
 use frame_system as system;
 use sp_core::H256;
 use sp_keyring::AccountKeyring;
@@ -27,6 +31,11 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
 	BuildStorage,
 };
+use staging_xcm::latest::{
+	Junction::{GeneralIndex, PalletInstance, Parachain},
+	Location,
+};
+use xcm_primitives::AssetLocationGetter;
 
 pub type Signature = sp_runtime::MultiSignature;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
@@ -51,6 +60,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Teerex: pallet_teerex::{Pallet, Call, Storage, Event<T>},
 		EnclaveBridge: pallet_enclave_bridge::{Pallet, Call, Storage, Event<T>},
@@ -91,7 +101,7 @@ impl frame_system::Config for Test {
 pub type Balance = u64;
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
+	pub static ExistentialDeposit: u64 = 1;
 }
 
 impl pallet_balances::Config for Test {
@@ -116,6 +126,29 @@ parameter_types! {
 
 pub type Moment = u64;
 
+impl pallet_assets::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u64;
+	type RemoveItemsLimit = ConstU32<5>;
+	type AssetId = u32;
+	type AssetIdParameter = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<10>;
+	type MetadataDepositBase = ConstU64<1>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
 impl pallet_timestamp::Config for Test {
 	type Moment = Moment;
 	type OnTimestampSet = ();
@@ -133,12 +166,72 @@ impl pallet_teerex::Config for Test {
 	type MomentsPerDay = MomentsPerDay;
 	type MaxAttestationRenewalPeriod = MaxAttestationRenewalPeriod;
 	type WeightInfo = ();
+	type SecurityFlagsOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollateralOrigin = frame_system::EnsureRoot<AccountId>;
+	type TrustAnchorOrigin = frame_system::EnsureRoot<AccountId>;
+	type AttestationPolicyOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub const EnclaveBridgePalletId: PalletId = PalletId(*b"py/encbr");
+	pub const UnshieldConfirmationTimeout: u32 = 10;
+	pub const UnshieldRetentionPeriod: u32 = 100;
+	pub const ShardSignerInactivityTimeout: u32 = 50;
+	pub const MaxRequestSize: u32 = 2048;
+	pub const RequestFeePerByte: u64 = 1;
+	pub const MaxInboxLength: u32 = 3;
+	pub TreasuryAccount: AccountId = AccountId::from([9u8; 32]);
+}
+
+/// Deposits request fees into the `TreasuryAccount`.
+pub struct FeesToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for FeesToTreasury {
+	fn on_nonzero_unbalanced(fee: pallet_balances::NegativeImbalance<Test>) {
+		Balances::resolve_creating(&TreasuryAccount::get(), fee);
+	}
+}
+
+pub const USDT_ASSET_ID: u32 = 1984;
+pub const UNREGISTERED_ASSET_ID: u32 = 7;
+
+pub fn usdt_location() -> Location {
+	Location::new(1, [Parachain(1000), PalletInstance(50), GeneralIndex(USDT_ASSET_ID.into())])
+}
+
+/// Knows the location of USDT only.
+pub struct MockAssetRegistry;
+impl AssetLocationGetter<u32> for MockAssetRegistry {
+	fn get_asset_location(asset_id: u32) -> Option<Location> {
+		(asset_id == USDT_ASSET_ID).then(usdt_location)
+	}
+
+	fn get_asset_id(asset_location: &Location) -> Option<u32> {
+		(asset_location == &usdt_location()).then_some(USDT_ASSET_ID)
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_enclave_bridge::BenchmarkHelper<u32> for MockAssetRegistry {
+	fn get_registered_asset() -> u32 {
+		USDT_ASSET_ID
+	}
 }
 
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type PalletId = EnclaveBridgePalletId;
 	type Currency = Balances;
+	type Fungibles = Assets;
+	type AssetLocationGetter = MockAssetRegistry;
 	type WeightInfo = ();
+	type UnshieldConfirmationTimeout = UnshieldConfirmationTimeout;
+	type UnshieldRetentionPeriod = UnshieldRetentionPeriod;
+	type ShardSignerInactivityTimeout = ShardSignerInactivityTimeout;
+	type MaxRequestSize = MaxRequestSize;
+	type RequestFee = pallet_enclave_bridge::PerByteFee<Test, RequestFeePerByte, FeesToTreasury>;
+	type MaxInboxLength = MaxInboxLength;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockAssetRegistry;
 }
 
 // This function basically just builds a genesis storage key/value store according to
@@ -150,6 +243,21 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
+	pallet_assets::GenesisConfig::<Test> {
+		assets: vec![
+			// id, owner, is_sufficient, min_balance
+			(USDT_ASSET_ID, AccountKeyring::Alice.to_account_id(), true, 1),
+			(UNREGISTERED_ASSET_ID, AccountKeyring::Alice.to_account_id(), true, 1),
+		],
+		metadata: vec![],
+		accounts: vec![
+			// id, account_id, balance
+			(USDT_ASSET_ID, AccountKeyring::Alice.to_account_id(), 1 << 40),
+			(UNREGISTERED_ASSET_ID, AccountKeyring::Alice.to_account_id(), 1 << 40),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 	let _teerex_config = pallet_teerex::GenesisConfig::<Test> {
 		allow_sgx_debug_mode: true,
 		allow_skipping_attestation: true,
@@ -171,6 +279,11 @@ pub fn run_to_block(n: u32) {
 			System::on_finalize(System::block_number());
 		}
 		Timestamp::on_finalize(System::block_number());
+		// give every block a distinct hash so parentchain block confirmations can be checked
+		system::BlockHash::<Test>::insert(
+			System::block_number(),
+			H256::repeat_byte(System::block_number() as u8),
+		);
 		System::set_block_number(System::block_number() + 1);
 		System::on_initialize(System::block_number());
 	}
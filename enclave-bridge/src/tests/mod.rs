@@ -17,7 +17,7 @@
 
 use crate::mock::*;
 use frame_support::assert_ok;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::Encode;
 use teerex_primitives::{EnclaveFingerprint, MultiEnclave, SgxEnclave};
 use test_utils::TestEnclave;
 
@@ -28,7 +28,7 @@ mod test_shard_config;
 mod test_shard_status;
 
 fn get_bonding_account(enclave: &MultiEnclave<Vec<u8>>) -> AccountId {
-	AccountId::decode(&mut enclave.fingerprint().encode().as_ref()).unwrap()
+	EnclaveBridge::shard_vault(enclave.fingerprint())
 }
 
 fn now() -> u64 {
@@ -16,11 +16,23 @@
 */
 
 use super::*;
-use crate::{Error, Event as EnclaveBridgeEvent, ExecutedUnshieldCalls, Request, ShardConfig};
-use enclave_bridge_primitives::{EnclaveFingerprint, ShardIdentifier};
-use frame_support::{assert_err, assert_ok};
+use crate::{
+	Error, Event as EnclaveBridgeEvent, ExecutedUnshieldCalls, PendingAssetUnshieldCalls,
+	PendingUnshieldCalls, Request, ShardConfig, UnshieldPruneCursor,
+};
+use enclave_bridge_primitives::{
+	EnclaveFingerprint, ExecutedUnshield, ProcessedParentchainBlock, RequestInboxStatus,
+	ShardIdentifier,
+};
+use frame_support::{
+	assert_err, assert_ok,
+	traits::{Get, Hooks},
+	weights::Weight,
+};
 use sp_core::H256;
+use sp_io::hashing::blake2_256;
 use sp_keyring::AccountKeyring;
+use sp_runtime::traits::AccountIdConversion;
 
 #[test]
 fn invoke_works() {
@@ -38,6 +50,194 @@ fn invoke_works() {
 	})
 }
 
+#[test]
+fn invoke_charges_fee_per_byte_to_fee_handler() {
+	new_test_ext().execute_with(|| {
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let signer = AccountKeyring::Alice.to_account_id();
+		let balance_before = Balances::free_balance(&signer);
+
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(signer.clone()),
+			Request { shard, cyphertext: vec![1u8; 100] }
+		));
+		let fee = 100 * RequestFeePerByte::get();
+		assert_eq!(Balances::free_balance(&signer), balance_before - fee);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), fee);
+		// fees don't mix with the funds shielded to the shard
+		assert_eq!(Balances::free_balance(EnclaveBridge::shard_vault(shard)), 0);
+	})
+}
+
+#[test]
+fn invoke_with_fee_below_existential_deposit_works() {
+	new_test_ext().execute_with(|| {
+		ExistentialDeposit::set(1_000);
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let signer = AccountKeyring::Alice.to_account_id();
+		let balance_before = Balances::free_balance(&signer);
+		let fee = 100 * RequestFeePerByte::get();
+		assert!(fee < ExistentialDeposit::get());
+
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(signer.clone()),
+			Request { shard, cyphertext: vec![1u8; 100] }
+		));
+		assert_eq!(Balances::free_balance(&signer), balance_before - fee);
+		assert_eq!(Balances::free_balance(EnclaveBridge::shard_vault(shard)), 0);
+	})
+}
+
+#[test]
+fn invoke_with_too_large_request_fails() {
+	new_test_ext().execute_with(|| {
+		let signer = AccountKeyring::Alice.to_account_id();
+		let cyphertext = vec![1u8; MaxRequestSize::get() as usize + 1];
+		assert_err!(
+			EnclaveBridge::invoke(
+				RuntimeOrigin::signed(signer),
+				Request { shard: ShardIdentifier::default(), cyphertext }
+			),
+			Error::<Test>::RequestTooLarge
+		);
+	})
+}
+
+#[test]
+fn invoke_queues_request_in_inbox() {
+	new_test_ext().execute_with(|| {
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let signer = AccountKeyring::Alice.to_account_id();
+		for i in 0..2u8 {
+			assert_ok!(EnclaveBridge::invoke(
+				RuntimeOrigin::signed(signer.clone()),
+				Request { shard, cyphertext: vec![i; 4] }
+			));
+			let expected_event =
+				RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::IndirectInvocationQueued {
+					shard,
+					request_id: i.into(),
+				});
+			assert!(System::events().iter().any(|a| a.event == expected_event));
+		}
+		assert_eq!(EnclaveBridge::inbox_request(shard, 0), Some(vec![0u8; 4]));
+		assert_eq!(EnclaveBridge::inbox_request(shard, 1), Some(vec![1u8; 4]));
+		assert_eq!(
+			EnclaveBridge::request_inbox_status(shard),
+			RequestInboxStatus { next_request_id: 2, next_unacknowledged_id: 0 }
+		);
+		// other shards have their own inbox
+		assert!(EnclaveBridge::request_inbox_status(ShardIdentifier::default()).is_empty());
+	})
+}
+
+#[test]
+fn invoke_registers_request_by_event_only_if_inbox_is_full() {
+	new_test_ext().execute_with(|| {
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let signer = AccountKeyring::Alice.to_account_id();
+		for _ in 0..MaxInboxLength::get() {
+			assert_ok!(EnclaveBridge::invoke(
+				RuntimeOrigin::signed(signer.clone()),
+				Request { shard, cyphertext: vec![1u8; 4] }
+			));
+		}
+		System::reset_events();
+
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(signer),
+			Request { shard, cyphertext: vec![2u8; 4] }
+		));
+		let expected_event =
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::IndirectInvocationRegistered(shard));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		// workers relying on the inbox learn that the request has not been queued
+		let expected_event =
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::IndirectInvocationNotQueued { shard });
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert!(!System::events().iter().any(|a| matches!(
+			a.event,
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::IndirectInvocationQueued { .. })
+		)));
+		assert_eq!(
+			EnclaveBridge::request_inbox_status(shard),
+			RequestInboxStatus {
+				next_request_id: MaxInboxLength::get() as u64,
+				next_unacknowledged_id: 0
+			}
+		);
+	})
+}
+
+#[test]
+fn acknowledge_processed_requests_prunes_inbox() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let shard = setup_multi_validateer_shard(&[enclave_signer.clone()]);
+		let signer = AccountKeyring::Alice.to_account_id();
+		for i in 0..MaxInboxLength::get() as u8 {
+			assert_ok!(EnclaveBridge::invoke(
+				RuntimeOrigin::signed(signer.clone()),
+				Request { shard, cyphertext: vec![i; 4] }
+			));
+		}
+
+		assert_err!(
+			EnclaveBridge::acknowledge_processed_requests(
+				RuntimeOrigin::signed(enclave_signer.clone()),
+				shard,
+				MaxInboxLength::get().into()
+			),
+			Error::<Test>::UnknownRequestId
+		);
+		assert_ok!(EnclaveBridge::acknowledge_processed_requests(
+			RuntimeOrigin::signed(enclave_signer.clone()),
+			shard,
+			1
+		));
+		let expected_event =
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::ProcessedRequestsAcknowledged {
+				shard,
+				request_id: 1,
+			});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(EnclaveBridge::inbox_request(shard, 0), None);
+		assert_eq!(EnclaveBridge::inbox_request(shard, 1), None);
+		assert_eq!(EnclaveBridge::inbox_request(shard, 2), Some(vec![2u8; 4]));
+		assert_eq!(EnclaveBridge::request_inbox_status(shard).len(), 1);
+
+		// acknowledged requests make room for new ones
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(signer),
+			Request { shard, cyphertext: vec![3u8; 4] }
+		));
+		assert_eq!(EnclaveBridge::inbox_request(shard, 3), Some(vec![3u8; 4]));
+
+		// acknowledging again is harmless
+		assert_ok!(EnclaveBridge::acknowledge_processed_requests(
+			RuntimeOrigin::signed(enclave_signer),
+			shard,
+			0
+		));
+		assert_eq!(EnclaveBridge::request_inbox_status(shard).len(), 2);
+	})
+}
+
+#[test]
+fn acknowledge_processed_requests_from_non_enclave_errs() {
+	new_test_ext().execute_with(|| {
+		assert_err!(
+			EnclaveBridge::acknowledge_processed_requests(
+				RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+				ShardIdentifier::default(),
+				0
+			),
+			pallet_teerex::Error::<Test>::EnclaveIsNotRegistered
+		);
+	})
+}
+
 #[test]
 fn unshield_is_only_executed_once_for_the_same_call_hash() {
 	new_test_ext().execute_with(|| {
@@ -66,7 +266,8 @@ fn unshield_is_only_executed_once_for_the_same_call_hash() {
 			shard,
 			beneficiary.clone(),
 			amount,
-			call_hash
+			call_hash,
+			0
 		)
 		.is_ok());
 		let expected_event = RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::UnshieldedFunds {
@@ -83,12 +284,457 @@ fn unshield_is_only_executed_once_for_the_same_call_hash() {
 			shard,
 			beneficiary,
 			amount,
-			call_hash
+			call_hash,
+			0
 		)
 		.is_ok());
 		assert!(!System::events().iter().any(|a| a.event == expected_event));
 
-		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash), 2)
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().confirmations, 2)
+	})
+}
+
+/// Registers the validateers of a shard and funds its bonding account.
+fn setup_multi_validateer_shard(signers: &[AccountId]) -> ShardIdentifier {
+	let fingerprint = EnclaveFingerprint::from([7u8; 32]);
+	let shard = ShardIdentifier::from(fingerprint);
+	for signer in signers {
+		register_sovereign_test_enclave(signer, fingerprint);
+		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
+			RuntimeOrigin::signed(signer.clone()),
+			shard,
+			System::block_hash(0),
+			0,
+			H256::default()
+		));
+	}
+	assert_ok!(EnclaveBridge::shield_funds(
+		RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+		shard,
+		vec![],
+		100,
+	));
+	shard
+}
+
+#[test]
+fn unshield_is_executed_once_a_majority_of_validateers_confirmed() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> =
+			[AccountKeyring::Eve, AccountKeyring::Ferdie, AccountKeyring::Dave]
+				.iter()
+				.map(|k| k.to_account_id())
+				.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		let expected_event = RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::UnshieldConfirmed {
+			shard,
+			call_hash,
+			confirmations: 1,
+			threshold: 2,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(Balances::free_balance(&bonding_account), 100);
+
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(validateers[0].clone()),
+				shard,
+				beneficiary.clone(),
+				50,
+				call_hash,
+				0
+			),
+			Error::<Test>::UnshieldAlreadyConfirmed
+		);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[1].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(&bonding_account), 50);
+		assert!(<PendingUnshieldCalls<Test>>::get(call_hash).is_none());
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().confirmations, 2);
+
+		// late confirmations are only counted
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[2].clone()),
+			shard,
+			beneficiary,
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(&bonding_account), 50);
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().confirmations, 3);
+	})
+}
+
+#[test]
+fn unshield_threshold_is_taken_from_shard_config() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(shard).with_unshield_threshold(1),
+			0
+		));
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary,
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(bonding_account), 50);
+	})
+}
+
+#[test]
+fn unshield_confirmations_must_agree_on_parameters() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(validateers[1].clone()),
+				shard,
+				beneficiary,
+				60,
+				call_hash,
+				0
+			),
+			Error::<Test>::UnshieldParametersMismatch
+		);
+	})
+}
+
+#[test]
+fn pending_unshield_confirmations_expire() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		run_to_block(1 + UnshieldConfirmationTimeout::get() + 1);
+
+		// the first confirmation has expired, hence this one starts over
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[1].clone()),
+			shard,
+			beneficiary,
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(bonding_account), 100);
+		assert_eq!(
+			<PendingUnshieldCalls<Test>>::get(call_hash).unwrap().confirmed_by,
+			vec![validateers[1].clone()]
+		);
+	})
+}
+
+#[test]
+fn unshield_confirmations_of_purged_validateers_are_not_counted() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> =
+			[AccountKeyring::Eve, AccountKeyring::Ferdie, AccountKeyring::Dave]
+				.iter()
+				.map(|k| k.to_account_id())
+				.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		assert_ok!(EnclaveBridge::purge_enclave_from_shard_status(
+			RuntimeOrigin::root(),
+			shard,
+			validateers[0].clone()
+		));
+
+		// the confirmation of the purged validateer doesn't count towards the threshold anymore
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[1].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		let expected_event = RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::UnshieldConfirmed {
+			shard,
+			call_hash,
+			confirmations: 1,
+			threshold: 2,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(Balances::free_balance(&bonding_account), 100);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[2].clone()),
+			shard,
+			beneficiary,
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(&bonding_account), 50);
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().confirmations, 2);
+	})
+}
+
+#[test]
+fn executed_unshield_calls_are_pruned_after_the_retention_period() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let shard = setup_multi_validateer_shard(&[enclave_signer.clone()]);
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(enclave_signer),
+			shard,
+			beneficiary,
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().executed_at, 1);
+
+		let retained_until = 1 + UnshieldRetentionPeriod::get();
+		EnclaveBridge::on_idle(retained_until, Weight::MAX);
+		assert!(<ExecutedUnshieldCalls<Test>>::contains_key(call_hash));
+
+		EnclaveBridge::on_idle(retained_until + 1, Weight::MAX);
+		assert!(!<ExecutedUnshieldCalls<Test>>::contains_key(call_hash));
+	})
+}
+
+#[test]
+fn replayed_unshield_call_is_rejected_after_pruning() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let shard = setup_multi_validateer_shard(&[enclave_signer.clone()]);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(enclave_signer.clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			0
+		));
+		assert_eq!(Balances::free_balance(&bonding_account), 50);
+
+		let now = 2 + UnshieldRetentionPeriod::get();
+		run_to_block(now);
+		EnclaveBridge::on_idle(now, Weight::MAX);
+		assert!(!<ExecutedUnshieldCalls<Test>>::contains_key(call_hash));
+
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(enclave_signer),
+				shard,
+				beneficiary,
+				50,
+				call_hash,
+				0
+			),
+			Error::<Test>::UnshieldCallExpired
+		);
+		assert_eq!(Balances::free_balance(&bonding_account), 50);
+		assert!(<PendingUnshieldCalls<Test>>::get(call_hash).is_none());
+	})
+}
+
+#[test]
+fn unshield_call_issued_at_current_block_fails() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let shard = setup_multi_validateer_shard(&[enclave_signer.clone()]);
+
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(enclave_signer),
+				shard,
+				AccountKeyring::Bob.to_account_id(),
+				50,
+				H256::from([1u8; 32]),
+				System::block_number()
+			),
+			Error::<Test>::UnknownParentchainBlock
+		);
+	})
+}
+
+#[test]
+fn unshield_confirmations_must_agree_on_issuing_block() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		run_to_block(3);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			call_hash,
+			1
+		));
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(validateers[1].clone()),
+				shard,
+				beneficiary,
+				50,
+				call_hash,
+				2
+			),
+			Error::<Test>::UnshieldParametersMismatch
+		);
+	})
+}
+
+#[test]
+fn expired_pending_unshield_calls_are_pruned() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let shard = setup_multi_validateer_shard(&validateers);
+
+		assert_ok!(EnclaveBridge::unshield_funds(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			beneficiary.clone(),
+			50,
+			H256::from([1u8; 32]),
+			0
+		));
+		assert_ok!(EnclaveBridge::unshield_asset(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			USDT_ASSET_ID,
+			beneficiary,
+			60,
+			H256::from([2u8; 32]),
+			0
+		));
+
+		let expires_at = 1 + UnshieldConfirmationTimeout::get();
+		EnclaveBridge::on_idle(expires_at, Weight::MAX);
+		assert_eq!(<PendingUnshieldCalls<Test>>::iter_keys().count(), 1);
+		assert_eq!(<PendingAssetUnshieldCalls<Test>>::iter_keys().count(), 1);
+
+		EnclaveBridge::on_idle(expires_at + 1, Weight::MAX);
+		assert_eq!(<PendingUnshieldCalls<Test>>::iter_keys().count(), 0);
+		assert_eq!(<PendingAssetUnshieldCalls<Test>>::iter_keys().count(), 0);
+	})
+}
+
+#[test]
+fn pruning_executed_unshield_calls_resumes_from_cursor() {
+	new_test_ext().execute_with(|| {
+		for i in 0..3u8 {
+			<ExecutedUnshieldCalls<Test>>::insert(
+				H256::from([i; 32]),
+				ExecutedUnshield { executed_at: 1, confirmations: 1 },
+			);
+		}
+		let first_key = <ExecutedUnshieldCalls<Test>>::iter_keys().next().unwrap();
+		<UnshieldPruneCursor<Test>>::put(<ExecutedUnshieldCalls<Test>>::hashed_key_for(first_key));
+
+		let now = 2 + UnshieldRetentionPeriod::get();
+		EnclaveBridge::on_idle(now, Weight::MAX);
+		// the sweep continues after the cursor and starts over once the map is exhausted
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::iter_keys().collect::<Vec<_>>(), vec![first_key]);
+		assert!(<UnshieldPruneCursor<Test>>::get().is_none());
+
+		EnclaveBridge::on_idle(now, Weight::MAX);
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::iter_keys().count(), 0);
 	})
 }
 
@@ -130,7 +776,8 @@ fn verify_unshield_funds_works() {
 			shard,
 			beneficiary.clone(),
 			50,
-			call_hash
+			call_hash,
+			0
 		)
 		.is_ok());
 		assert_eq!(Balances::free_balance(bonding_account), 50);
@@ -158,7 +805,8 @@ fn unshield_funds_from_not_registered_enclave_errs() {
 				shard,
 				beneficiary,
 				51,
-				call_hash
+				call_hash,
+				0
 			),
 			pallet_teerex::Error::<Test>::EnclaveIsNotRegistered
 		);
@@ -180,8 +828,8 @@ fn unshield_funds_from_enclave_on_wrong_shard_errs() {
 			register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
 		let shard = ShardIdentifier::from(enclave.fingerprint());
 		let not_shard = ShardIdentifier::from([222u8; 32]);
-		let bonding_account = AccountId::decode(&mut shard.as_ref()).unwrap();
-		let not_bonding_account = AccountId::decode(&mut not_shard.as_ref()).unwrap();
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+		let not_bonding_account = EnclaveBridge::shard_vault(not_shard);
 
 		//Ensure that both bonding account have funds
 		assert!(EnclaveBridge::shield_funds(
@@ -206,7 +854,8 @@ fn unshield_funds_from_enclave_on_wrong_shard_errs() {
 				not_shard,
 				beneficiary,
 				50,
-				call_hash
+				call_hash,
+				0
 			),
 			Error::<Test>::WrongFingerprintForShard
 		);
@@ -219,9 +868,10 @@ fn unshield_funds_from_enclave_on_wrong_shard_errs() {
 #[test]
 fn confirm_processed_parentchain_block_works() {
 	new_test_ext().execute_with(|| {
-		let block_hash = H256::default();
-		let merkle_root = H256::default();
+		run_to_block(4);
 		let block_number = 3;
+		let block_hash = System::block_hash(block_number);
+		let merkle_root = H256::default();
 		let enclave_signer = AccountKeyring::Eve.to_account_id();
 		let _enclave =
 			register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
@@ -242,6 +892,130 @@ fn confirm_processed_parentchain_block_works() {
 				block_number,
 			});
 		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(
+			EnclaveBridge::last_processed_parentchain_block(ShardIdentifier::default()),
+			Some(ProcessedParentchainBlock {
+				block_number,
+				block_hash,
+				trusted_calls_merkle_root: merkle_root
+			})
+		);
+	})
+}
+
+#[test]
+fn confirm_processed_parentchain_block_regression_fails() {
+	new_test_ext().execute_with(|| {
+		run_to_block(4);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let other_signer = AccountKeyring::Ferdie.to_account_id();
+		register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
+		register_sovereign_test_enclave(&other_signer, EnclaveFingerprint::default());
+		let shard = ShardIdentifier::default();
+
+		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
+			RuntimeOrigin::signed(enclave_signer.clone()),
+			shard,
+			System::block_hash(3),
+			3,
+			H256::default(),
+		));
+		// other validateers of the shard confirm the same block
+		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
+			RuntimeOrigin::signed(other_signer),
+			shard,
+			System::block_hash(3),
+			3,
+			H256::default(),
+		));
+		assert_err!(
+			EnclaveBridge::confirm_processed_parentchain_block(
+				RuntimeOrigin::signed(enclave_signer),
+				shard,
+				System::block_hash(2),
+				2,
+				H256::default(),
+			),
+			Error::<Test>::ParentchainBlockNumberRegression
+		);
+		assert_eq!(EnclaveBridge::last_processed_parentchain_block(shard).unwrap().block_number, 3);
+	})
+}
+
+#[test]
+fn lagging_validateer_may_confirm_older_parentchain_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(4);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let lagging_signer = AccountKeyring::Ferdie.to_account_id();
+		register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
+		register_sovereign_test_enclave(&lagging_signer, EnclaveFingerprint::default());
+		let shard = ShardIdentifier::default();
+
+		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
+			RuntimeOrigin::signed(enclave_signer),
+			shard,
+			System::block_hash(3),
+			3,
+			H256::default(),
+		));
+		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
+			RuntimeOrigin::signed(lagging_signer.clone()),
+			shard,
+			System::block_hash(2),
+			2,
+			H256::default(),
+		));
+
+		assert_eq!(
+			EnclaveBridge::last_processed_parentchain_block_of(shard, &lagging_signer),
+			Some(2)
+		);
+		// the shard isn't set back by the lagging validateer
+		assert_eq!(EnclaveBridge::last_processed_parentchain_block(shard).unwrap().block_number, 3);
+	})
+}
+
+#[test]
+fn confirm_processed_parentchain_block_with_wrong_hash_fails() {
+	new_test_ext().execute_with(|| {
+		run_to_block(4);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
+
+		assert_err!(
+			EnclaveBridge::confirm_processed_parentchain_block(
+				RuntimeOrigin::signed(enclave_signer),
+				ShardIdentifier::default(),
+				System::block_hash(2),
+				3,
+				H256::default(),
+			),
+			Error::<Test>::ParentchainBlockHashMismatch
+		);
+		assert!(
+			EnclaveBridge::last_processed_parentchain_block(ShardIdentifier::default()).is_none()
+		);
+	})
+}
+
+#[test]
+fn confirm_processed_parentchain_block_of_current_block_fails() {
+	new_test_ext().execute_with(|| {
+		run_to_block(4);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
+
+		assert_err!(
+			EnclaveBridge::confirm_processed_parentchain_block(
+				RuntimeOrigin::signed(enclave_signer),
+				ShardIdentifier::default(),
+				H256::default(),
+				4,
+				H256::default(),
+			),
+			Error::<Test>::UnknownParentchainBlock
+		);
 	})
 }
 
@@ -289,7 +1063,7 @@ fn confirm_processed_parentchain_block_from_wrong_enclave_fails() {
 fn confirm_processed_parentchain_block_from_updated_enclave_works() {
 	new_test_ext().execute_with(|| {
 		Timestamp::set_timestamp(NOW);
-		run_to_block(1);
+		run_to_block(2);
 		let enclave_signer = AccountKeyring::Eve.to_account_id();
 		let enclave =
 			register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
@@ -305,7 +1079,7 @@ fn confirm_processed_parentchain_block_from_updated_enclave_works() {
 		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
 			RuntimeOrigin::signed(enclave_signer.clone()),
 			ShardIdentifier::default(),
-			H256::default(),
+			System::block_hash(1),
 			1,
 			H256::default(),
 		));
@@ -323,12 +1097,12 @@ fn confirm_processed_parentchain_block_from_updated_enclave_works() {
 		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
 			RuntimeOrigin::signed(enclave_signer.clone()),
 			ShardIdentifier::default(),
-			H256::default(),
+			System::block_hash(1),
 			1,
 			H256::default(),
 		));
 
-		run_to_block(2);
+		run_to_block(3);
 
 		// enclave upgrade of instance takes place
 		register_sovereign_test_enclave(&enclave_signer, new_fingerprint);
@@ -337,9 +1111,244 @@ fn confirm_processed_parentchain_block_from_updated_enclave_works() {
 		assert_ok!(EnclaveBridge::confirm_processed_parentchain_block(
 			RuntimeOrigin::signed(enclave_signer.clone()),
 			ShardIdentifier::default(),
-			H256::default(),
+			System::block_hash(2),
 			2,
 			H256::default(),
 		));
 	})
 }
+
+#[test]
+fn shield_asset_works() {
+	new_test_ext().execute_with(|| {
+		let shielder = AccountKeyring::Alice.to_account_id();
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+
+		assert_ok!(EnclaveBridge::shield_asset(
+			RuntimeOrigin::signed(shielder),
+			shard,
+			USDT_ASSET_ID,
+			vec![1u8; 32],
+			100
+		));
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &bonding_account), 100);
+		let expected_event = RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::ShieldedAsset {
+			shard,
+			asset_id: USDT_ASSET_ID,
+			location: usdt_location(),
+			encrypted_beneficiary: vec![1u8; 32],
+			amount: 100,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn shield_asset_fails_for_asset_without_location() {
+	new_test_ext().execute_with(|| {
+		assert_err!(
+			EnclaveBridge::shield_asset(
+				RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+				ShardIdentifier::from([7u8; 32]),
+				UNREGISTERED_ASSET_ID,
+				vec![1u8; 32],
+				100
+			),
+			Error::<Test>::UnknownAssetLocation
+		);
+	})
+}
+
+#[test]
+fn unshield_asset_is_executed_once_a_majority_of_validateers_confirmed() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+		let bonding_account = EnclaveBridge::shard_vault(shard);
+		assert_ok!(EnclaveBridge::shield_asset(
+			RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+			shard,
+			USDT_ASSET_ID,
+			vec![],
+			100
+		));
+
+		assert_ok!(EnclaveBridge::unshield_asset(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			USDT_ASSET_ID,
+			beneficiary.clone(),
+			60,
+			call_hash,
+			0
+		));
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &bonding_account), 100);
+		assert_eq!(<PendingAssetUnshieldCalls<Test>>::get(call_hash).unwrap().0, USDT_ASSET_ID);
+
+		assert_ok!(EnclaveBridge::unshield_asset(
+			RuntimeOrigin::signed(validateers[1].clone()),
+			shard,
+			USDT_ASSET_ID,
+			beneficiary.clone(),
+			60,
+			call_hash,
+			0
+		));
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &bonding_account), 40);
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &beneficiary), 60);
+		// the native balance of the vault is untouched
+		assert_eq!(Balances::free_balance(&bonding_account), 100);
+		assert!(<PendingAssetUnshieldCalls<Test>>::get(call_hash).is_none());
+		assert_eq!(<ExecutedUnshieldCalls<Test>>::get(call_hash).unwrap().confirmations, 2);
+		let expected_event = RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::UnshieldedAsset {
+			shard,
+			asset_id: USDT_ASSET_ID,
+			location: Some(usdt_location()),
+			beneficiary,
+			amount: 60,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn unshield_asset_fails_for_mismatching_asset() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let validateers: Vec<AccountId> = [AccountKeyring::Eve, AccountKeyring::Ferdie]
+			.iter()
+			.map(|k| k.to_account_id())
+			.collect();
+		let beneficiary = AccountKeyring::Bob.to_account_id();
+		let call_hash: H256 = H256::from([1u8; 32]);
+		let shard = setup_multi_validateer_shard(&validateers);
+
+		assert_ok!(EnclaveBridge::unshield_asset(
+			RuntimeOrigin::signed(validateers[0].clone()),
+			shard,
+			USDT_ASSET_ID,
+			beneficiary.clone(),
+			60,
+			call_hash,
+			0
+		));
+		assert_err!(
+			EnclaveBridge::unshield_asset(
+				RuntimeOrigin::signed(validateers[1].clone()),
+				shard,
+				UNREGISTERED_ASSET_ID,
+				beneficiary,
+				60,
+				call_hash,
+				0
+			),
+			Error::<Test>::UnshieldParametersMismatch
+		);
+	})
+}
+
+#[test]
+fn shard_vault_is_derived_from_pallet_id() {
+	new_test_ext().execute_with(|| {
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let vault = EnclaveBridge::shard_vault(shard);
+		assert_eq!(
+			vault,
+			EnclaveBridgePalletId::get().into_sub_account_truncating(blake2_256(&shard.encode()))
+		);
+		assert_ne!(Some(vault), EnclaveBridge::legacy_shard_vault(shard));
+		assert_ne!(vault, EnclaveBridge::shard_vault(ShardIdentifier::from([8u8; 32])));
+	})
+}
+
+#[test]
+fn shard_vaults_differ_for_shards_differing_only_in_their_last_byte() {
+	new_test_ext().execute_with(|| {
+		let mut last_byte_differs = [7u8; 32];
+		last_byte_differs[31] = 8;
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let other_shard = ShardIdentifier::from(last_byte_differs);
+		assert_ne!(EnclaveBridge::shard_vault(shard), EnclaveBridge::shard_vault(other_shard));
+
+		assert_ok!(EnclaveBridge::shield_funds(
+			RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+			shard,
+			vec![],
+			100,
+		));
+		assert_eq!(Balances::free_balance(EnclaveBridge::shard_vault(shard)), 100);
+		assert_eq!(Balances::free_balance(EnclaveBridge::shard_vault(other_shard)), 0);
+	})
+}
+
+#[test]
+fn anyone_may_sweep_legacy_vault_of_unconfigured_shard() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let shard = ShardIdentifier::from([7u8; 32]);
+		let legacy_vault = EnclaveBridge::legacy_shard_vault(shard).unwrap();
+		let vault = EnclaveBridge::shard_vault(shard);
+		assert_ok!(Balances::transfer_allow_death(
+			RuntimeOrigin::signed(alice.clone()),
+			legacy_vault.clone(),
+			100
+		));
+		assert_ok!(Assets::transfer(
+			RuntimeOrigin::signed(alice),
+			USDT_ASSET_ID,
+			legacy_vault.clone(),
+			50
+		));
+		assert!(EnclaveBridge::shard_config(shard).is_none());
+		assert!(EnclaveBridge::shard_status(shard).is_none());
+
+		let sweeper = AccountKeyring::Bob.to_account_id();
+		assert_ok!(EnclaveBridge::sweep_legacy_shard_vault(
+			RuntimeOrigin::signed(sweeper.clone()),
+			shard
+		));
+		assert_eq!(Balances::free_balance(&vault), 100);
+		assert_eq!(Balances::free_balance(&legacy_vault), 0);
+		let expected_event =
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::SweptLegacyShardVault {
+				shard,
+				amount: 100,
+			});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(EnclaveBridge::sweep_legacy_shard_vault_asset(
+			RuntimeOrigin::signed(sweeper),
+			shard,
+			USDT_ASSET_ID
+		));
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &vault), 50);
+		assert_eq!(Assets::balance(USDT_ASSET_ID, &legacy_vault), 0);
+	})
+}
+
+#[test]
+fn sweeping_empty_legacy_vault_fails() {
+	new_test_ext().execute_with(|| {
+		let sweeper = AccountKeyring::Bob.to_account_id();
+		let shard = ShardIdentifier::from([7u8; 32]);
+		assert_err!(
+			EnclaveBridge::sweep_legacy_shard_vault(RuntimeOrigin::signed(sweeper.clone()), shard),
+			Error::<Test>::LegacyShardVaultEmpty
+		);
+		assert_err!(
+			EnclaveBridge::sweep_legacy_shard_vault_asset(
+				RuntimeOrigin::signed(sweeper),
+				shard,
+				USDT_ASSET_ID
+			),
+			Error::<Test>::LegacyShardVaultEmpty
+		);
+	})
+}
@@ -16,9 +16,12 @@
 */
 
 use super::*;
-use crate::{Error, Event as EnclaveBridgeEvent, ShardConfigRegistry};
-use enclave_bridge_primitives::{ShardConfig, ShardIdentifier, UpgradableShardConfig};
-use frame_support::{assert_err, assert_ok};
+use crate::{Error, Event as EnclaveBridgeEvent, Request, ShardConfigRegistry};
+use enclave_bridge_primitives::{
+	ShardConfig, ShardIdentifier, UpgradableShardConfig, MAX_SHARD_STATUS_SIGNER_COUNT,
+};
+use frame_support::{assert_err, assert_ok, dispatch::DispatchResultWithPostInfo};
+use sp_core::H256;
 use sp_keyring::AccountKeyring;
 use teerex_primitives::EnclaveFingerprint;
 
@@ -287,3 +290,232 @@ fn get_maybe_updated_shard_config_works() {
 		);
 	})
 }
+
+fn confirm_block(signer: &AccountId, shard: ShardIdentifier) -> DispatchResultWithPostInfo {
+	EnclaveBridge::confirm_processed_parentchain_block(
+		RuntimeOrigin::signed(signer.clone()),
+		shard,
+		System::block_hash(0),
+		0,
+		H256::default(),
+	)
+}
+
+#[test]
+fn only_authorities_may_touch_shard() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let authority = AccountKeyring::Eve.to_account_id();
+		let intruder = AccountKeyring::Ferdie.to_account_id();
+		let fingerprint = EnclaveFingerprint::from([1u8; 32]);
+		register_sovereign_test_enclave(&authority, fingerprint);
+		register_sovereign_test_enclave(&intruder, fingerprint);
+		let shard = ShardIdentifier::from(fingerprint);
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(fingerprint).with_authorities(vec![authority.clone()]),
+			0,
+		));
+
+		assert_err!(confirm_block(&intruder, shard), Error::<Test>::EnclaveNotAuthorizedForShard);
+		assert_err!(
+			EnclaveBridge::update_shard_config(
+				RuntimeOrigin::signed(intruder),
+				shard,
+				ShardConfig::new(fingerprint),
+				0,
+			),
+			Error::<Test>::EnclaveNotAuthorizedForShard
+		);
+		assert_ok!(confirm_block(&authority, shard));
+
+		let status = EnclaveBridge::shard_status(shard).unwrap();
+		assert_eq!(status.len(), 1);
+		assert_eq!(status[0].signer, authority);
+	})
+}
+
+#[test]
+fn shard_status_may_not_exceed_max_instances() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let signers: Vec<AccountId> =
+			[AccountKeyring::Eve, AccountKeyring::Ferdie, AccountKeyring::Dave]
+				.iter()
+				.map(|k| k.to_account_id())
+				.collect();
+		let fingerprint = EnclaveFingerprint::from([1u8; 32]);
+		for signer in signers.iter() {
+			register_sovereign_test_enclave(signer, fingerprint);
+		}
+		let shard = ShardIdentifier::from(fingerprint);
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(fingerprint).with_max_instances(2),
+			0,
+		));
+
+		assert_ok!(confirm_block(&signers[0], shard));
+		assert_ok!(confirm_block(&signers[1], shard));
+		assert_err!(confirm_block(&signers[2], shard), Error::<Test>::TooManyInstancesForShard);
+		// known signers can still confirm
+		assert_ok!(confirm_block(&signers[0], shard));
+		assert_eq!(EnclaveBridge::shard_status(shard).unwrap().len(), 2);
+
+		// lowering the limit keeps the signers which have joined already
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(fingerprint).with_max_instances(1),
+			0,
+		));
+		assert_ok!(confirm_block(&signers[1], shard));
+		assert_eq!(EnclaveBridge::shard_status(shard).unwrap().len(), 2);
+		assert_err!(confirm_block(&signers[2], shard), Error::<Test>::TooManyInstancesForShard);
+	})
+}
+
+#[test]
+fn update_shard_config_with_unattainable_unshield_threshold_fails() {
+	new_test_ext().execute_with(|| {
+		let shard = ShardIdentifier::from(EnclaveFingerprint::from([1u8; 32]));
+
+		assert_err!(
+			EnclaveBridge::update_shard_config(
+				RuntimeOrigin::root(),
+				shard,
+				ShardConfig::new(shard).with_unshield_threshold(MAX_SHARD_STATUS_SIGNER_COUNT + 1),
+				0,
+			),
+			Error::<Test>::InvalidUnshieldThreshold
+		);
+		assert_err!(
+			EnclaveBridge::update_shard_config(
+				RuntimeOrigin::root(),
+				shard,
+				ShardConfig::new(shard).with_max_instances(2).with_unshield_threshold(3),
+				0,
+			),
+			Error::<Test>::InvalidUnshieldThreshold
+		);
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(shard).with_max_instances(2).with_unshield_threshold(2),
+			0,
+		));
+	})
+}
+
+#[test]
+fn maintenance_mode_blocks_state_transitions_of_shard() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let alice = AccountKeyring::Alice.to_account_id();
+		let fingerprint = EnclaveFingerprint::from([1u8; 32]);
+		register_sovereign_test_enclave(&enclave_signer, fingerprint);
+		let shard = ShardIdentifier::from(fingerprint);
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(fingerprint).with_maintenance_mode(true),
+			0,
+		));
+
+		assert_err!(
+			EnclaveBridge::invoke(
+				RuntimeOrigin::signed(alice.clone()),
+				Request { shard, cyphertext: vec![1u8; 4] }
+			),
+			Error::<Test>::InvocationDuringMaintenance
+		);
+		assert_err!(
+			EnclaveBridge::shield_funds(RuntimeOrigin::signed(alice.clone()), shard, vec![], 100),
+			Error::<Test>::ShieldingDuringMaintenance
+		);
+		assert_err!(
+			EnclaveBridge::shield_asset(
+				RuntimeOrigin::signed(alice.clone()),
+				shard,
+				USDT_ASSET_ID,
+				vec![],
+				100
+			),
+			Error::<Test>::ShieldingDuringMaintenance
+		);
+		assert_err!(
+			EnclaveBridge::unshield_funds(
+				RuntimeOrigin::signed(enclave_signer.clone()),
+				shard,
+				alice.clone(),
+				100,
+				H256::default(),
+				0
+			),
+			Error::<Test>::UnshieldingDuringMaintenance
+		);
+		assert_err!(
+			EnclaveBridge::unshield_asset(
+				RuntimeOrigin::signed(enclave_signer.clone()),
+				shard,
+				USDT_ASSET_ID,
+				alice.clone(),
+				100,
+				H256::default(),
+				0
+			),
+			Error::<Test>::UnshieldingDuringMaintenance
+		);
+
+		// the shard config can still be updated to leave maintenance
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::signed(enclave_signer.clone()),
+			shard,
+			ShardConfig::new(fingerprint),
+			0,
+		));
+		assert!(!EnclaveBridge::is_in_maintenance_mode(shard, System::block_number()));
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(alice),
+			Request { shard, cyphertext: vec![1u8; 4] }
+		));
+	})
+}
+
+#[test]
+fn whitelisted_operations_are_allowed_during_maintenance() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(NOW);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let fingerprint = EnclaveFingerprint::from([1u8; 32]);
+		register_sovereign_test_enclave(&enclave_signer, fingerprint);
+		let shard = ShardIdentifier::from(fingerprint);
+		assert_ok!(EnclaveBridge::invoke(
+			RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+			Request { shard, cyphertext: vec![1u8; 4] }
+		));
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(fingerprint).with_maintenance_mode(true),
+			0,
+		));
+
+		assert_ok!(confirm_block(&enclave_signer, shard));
+		assert_eq!(EnclaveBridge::last_processed_parentchain_block(shard).unwrap().block_number, 0);
+		assert_ok!(EnclaveBridge::acknowledge_processed_requests(
+			RuntimeOrigin::signed(enclave_signer.clone()),
+			shard,
+			0
+		));
+		assert!(EnclaveBridge::request_inbox_status(shard).is_empty());
+		assert_ok!(EnclaveBridge::purge_enclave_from_shard_status(
+			RuntimeOrigin::root(),
+			shard,
+			enclave_signer
+		));
+	})
+}
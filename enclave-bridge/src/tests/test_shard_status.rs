@@ -17,8 +17,8 @@
 
 use super::*;
 use crate::{Error, Event as EnclaveBridgeEvent};
-use enclave_bridge_primitives::ShardIdentifier;
-use frame_support::{assert_noop, assert_ok};
+use enclave_bridge_primitives::{ShardIdentifier, MAX_SHARD_STATUS_SIGNER_COUNT};
+use frame_support::{assert_noop, assert_ok, traits::Get};
 use sp_keyring::AccountKeyring;
 use teerex_primitives::EnclaveFingerprint;
 
@@ -117,3 +117,95 @@ fn purge_enclave_from_shard_status_fails_if_not_root() {
 		.is_err());
 	})
 }
+
+#[test]
+fn touch_shard_evicts_inactive_signers() {
+	new_test_ext().execute_with(|| {
+		let enclave_fingerprint = EnclaveFingerprint::default();
+		let shard = ShardIdentifier::default();
+		let signers: Vec<AccountId> = (0..MAX_SHARD_STATUS_SIGNER_COUNT as u8)
+			.map(|i| AccountId::from([i; 32]))
+			.collect();
+		for signer in signers.iter() {
+			assert_ok!(EnclaveBridge::touch_shard(shard, signer, enclave_fingerprint, 1));
+		}
+		let new_signer = AccountKeyring::Eve.to_account_id();
+		assert_noop!(
+			EnclaveBridge::touch_shard(shard, &new_signer, enclave_fingerprint, 2),
+			Error::<Test>::TooManyEnclaves
+		);
+
+		let timeout = ShardSignerInactivityTimeout::get();
+		assert_ok!(EnclaveBridge::touch_shard(shard, &signers[0], enclave_fingerprint, 2));
+		assert_ok!(EnclaveBridge::touch_shard(
+			shard,
+			&new_signer,
+			enclave_fingerprint,
+			2 + timeout
+		));
+
+		let status = EnclaveBridge::shard_status(shard).unwrap();
+		assert_eq!(
+			status.iter().map(|s| s.signer.clone()).collect::<Vec<_>>(),
+			vec![signers[0].clone(), new_signer]
+		);
+		for signer in signers[1..].iter() {
+			let expected_event =
+				RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::PurgedEnclaveFromShardConfig {
+					shard,
+					subject: signer.clone(),
+				});
+			assert!(System::events().iter().any(|a| a.event == expected_event));
+		}
+	})
+}
+
+#[test]
+fn purge_inactive_enclaves_from_shard_status_works() {
+	new_test_ext().execute_with(|| {
+		let enclave_signer_1 = AccountKeyring::Eve.to_account_id();
+		let enclave_signer_2 = AccountKeyring::Ferdie.to_account_id();
+		let enclave_fingerprint = EnclaveFingerprint::default();
+		let shard = ShardIdentifier::default();
+		assert_ok!(EnclaveBridge::touch_shard(shard, &enclave_signer_1, enclave_fingerprint, 1));
+		assert_ok!(EnclaveBridge::touch_shard(shard, &enclave_signer_2, enclave_fingerprint, 5));
+
+		let timeout = ShardSignerInactivityTimeout::get();
+		System::set_block_number(1 + timeout);
+		assert_noop!(
+			EnclaveBridge::purge_inactive_enclaves_from_shard_status(
+				RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+				shard,
+			),
+			Error::<Test>::NoInactiveEnclaveInShardStatus
+		);
+
+		System::set_block_number(2 + timeout);
+		assert_ok!(EnclaveBridge::purge_inactive_enclaves_from_shard_status(
+			RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+			shard,
+		));
+		let expected_event =
+			RuntimeEvent::EnclaveBridge(EnclaveBridgeEvent::PurgedEnclaveFromShardConfig {
+				shard,
+				subject: enclave_signer_1,
+			});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		let status = EnclaveBridge::shard_status(shard).unwrap();
+		assert_eq!(status.len(), 1);
+		assert_eq!(status[0].signer, enclave_signer_2);
+	})
+}
+
+#[test]
+fn purge_inactive_enclaves_from_unknown_shard_errs() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EnclaveBridge::purge_inactive_enclaves_from_shard_status(
+				RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()),
+				ShardIdentifier::default(),
+			),
+			Error::<Test>::ShardNotFound
+		);
+	})
+}
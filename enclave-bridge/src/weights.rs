@@ -50,43 +50,38 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_teerex.
 pub trait WeightInfo {
-	fn invoke() -> Weight;
+	fn invoke(l: u32) -> Weight;
 	fn confirm_processed_parentchain_block() -> Weight;
 	fn shield_funds() -> Weight;
 	fn unshield_funds() -> Weight;
 	fn publish_hash(l: u32, t: u32) -> Weight;
 	fn update_shard_config() -> Weight;
 	fn purge_enclave_from_shard_status() -> Weight;
+	fn shield_asset() -> Weight;
+	fn unshield_asset() -> Weight;
+	fn purge_inactive_enclaves_from_shard_status() -> Weight;
+	fn acknowledge_processed_requests(n: u32) -> Weight;
+	fn sweep_legacy_shard_vault() -> Weight;
+	fn sweep_legacy_shard_vault_asset() -> Weight;
 }
 
 /// Weights for pallet_teerex using the Integritee parachain node and recommended hardware.
 pub struct IntegriteeWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
-	fn invoke() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 21_800 nanoseconds.
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+	fn invoke(l: u32) -> Weight {
+		estimates::invoke(T::DbWeight::get(), l)
 	}
-	/// Storage: Teerex EnclaveIndex (r:1 w:0)
-	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
+
 	fn confirm_processed_parentchain_block() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `242`
-		//  Estimated: `2717`
-		// Minimum execution time: 27_800 nanoseconds.
-		Weight::from_parts(28_700_000, 0u64)
-			.saturating_add(Weight::from_parts(0u64, 2717))
-			.saturating_add(T::DbWeight::get().reads(1))
+		estimates::confirm_processed_parentchain_block(T::DbWeight::get())
 	}
 
 	fn shield_funds() -> Weight {
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+		estimates::shield_funds(T::DbWeight::get())
 	}
 
 	fn unshield_funds() -> Weight {
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+		estimates::unshield_funds(T::DbWeight::get())
 	}
 
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
@@ -132,39 +127,50 @@ impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
 	}
 
 	fn purge_enclave_from_shard_status() -> Weight {
-		Weight::from_parts(10_000_000, 0)
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+		estimates::purge_enclave_from_shard_status(T::DbWeight::get())
+	}
+
+	fn shield_asset() -> Weight {
+		estimates::shield_asset(T::DbWeight::get())
+	}
+
+	fn unshield_asset() -> Weight {
+		estimates::unshield_asset(T::DbWeight::get())
+	}
+
+	fn purge_inactive_enclaves_from_shard_status() -> Weight {
+		estimates::purge_inactive_enclaves_from_shard_status(T::DbWeight::get())
+	}
+
+	fn acknowledge_processed_requests(n: u32) -> Weight {
+		estimates::acknowledge_processed_requests(T::DbWeight::get(), n)
+	}
+
+	fn sweep_legacy_shard_vault() -> Weight {
+		estimates::sweep_legacy_shard_vault(T::DbWeight::get())
+	}
+
+	fn sweep_legacy_shard_vault_asset() -> Weight {
+		estimates::sweep_legacy_shard_vault_asset(T::DbWeight::get())
 	}
 }
 
 /// For tests, weights have been generated with the integritee-node.
 impl WeightInfo for () {
-	fn invoke() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 21_800 nanoseconds.
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+	fn invoke(l: u32) -> Weight {
+		estimates::invoke(RocksDbWeight::get(), l)
 	}
-	/// Storage: Teerex EnclaveIndex (r:1 w:0)
-	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
+
 	fn confirm_processed_parentchain_block() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `242`
-		//  Estimated: `2717`
-		// Minimum execution time: 27_800 nanoseconds.
-		Weight::from_parts(28_700_000, 0u64)
-			.saturating_add(Weight::from_parts(0u64, 2717))
-			.saturating_add(RocksDbWeight::get().reads(1))
+		estimates::confirm_processed_parentchain_block(RocksDbWeight::get())
 	}
 
 	fn shield_funds() -> Weight {
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+		estimates::shield_funds(RocksDbWeight::get())
 	}
 
 	fn unshield_funds() -> Weight {
-		Weight::from_parts(26_600_000, 0u64).saturating_add(Weight::from_parts(0u64, 0))
+		estimates::unshield_funds(RocksDbWeight::get())
 	}
 
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
@@ -203,9 +209,128 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3))
 			.saturating_add(RocksDbWeight::get().writes(2))
 	}
+
 	fn purge_enclave_from_shard_status() -> Weight {
+		estimates::purge_enclave_from_shard_status(RocksDbWeight::get())
+	}
+
+	fn shield_asset() -> Weight {
+		estimates::shield_asset(RocksDbWeight::get())
+	}
+
+	fn unshield_asset() -> Weight {
+		estimates::unshield_asset(RocksDbWeight::get())
+	}
+
+	fn purge_inactive_enclaves_from_shard_status() -> Weight {
+		estimates::purge_inactive_enclaves_from_shard_status(RocksDbWeight::get())
+	}
+
+	fn acknowledge_processed_requests(n: u32) -> Weight {
+		estimates::acknowledge_processed_requests(RocksDbWeight::get(), n)
+	}
+
+	fn sweep_legacy_shard_vault() -> Weight {
+		estimates::sweep_legacy_shard_vault(RocksDbWeight::get())
+	}
+
+	fn sweep_legacy_shard_vault_asset() -> Weight {
+		estimates::sweep_legacy_shard_vault_asset(RocksDbWeight::get())
+	}
+}
+
+/// Hand-written estimates for the calls which haven't been benchmarked yet. They are NOT
+/// generated and must be replaced by the output of running the benchmarks of these calls.
+///
+/// The reads and writes are counted from the worst case of each benchmark. Calls which touch a
+/// shard may evict `MAX_SHARD_STATUS_SIGNER_COUNT - 1` inactive signers, each removing the last
+/// parentchain block the signer has confirmed.
+mod estimates {
+	use frame_support::weights::{RuntimeDbWeight, Weight};
+
+	/// The shard config, the sender and the fee beneficiary, the inbox status and the request.
+	pub fn invoke(db: RuntimeDbWeight, l: u32) -> Weight {
+		Weight::from_parts(26_600_000, 5222)
+			.saturating_add(Weight::from_parts(1_000, 0).saturating_mul(l.into()))
+			.saturating_add(db.reads(4))
+			.saturating_add(db.writes(4))
+	}
+
+	/// The enclave, the shard config, the shard status with evictions, the last parentchain block
+	/// of the sender and of the shard and the hash of the confirmed block.
+	pub fn confirm_processed_parentchain_block(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(40_000_000, 4517)
+			.saturating_add(db.reads(6))
+			.saturating_add(db.writes(13))
+	}
+
+	/// The shard config and the sender and vault accounts.
+	pub fn shield_funds(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(26_600_000, 5206)
+			.saturating_add(db.reads(3))
+			.saturating_add(db.writes(2))
+	}
+
+	/// The shard config, the enclave, the shard status with evictions, the executed and pending
+	/// unshield calls and the vault and beneficiary accounts.
+	pub fn unshield_funds(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(50_000_000, 7006)
+			.saturating_add(db.reads(7))
+			.saturating_add(db.writes(15))
+	}
+
+	/// The shard status and the last parentchain block of the purged enclave.
+	pub fn purge_enclave_from_shard_status(db: RuntimeDbWeight) -> Weight {
 		Weight::from_parts(10_000_000, 0)
-			.saturating_add(RocksDbWeight::get().reads(1))
-			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(2))
+	}
+
+	/// The shard config, the asset location, the asset, the asset accounts of the sender and the
+	/// vault and the vault account.
+	pub fn shield_asset(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(40_000_000, 13603)
+			.saturating_add(db.reads(6))
+			.saturating_add(db.writes(4))
+	}
+
+	/// Like `unshield_funds` with a full shard status and an asset transfer instead, plus the
+	/// asset location for the event.
+	pub fn unshield_asset(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(60_000_000, 15403)
+			.saturating_add(db.reads(10))
+			.saturating_add(db.writes(9))
+	}
+
+	/// The shard status and the last parentchain blocks of `MAX_SHARD_STATUS_SIGNER_COUNT`
+	/// evicted signers.
+	pub fn purge_inactive_enclaves_from_shard_status(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(25_000_000, 1800)
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(11))
+	}
+
+	/// The enclave, the shard config, the shard status with evictions, the inbox status and the
+	/// `n` removed requests.
+	pub fn acknowledge_processed_requests(db: RuntimeDbWeight, n: u32) -> Weight {
+		Weight::from_parts(30_000_000, 1800)
+			.saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(db.reads(4))
+			.saturating_add(db.writes(12))
+			.saturating_add(db.writes((1_u64).saturating_mul(n.into())))
+	}
+
+	/// The legacy vault and vault accounts.
+	pub fn sweep_legacy_shard_vault(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(30_000_000, 5206)
+			.saturating_add(db.reads(2))
+			.saturating_add(db.writes(2))
+	}
+
+	/// The asset, the asset accounts of the legacy vault and the vault and the vault account.
+	pub fn sweep_legacy_shard_vault_asset(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(40_000_000, 7903)
+			.saturating_add(db.reads(4))
+			.saturating_add(db.writes(4))
 	}
 }
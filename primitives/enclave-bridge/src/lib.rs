@@ -20,6 +20,7 @@
 pub use common_primitives::{EnclaveFingerprint, ShardIdentifier};
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use sp_core::{bounded::BoundedVec, ConstU32, H256};
 use sp_std::prelude::*;
 
 pub const ENCLAVE_BRIDGE: &str = "enclave_bridge";
@@ -46,8 +47,14 @@ pub struct ShardConfig<AccountId> {
 	pub max_instances: Option<u32>,
 	/// an optional set of authorities for permissioned sidechains
 	pub authorities: Option<Vec<AccountId>>,
-	/// maintenance mode blocks any upcoming state transitions on this shard
+	/// maintenance mode blocks any upcoming state transitions on this shard. Only updating the
+	/// shard config, purging the shard status, confirming processed parentchain blocks and
+	/// acknowledging processed requests remain possible on chain
 	pub maintenance_mode: bool,
+	/// the number of distinct validateers which must confirm an unshielding before it is executed.
+	/// `None` requires a majority of the signers in the shard status. It may exceed neither
+	/// `max_instances` nor `MAX_SHARD_STATUS_SIGNER_COUNT`
+	pub unshield_threshold: Option<u32>,
 }
 
 impl<AccountId> ShardConfig<AccountId> {
@@ -57,8 +64,81 @@ impl<AccountId> ShardConfig<AccountId> {
 			max_instances: None,
 			authorities: None,
 			maintenance_mode: false,
+			unshield_threshold: None,
 		}
 	}
+
+	pub fn with_max_instances(mut self, max_instances: u32) -> Self {
+		self.max_instances = Some(max_instances);
+		self
+	}
+
+	pub fn with_authorities(mut self, authorities: Vec<AccountId>) -> Self {
+		self.authorities = Some(authorities);
+		self
+	}
+
+	pub fn with_maintenance_mode(mut self, maintenance_mode: bool) -> Self {
+		self.maintenance_mode = maintenance_mode;
+		self
+	}
+
+	pub fn with_unshield_threshold(mut self, threshold: u32) -> Self {
+		self.unshield_threshold = Some(threshold);
+		self
+	}
+}
+
+/// The latest parentchain block L2 has confirmed to have processed for a shard.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct ProcessedParentchainBlock<BlockNumber> {
+	pub block_number: BlockNumber,
+	pub block_hash: H256,
+	/// merkle root of the trusted calls executed while processing the block
+	pub trusted_calls_merkle_root: H256,
+}
+
+/// The range of requests in the inbox of a shard which haven't been acknowledged yet.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct RequestInboxStatus {
+	/// the id the next queued request gets
+	pub next_request_id: u64,
+	/// the id of the oldest request which hasn't been acknowledged yet
+	pub next_unacknowledged_id: u64,
+}
+
+impl RequestInboxStatus {
+	/// the number of requests in the inbox
+	pub fn len(&self) -> u64 {
+		self.next_request_id.saturating_sub(self.next_unacknowledged_id)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+/// Record of an executed unshielding, kept to prevent its replay.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct ExecutedUnshield<BlockNumber> {
+	/// the parentchain block the transfer has been executed in
+	pub executed_at: BlockNumber,
+	/// the number of enclaves which have confirmed the unshielding, including late ones
+	pub confirmations: u64,
+}
+
+/// Confirmations of an unshielding which has not reached its threshold yet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct PendingUnshield<AccountId, Balance, BlockNumber> {
+	pub shard: ShardIdentifier,
+	pub beneficiary: AccountId,
+	pub amount: Balance,
+	/// the latest parentchain block L2 had processed when it issued the unshielding
+	pub issued_at: BlockNumber,
+	/// the distinct enclave signers which have confirmed the unshielding so far
+	pub confirmed_by: BoundedVec<AccountId, ConstU32<MAX_SHARD_STATUS_SIGNER_COUNT>>,
+	/// the confirmations are discarded after this parentchain block
+	pub expires_at: BlockNumber,
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
@@ -23,6 +23,7 @@ use derive_more::From;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
+use sp_core::{bounded::BoundedVec, ConstU32};
 use sp_runtime::MultiSigner;
 use sp_std::prelude::*;
 
@@ -35,6 +36,70 @@ pub enum SgxBuildMode {
 	Production,
 }
 
+/// The SGX type of a platform, as stated in its PCK certificate.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub enum SgxType {
+	Standard,
+	Scalable,
+	ScalableWithIntegrity,
+}
+
+/// The configuration Intel states in PCK certificates of multi-package platforms.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SgxPlatformConfiguration {
+	pub dynamic_platform: bool,
+	pub cached_keys: bool,
+	pub smt_enabled: bool,
+}
+
+/// What the PCK certificate states about the platform an SGX enclave runs on.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SgxPlatformInfo {
+	pub sgx_type: SgxType,
+	pub pce_id: PceId,
+	/// Only stated for multi-package platforms, whose PCK certificates the Platform CA issues.
+	pub platform_instance_id: Option<PlatformInstanceId>,
+	/// Only stated for multi-package platforms, whose PCK certificates the Platform CA issues.
+	pub configuration: Option<SgxPlatformConfiguration>,
+}
+
+/// Restricts the platforms SGX enclaves may register from with DCAP. The default accepts all.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SgxPlatformPolicy {
+	/// Scalable SGX does not protect the integrity of the enclave memory.
+	pub allow_scalable_without_integrity: bool,
+	pub allow_dynamic_platform: bool,
+	pub allow_cached_keys: bool,
+	pub allow_smt: bool,
+}
+
+impl Default for SgxPlatformPolicy {
+	fn default() -> Self {
+		Self {
+			allow_scalable_without_integrity: true,
+			allow_dynamic_platform: true,
+			allow_cached_keys: true,
+			allow_smt: true,
+		}
+	}
+}
+
+impl SgxPlatformPolicy {
+	/// Platforms which don't state their configuration only need to satisfy the SGX type.
+	pub fn accepts(&self, platform: &SgxPlatformInfo) -> bool {
+		if platform.sgx_type == SgxType::Scalable && !self.allow_scalable_without_integrity {
+			return false
+		}
+		match platform.configuration {
+			Some(c) =>
+				(self.allow_dynamic_platform || !c.dynamic_platform) &&
+					(self.allow_cached_keys || !c.cached_keys) &&
+					(self.allow_smt || !c.smt_enabled),
+			None => true,
+		}
+	}
+}
+
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
 pub enum SgxAttestationMethod {
 	Skip { proxied: bool },
@@ -48,6 +113,49 @@ impl Default for SgxAttestationMethod {
 	}
 }
 
+/// How an enclave of any TEE type has been attested
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub enum AttestationMethod {
+	Sgx(SgxAttestationMethod),
+	AmdSevSnp,
+	Eat,
+}
+
+impl From<SgxAttestationMethod> for AttestationMethod {
+	fn from(method: SgxAttestationMethod) -> Self {
+		AttestationMethod::Sgx(method)
+	}
+}
+
+/// Freshness requirements for attestations of a specific `AttestationMethod`
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct AttestationFreshnessPolicy {
+	/// maximal age of an attestation at the time of registration [ms]. Has no effect for
+	/// attestations which are stamped with the registration time, like DCAP quotes
+	pub max_attestation_age: u64,
+	/// an enclave which does not renew its attestation within this period can be unregistered by anyone [ms]
+	pub renewal_period: u64,
+}
+
+impl AttestationFreshnessPolicy {
+	pub fn new(max_attestation_age: u64, renewal_period: u64) -> Self {
+		Self { max_attestation_age, renewal_period }
+	}
+}
+
+/// Security flags which can be overridden for a specific `EnclaveFingerprint`
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SecurityFlags {
+	pub allow_skipping_attestation: bool,
+	pub sgx_allow_debug_mode: bool,
+}
+
+impl SecurityFlags {
+	pub fn new(allow_skipping_attestation: bool, sgx_allow_debug_mode: bool) -> Self {
+		Self { allow_skipping_attestation, sgx_allow_debug_mode }
+	}
+}
+
 const SGX_REPORT_DATA_SIZE: usize = 64;
 #[derive(Debug, Encode, Decode, Copy, Clone, PartialEq, Eq, TypeInfo)]
 #[repr(C)]
@@ -131,9 +239,31 @@ pub enum TcbStatus {
 	Revoked,
 }
 
-#[derive(Encode, Decode, Copy, Clone, PartialEq, From, Eq, sp_core::RuntimeDebug, TypeInfo)]
+/// The `tcbStatus` values of Intel's QE identity, besides `UpToDate`, under which a quoting
+/// enclave is trusted. The default only trusts `UpToDate` levels. `Revoked` levels and statuses
+/// which only apply to platform TCBs are never trusted.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct QeTcbStatusPolicy {
+	pub accept_sw_hardening_needed: bool,
+	pub accept_out_of_date: bool,
+}
+
+impl QeTcbStatusPolicy {
+	pub fn accepts(&self, status: TcbStatus) -> bool {
+		match status {
+			TcbStatus::UpToDate => true,
+			TcbStatus::SWHardeningNeeded => self.accept_sw_hardening_needed,
+			TcbStatus::OutOfDate => self.accept_out_of_date,
+			_ => false,
+		}
+	}
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, From, Eq, sp_core::RuntimeDebug, TypeInfo)]
 pub enum MultiEnclave<Url> {
 	Sgx(SgxEnclave<Url>),
+	Snp(SnpEnclave<Url>),
+	Eat(EatEnclave<Url>),
 }
 
 impl<Url> MultiEnclave<Url>
@@ -145,12 +275,20 @@ where
 			MultiEnclave::Sgx(enclave) => AnySigner::Opaque(
 				OpaqueSigner::try_from(enclave.mr_signer.to_vec()).unwrap_or_default(),
 			),
+			MultiEnclave::Snp(enclave) => AnySigner::Opaque(
+				OpaqueSigner::try_from(enclave.id_key_digest.to_vec()).unwrap_or_default(),
+			),
+			MultiEnclave::Eat(enclave) => AnySigner::Opaque(
+				OpaqueSigner::try_from(enclave.key_id.to_vec()).unwrap_or_default(),
+			),
 		}
 	}
 
 	pub fn fingerprint(&self) -> EnclaveFingerprint {
 		match self {
 			MultiEnclave::Sgx(enclave) => EnclaveFingerprint::from(enclave.mr_enclave),
+			MultiEnclave::Snp(enclave) => enclave.fingerprint(),
+			MultiEnclave::Eat(enclave) => enclave.fingerprint(),
 		}
 	}
 
@@ -161,18 +299,36 @@ where
 					AnySigner::from(MultiSigner::from(sp_core::ed25519::Public::from_raw(pubkey))),
 				None => AnySigner::try_from(enclave.report_data.d).unwrap_or_default(),
 			},
+			MultiEnclave::Snp(enclave) => AnySigner::from(MultiSigner::from(
+				sp_core::ed25519::Public::from_raw(enclave.pubkey()),
+			)),
+			MultiEnclave::Eat(enclave) => AnySigner::from(MultiSigner::from(
+				sp_core::ed25519::Public::from_raw(enclave.nonce),
+			)),
 		}
 	}
 
 	pub fn instance_url(&self) -> Option<Url> {
 		match self {
 			MultiEnclave::Sgx(enclave) => enclave.url.clone(),
+			MultiEnclave::Snp(enclave) => enclave.url.clone(),
+			MultiEnclave::Eat(enclave) => enclave.url.clone(),
 		}
 	}
 
 	pub fn attestation_timestamp(&self) -> u64 {
 		match self {
 			MultiEnclave::Sgx(enclave) => enclave.timestamp,
+			MultiEnclave::Snp(enclave) => enclave.timestamp,
+			MultiEnclave::Eat(enclave) => enclave.timestamp,
+		}
+	}
+
+	pub fn attestation_method(&self) -> AttestationMethod {
+		match self {
+			MultiEnclave::Sgx(enclave) => enclave.attestation_method.into(),
+			MultiEnclave::Snp(_) => AttestationMethod::AmdSevSnp,
+			MultiEnclave::Eat(_) => AttestationMethod::Eat,
 		}
 	}
 
@@ -183,6 +339,7 @@ where
 				SgxAttestationMethod::Skip { proxied: true } |
 					SgxAttestationMethod::Dcap { proxied: true }
 			),
+			MultiEnclave::Snp(_) | MultiEnclave::Eat(_) => false,
 		}
 	}
 }
@@ -197,6 +354,9 @@ pub struct SgxEnclave<Url> {
 	pub build_mode: SgxBuildMode,
 	pub attestation_method: SgxAttestationMethod,
 	pub status: SgxStatus,
+	/// `None` unless attested with DCAP. Enclaves registered before the platform was recorded
+	/// lack it until they re-register.
+	pub platform: Option<SgxPlatformInfo>,
 }
 
 impl<Url> SgxEnclave<Url> {
@@ -217,6 +377,7 @@ impl<Url> SgxEnclave<Url> {
 			build_mode,
 			attestation_method: SgxAttestationMethod::default(),
 			status,
+			platform: None,
 		}
 	}
 
@@ -245,6 +406,11 @@ impl<Url> SgxEnclave<Url> {
 		self
 	}
 
+	pub fn with_platform(mut self, platform: SgxPlatformInfo) -> Self {
+		self.platform = Some(platform);
+		self
+	}
+
 	pub fn with_pubkey(mut self, pubkey: &[u8]) -> Self {
 		let mut data = SgxReportData::default();
 		data.d[..pubkey.len()].copy_from_slice(pubkey);
@@ -253,6 +419,165 @@ impl<Url> SgxEnclave<Url> {
 	}
 }
 
+/// The TCB version of an AMD SEV-SNP platform, i.e. the security patch levels of its firmware.
+#[derive(Encode, Decode, Default, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SnpTcbVersion {
+	pub bootloader: u8,
+	pub tee: u8,
+	pub snp: u8,
+	pub microcode: u8,
+}
+
+impl SnpTcbVersion {
+	pub fn new(bootloader: u8, tee: u8, snp: u8, microcode: u8) -> Self {
+		Self { bootloader, tee, snp, microcode }
+	}
+
+	/// Whether every component is at least the one of `min`.
+	pub fn is_at_least(&self, min: &SnpTcbVersion) -> bool {
+		self.bootloader >= min.bootloader &&
+			self.tee >= min.tee &&
+			self.snp >= min.snp &&
+			self.microcode >= min.microcode
+	}
+}
+
+/// An AMD SEV-SNP guest, which has been attested with a report signed by the VCEK of its chip.
+/// It is always sovereign: the first 32 bytes of the report data are the public key of the
+/// account that registered it.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct SnpEnclave<Url> {
+	pub report_data: [u8; 64],
+	pub measurement: SnpMeasurement,
+	/// Digest of the key which signed the ID block of the guest
+	pub id_key_digest: [u8; 48],
+	pub guest_svn: u32,
+	pub policy: u64,
+	pub reported_tcb: SnpTcbVersion,
+	pub timestamp: u64,   // unix epoch in milliseconds
+	pub url: Option<Url>, // utf8 encoded url
+}
+
+impl<Url> SnpEnclave<Url> {
+	pub fn new(
+		report_data: [u8; 64],
+		measurement: SnpMeasurement,
+		id_key_digest: [u8; 48],
+		guest_svn: u32,
+		policy: u64,
+		reported_tcb: SnpTcbVersion,
+		timestamp: u64,
+	) -> Self {
+		SnpEnclave {
+			report_data,
+			measurement,
+			id_key_digest,
+			guest_svn,
+			policy,
+			reported_tcb,
+			timestamp,
+			url: None,
+		}
+	}
+
+	/// The measurement has 48 bytes, so the fingerprint is its hash.
+	pub fn fingerprint(&self) -> EnclaveFingerprint {
+		EnclaveFingerprint::from(sp_core::hashing::blake2_256(&self.measurement))
+	}
+
+	pub fn pubkey(&self) -> [u8; 32] {
+		let mut pubkey = [0u8; 32];
+		pubkey.copy_from_slice(&self.report_data[..32]);
+		pubkey
+	}
+
+	pub fn with_url(mut self, url: Url) -> Self {
+		self.url = Some(url);
+		self
+	}
+}
+
+/// The identifier of an EAT profile, e.g. `tag:arm.com,2023:realm#1.0.0` or an OID.
+pub type EatProfile = BoundedVec<u8, ConstU32<MAX_EAT_PROFILE_LEN>>;
+/// The blake2_256 hash of the public key of a trusted EAT signer.
+pub type EatKeyId = [u8; 32];
+/// Measurement claims are digests, 64 bytes fit SHA-512.
+pub type EatMeasurement = BoundedVec<u8, ConstU32<MAX_EAT_MEASUREMENT_LEN>>;
+
+pub const MAX_EAT_PROFILE_LEN: u32 = 64;
+pub const MAX_EAT_MEASUREMENT_LEN: u32 = 64;
+/// An uncompressed P-384 point.
+pub const MAX_EAT_PUBLIC_KEY_LEN: u32 = 97;
+
+/// The COSE algorithms EAT signers may use.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub enum EatSignatureAlgorithm {
+	Es256,
+	Es384,
+}
+
+impl EatSignatureAlgorithm {
+	/// The identifier in the COSE algorithms registry.
+	pub fn cose_alg(&self) -> i64 {
+		match self {
+			EatSignatureAlgorithm::Es256 => -7,
+			EatSignatureAlgorithm::Es384 => -35,
+		}
+	}
+}
+
+/// A key which is trusted to sign attestation tokens of a profile, e.g. the key of a Veraison
+/// verifier or of a platform attestation service.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct EatTrustedKey {
+	pub algorithm: EatSignatureAlgorithm,
+	/// The uncompressed SEC1 encoded public key
+	pub public_key: BoundedVec<u8, ConstU32<MAX_EAT_PUBLIC_KEY_LEN>>,
+	/// The claim which holds the measurement of the enclave, it differs between profiles.
+	pub measurement_claim: i64,
+}
+
+impl EatTrustedKey {
+	pub fn key_id(&self) -> EatKeyId {
+		sp_core::hashing::blake2_256(&self.public_key)
+	}
+}
+
+/// An enclave attested by an Entity Attestation Token. It is always sovereign: the nonce claim is
+/// the public key of the account that registered it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+pub struct EatEnclave<Url> {
+	pub profile: EatProfile,
+	/// The trusted key which signed the token
+	pub key_id: EatKeyId,
+	pub measurement: EatMeasurement,
+	pub nonce: [u8; 32],
+	pub timestamp: u64,   // unix epoch in milliseconds
+	pub url: Option<Url>, // utf8 encoded url
+}
+
+impl<Url> EatEnclave<Url> {
+	pub fn new(
+		profile: EatProfile,
+		key_id: EatKeyId,
+		measurement: EatMeasurement,
+		nonce: [u8; 32],
+		timestamp: u64,
+	) -> Self {
+		EatEnclave { profile, key_id, measurement, nonce, timestamp, url: None }
+	}
+
+	/// Measurements differ in length between profiles, so the fingerprint is their hash.
+	pub fn fingerprint(&self) -> EnclaveFingerprint {
+		EnclaveFingerprint::from(sp_core::hashing::blake2_256(&self.measurement))
+	}
+
+	pub fn with_url(mut self, url: Url) -> Self {
+		self.url = Some(url);
+		self
+	}
+}
+
 /// The list of valid TCBs for an enclave.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
 pub struct QeTcb {
@@ -350,6 +675,13 @@ impl TcbVersionStatus {
 		);
 		self.pcesvn <= examinee.pcesvn
 	}
+
+	/// Whether all components of `other` are at least the ones of this level. This is the partial
+	/// order Intel compares TCB levels with. Unlike `verify_examinee` it does not log.
+	fn is_covered_by(&self, other: &TcbVersionStatus) -> bool {
+		self.cpusvn.iter().zip(other.cpusvn.iter()).all(|(s, o)| s <= o) &&
+			self.pcesvn <= other.pcesvn
+	}
 }
 
 /// This represents all the collateral data that we need to store on chain in order to verify
@@ -374,13 +706,31 @@ impl SgxTcbInfoOnChain {
 	pub fn verify_examinee(&self, examinee: &TcbVersionStatus) -> Option<SgxStatus> {
 		log::debug!(target: TEEREX, "TcbInfoOnChain::verify_examinee: self={:?}", &self,);
 		log::debug!(target: TEEREX, "TcbInfoOnChain::verify_examinee: examinee={:?}", &examinee,);
-		for tb in &self.tcb_levels {
+		self.matching_tcb_level(examinee).map(|tb| tb.tcb_status.into())
+	}
+
+	pub fn tcb_levels(&self) -> &[TcbVersionStatus] {
+		&self.tcb_levels
+	}
+
+	/// The first level the examinee satisfies, as in Intel's DCAP implementation. If the levels are
+	/// ordered, see `tcb_levels_are_ordered`, no other level the examinee satisfies is above it.
+	pub fn matching_tcb_level(&self, examinee: &TcbVersionStatus) -> Option<&TcbVersionStatus> {
+		self.tcb_levels.iter().find(|tb| {
 			log::debug!(target: TEEREX, "TcbInfoOnChain::verify_examinee: tb={:?}", &tb,);
-			if tb.verify_examinee(examinee) {
-				return Some(tb.tcb_status.into())
-			}
-		}
-		None
+			tb.verify_examinee(examinee)
+		})
+	}
+
+	/// Whether no level is covered by a later one, as Intel lists them. Levels are only partially
+	/// ordered: a level is above another if all its components are at least the ones of the
+	/// other. Incomparable levels may be listed in any order. Collateral whose levels are not
+	/// ordered must be rejected, as `verify_examinee` would pick a lower level than the examinee
+	/// is entitled to, or a level with a better status than it deserves.
+	pub fn tcb_levels_are_ordered(&self) -> bool {
+		self.tcb_levels.iter().enumerate().all(|(i, level)| {
+			self.tcb_levels[i + 1..].iter().all(|later| !level.is_covered_by(later))
+		})
 	}
 }
 
@@ -389,6 +739,9 @@ pub type MrEnclave = [u8; 32];
 pub type Fmspc = [u8; 6];
 pub type Cpusvn = [u8; 16];
 pub type Pcesvn = u16;
+pub type PceId = [u8; 2];
+pub type PlatformInstanceId = [u8; 16];
+pub type SnpMeasurement = [u8; 48];
 
 #[derive(Encode, Decode, Default, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
 pub struct EnclaveInstanceAddress<AccountId> {
@@ -401,6 +754,7 @@ pub struct EnclaveInstanceAddress<AccountId> {
 mod tests {
 	use super::*;
 	use hex_literal::hex;
+	use proptest::prelude::*;
 
 	#[test]
 	fn tcb_full_is_valid() {
@@ -429,4 +783,173 @@ mod tests {
 			TcbStatus::UpToDate
 		)));
 	}
+
+	#[test]
+	fn sgx_platform_policy_works() {
+		let multi_package = SgxPlatformInfo {
+			sgx_type: SgxType::ScalableWithIntegrity,
+			pce_id: [0, 0],
+			platform_instance_id: Some([1; 16]),
+			configuration: Some(SgxPlatformConfiguration {
+				dynamic_platform: true,
+				cached_keys: true,
+				smt_enabled: true,
+			}),
+		};
+		let single_package = SgxPlatformInfo {
+			sgx_type: SgxType::Standard,
+			pce_id: [0, 0],
+			platform_instance_id: None,
+			configuration: None,
+		};
+		let scalable = SgxPlatformInfo { sgx_type: SgxType::Scalable, ..single_package };
+
+		let default = SgxPlatformPolicy::default();
+		assert!(default.accepts(&multi_package));
+		assert!(default.accepts(&single_package));
+		assert!(default.accepts(&scalable));
+
+		let no_smt = SgxPlatformPolicy { allow_smt: false, ..default };
+		assert!(!no_smt.accepts(&multi_package));
+		assert!(no_smt.accepts(&single_package));
+		let smt_disabled = SgxPlatformInfo {
+			configuration: Some(SgxPlatformConfiguration {
+				smt_enabled: false,
+				..multi_package.configuration.unwrap()
+			}),
+			..multi_package
+		};
+		assert!(no_smt.accepts(&smt_disabled));
+		assert!(!SgxPlatformPolicy { allow_cached_keys: false, ..default }.accepts(&smt_disabled));
+		assert!(
+			!SgxPlatformPolicy { allow_dynamic_platform: false, ..default }.accepts(&smt_disabled)
+		);
+
+		let integrity_only =
+			SgxPlatformPolicy { allow_scalable_without_integrity: false, ..default };
+		assert!(!integrity_only.accepts(&scalable));
+		assert!(integrity_only.accepts(&multi_package));
+		assert!(integrity_only.accepts(&single_package));
+	}
+
+	fn tcb_status() -> impl Strategy<Value = TcbStatus> {
+		prop_oneof![
+			Just(TcbStatus::Unknown),
+			Just(TcbStatus::UpToDate),
+			Just(TcbStatus::SWHardeningNeeded),
+			Just(TcbStatus::ConfigurationAndSWHardeningNeeded),
+			Just(TcbStatus::OutOfDate),
+			Just(TcbStatus::OutOfDateConfigurationNeeded),
+			Just(TcbStatus::Revoked),
+		]
+	}
+
+	// Only two cpusvn components and the pcesvn vary, within small ranges, such that levels are
+	// frequently comparable.
+	fn tcb_level() -> impl Strategy<Value = TcbVersionStatus> {
+		(0u8..3, 0u8..3, 0u16..3, tcb_status()).prop_map(|(svn0, svn1, pcesvn, status)| {
+			let mut cpusvn = [0u8; 16];
+			cpusvn[0] = svn0;
+			cpusvn[1] = svn1;
+			TcbVersionStatus::new(cpusvn, pcesvn, status)
+		})
+	}
+
+	/// Component-wise comparison, independent of the implementation under test.
+	fn covers(upper: &TcbVersionStatus, lower: &TcbVersionStatus) -> bool {
+		(0..16).all(|i| upper.cpusvn[i] >= lower.cpusvn[i]) && upper.pcesvn >= lower.pcesvn
+	}
+
+	fn component_sum(level: &TcbVersionStatus) -> u32 {
+		level.cpusvn.iter().map(|svn| *svn as u32).sum::<u32>() + level.pcesvn as u32
+	}
+
+	/// Levels in an order Intel may list them. Descending by the sum of the components is one, as
+	/// a level above another has a strictly greater sum.
+	fn ordered_tcb_levels() -> impl Strategy<Value = Vec<TcbVersionStatus>> {
+		prop::collection::vec(tcb_level(), 0..16).prop_map(|mut levels| {
+			levels.sort_by_key(|level| core::cmp::Reverse(component_sum(level)));
+			let mut distinct: Vec<TcbVersionStatus> = Vec::new();
+			for level in levels {
+				if !distinct.iter().any(|d| d.cpusvn == level.cpusvn && d.pcesvn == level.pcesvn) {
+					distinct.push(level);
+				}
+			}
+			distinct
+		})
+	}
+
+	proptest! {
+		#[test]
+		fn matching_tcb_level_is_the_first_one_covered_by_the_examinee(
+			levels in ordered_tcb_levels(),
+			examinee in tcb_level(),
+		) {
+			let tcb_info = SgxTcbInfoOnChain::new(0, 0, levels.clone());
+			prop_assert!(tcb_info.tcb_levels_are_ordered());
+
+			let expected = levels.iter().find(|level| covers(&examinee, level));
+			let matching = tcb_info.matching_tcb_level(&examinee);
+			prop_assert_eq!(matching, expected);
+			prop_assert_eq!(
+				tcb_info.verify_examinee(&examinee),
+				expected.map(|level| level.tcb_status.into())
+			);
+
+			// no other level the examinee covers is above the matching one
+			if let Some(matching) = matching {
+				for level in levels.iter().filter(|level| covers(&examinee, level)) {
+					prop_assert!(level == matching || !covers(level, matching));
+				}
+			}
+		}
+
+		#[test]
+		fn tcb_levels_are_ordered_iff_no_level_is_covered_by_a_later_one(
+			levels in prop::collection::vec(tcb_level(), 0..8),
+		) {
+			let expected = (0..levels.len())
+				.all(|i| (i + 1..levels.len()).all(|j| !covers(&levels[j], &levels[i])));
+			prop_assert_eq!(SgxTcbInfoOnChain::new(0, 0, levels).tcb_levels_are_ordered(), expected);
+		}
+	}
+
+	#[test]
+	fn incomparable_tcb_levels_may_be_listed_in_any_order() {
+		let a =
+			TcbVersionStatus::new(hex!("02000000000000000000000000000000"), 7, TcbStatus::UpToDate);
+		let b = TcbVersionStatus::new(
+			hex!("01050000000000000000000000000000"),
+			7,
+			TcbStatus::OutOfDate,
+		);
+		assert!(SgxTcbInfoOnChain::new(0, 0, vec![a.clone(), b.clone()]).tcb_levels_are_ordered());
+		assert!(SgxTcbInfoOnChain::new(0, 0, vec![b.clone(), a.clone()]).tcb_levels_are_ordered());
+
+		// an examinee covering `b` only matches `b`, even though `a` is lexicographically greater
+		let examinee =
+			TcbVersionStatus::new(hex!("01050000000000000000000000000000"), 7, TcbStatus::Unknown);
+		let tcb_info = SgxTcbInfoOnChain::new(0, 0, vec![a, b.clone()]);
+		assert_eq!(tcb_info.matching_tcb_level(&examinee), Some(&b));
+	}
+
+	#[test]
+	fn tcb_level_listed_below_a_level_it_is_above_is_not_ordered() {
+		let low = TcbVersionStatus::new(
+			hex!("01010000000000000000000000000000"),
+			7,
+			TcbStatus::OutOfDate,
+		);
+		let high =
+			TcbVersionStatus::new(hex!("01020000000000000000000000000000"), 7, TcbStatus::UpToDate);
+		assert!(!SgxTcbInfoOnChain::new(0, 0, vec![low, high]).tcb_levels_are_ordered());
+	}
+
+	#[test]
+	fn duplicate_tcb_levels_are_not_ordered() {
+		let level =
+			TcbVersionStatus::new(hex!("11110204018007000000000000000000"), 7, TcbStatus::UpToDate);
+		let tcb_info = SgxTcbInfoOnChain::new(0, 0, vec![level.clone(), level]);
+		assert!(!tcb_info.tcb_levels_are_ordered());
+	}
 }
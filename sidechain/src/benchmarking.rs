@@ -20,10 +20,13 @@
 #![cfg(any(test, feature = "runtime-benchmarks"))]
 
 use super::*;
+use enclave_bridge_primitives::MAX_SHARD_STATUS_SIGNER_COUNT;
 use frame_benchmarking::{account, benchmarks};
 use frame_system::RawOrigin;
+use pallet_enclave_bridge::{ShardSignerStatus, ShardSignerStatusVec};
 use pallet_teerex::Pallet as Teerex;
 use parity_scale_codec::Encode;
+use sp_runtime::traits::Saturating;
 use test_utils::test_data::ias::*;
 
 fn assert_latest_worker_update<T: Config>(sender: &T::AccountId, shard: &ShardIdentifier) {
@@ -48,14 +51,38 @@ fn add_enclaves_to_registry<T: Config>(accounts: &[T::AccountId]) {
 	}
 }
 
+/// Fills the shard status with `count` signers and advances the current block until they have
+/// exceeded the `ShardSignerInactivityTimeout`.
+fn add_inactive_signers_to_shard_status<T: Config>(shard: ShardIdentifier, count: u32) {
+	let inactive_since = frame_system::Pallet::<T>::block_number();
+	let signer_statuses: Vec<ShardSignerStatus<T>> = (0..count)
+		.map(|n| ShardSignerStatus::<T> {
+			signer: account("inactive", n, n),
+			fingerprint: shard,
+			last_activity: inactive_since,
+		})
+		.collect();
+	pallet_enclave_bridge::ShardStatus::<T>::insert(
+		shard,
+		ShardSignerStatusVec::<T>::try_from(signer_statuses).unwrap(),
+	);
+	frame_system::Pallet::<T>::set_block_number(
+		inactive_since
+			.saturating_add(T::ShardSignerInactivityTimeout::get())
+			.saturating_add(1u32.into()),
+	);
+}
+
 benchmarks! {
 	// Benchmark `confirm_imported_sidechain_block` with the worst possible conditions:
 	// * sender enclave is registered
+	// * all other signers of a full shard status are evicted for inactivity
 	confirm_imported_sidechain_block {
 		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
 		add_enclaves_to_registry::<T>(&accounts);
 
 		let shard: ShardIdentifier = H256::from_slice(&TEST4_SETUP.mrenclave);
+		add_inactive_signers_to_shard_status::<T>(shard, MAX_SHARD_STATUS_SIGNER_COUNT - 1);
 		let ancestor = SidechainBlockConfirmation {
 			block_number: 2,
 			block_header_hash: [2; 32].into()
@@ -20,7 +20,7 @@
 use enclave_bridge_primitives::ShardIdentifier;
 use frame_support::dispatch::DispatchResultWithPostInfo;
 use frame_system::{self};
-use pallet_enclave_bridge::Pallet as EnclaveBridge;
+use pallet_enclave_bridge::{Pallet as EnclaveBridge, ShardOperation};
 use sidechain_primitives::{SidechainBlockConfirmation, SidechainBlockNumber};
 use sp_core::H256;
 use sp_std::{prelude::*, str, vec};
@@ -78,6 +78,8 @@ pub mod pallet {
 		AncestorHashMismatch,
 		/// sender hasn't provided an ancestor although an ancestor has been finalized
 		AncestorMissing,
+		/// Sidechain block confirmations are rejected while the shard is in maintenance mode
+		SidechainBlockConfirmationDuringMaintenance,
 	}
 
 	#[pallet::storage]
@@ -97,11 +99,20 @@ pub mod pallet {
 			finalization_candidate: SidechainBlockConfirmation,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				EnclaveBridge::<T>::is_allowed_during_maintenance(
+					shard,
+					ShardOperation::ConfirmSidechainBlock,
+					current_block_number
+				),
+				<Error<T>>::SidechainBlockConfirmationDuringMaintenance
+			);
 			let (_enclave, shard_status) =
 				EnclaveBridge::<T>::get_sovereign_enclave_and_touch_shard(
 					&sender,
 					shard,
-					<frame_system::Pallet<T>>::block_number(),
+					current_block_number,
 				)?;
 
 			// TODO: Simple but robust logic for now:
@@ -17,7 +17,11 @@
 
 // Creating mock runtime here
 use crate as pallet_sidechain;
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64},
+	PalletId,
+};
 use frame_system as system;
 use pallet_sidechain::Config;
 use sp_core::H256;
@@ -27,6 +31,8 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
 	BuildStorage,
 };
+use staging_xcm::latest::Location;
+use xcm_primitives::AssetLocationGetter;
 
 pub type Signature = sp_runtime::MultiSignature;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
@@ -51,6 +57,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Teerex: pallet_teerex::{Pallet, Call, Storage, Event<T>},
 		EnclaveBridge: pallet_enclave_bridge::{Pallet, Call, Storage, Event<T>},
@@ -117,6 +124,29 @@ parameter_types! {
 
 pub type Moment = u64;
 
+impl pallet_assets::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u64;
+	type RemoveItemsLimit = ConstU32<5>;
+	type AssetId = u32;
+	type AssetIdParameter = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU64<1>;
+	type AssetAccountDeposit = ConstU64<10>;
+	type MetadataDepositBase = ConstU64<1>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type ApprovalDeposit = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
 impl pallet_timestamp::Config for Test {
 	type Moment = Moment;
 	type OnTimestampSet = ();
@@ -133,12 +163,56 @@ impl pallet_teerex::Config for Test {
 	type MomentsPerDay = MomentsPerDay;
 	type WeightInfo = ();
 	type MaxAttestationRenewalPeriod = MaxAttestationRenewalPeriod;
+	type SecurityFlagsOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollateralOrigin = frame_system::EnsureRoot<AccountId>;
+	type TrustAnchorOrigin = frame_system::EnsureRoot<AccountId>;
+	type AttestationPolicyOrigin = frame_system::EnsureRoot<AccountId>;
+}
+
+parameter_types! {
+	pub const EnclaveBridgePalletId: PalletId = PalletId(*b"py/encbr");
+	pub const UnshieldConfirmationTimeout: u32 = 10;
+	pub const UnshieldRetentionPeriod: u32 = 100;
+	pub const ShardSignerInactivityTimeout: u32 = 50;
+	pub const MaxRequestSize: u32 = 2048;
+	pub const MaxInboxLength: u32 = 3;
+}
+
+/// Asset shielding is not used in sidechain tests.
+pub struct NoAssetLocations;
+impl AssetLocationGetter<u32> for NoAssetLocations {
+	fn get_asset_location(_asset_id: u32) -> Option<Location> {
+		None
+	}
+
+	fn get_asset_id(_asset_location: &Location) -> Option<u32> {
+		None
+	}
+}
+
+/// The enclave-bridge benchmarks are not run with this runtime.
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_enclave_bridge::BenchmarkHelper<u32> for NoAssetLocations {
+	fn get_registered_asset() -> u32 {
+		0
+	}
 }
 
 impl pallet_enclave_bridge::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type PalletId = EnclaveBridgePalletId;
 	type Currency = Balances;
+	type Fungibles = Assets;
+	type AssetLocationGetter = NoAssetLocations;
 	type WeightInfo = ();
+	type UnshieldConfirmationTimeout = UnshieldConfirmationTimeout;
+	type UnshieldRetentionPeriod = UnshieldRetentionPeriod;
+	type ShardSignerInactivityTimeout = ShardSignerInactivityTimeout;
+	type MaxRequestSize = MaxRequestSize;
+	type RequestFee = ();
+	type MaxInboxLength = MaxInboxLength;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = NoAssetLocations;
 }
 
 parameter_types! {
@@ -148,6 +222,7 @@ parameter_types! {
 
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type PalletId = EnclaveBridgePalletId;
 	type WeightInfo = ();
 }
 
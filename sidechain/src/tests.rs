@@ -362,6 +362,50 @@ fn two_sidechains_with_different_fingerprint_works() {
 	})
 }
 
+#[test]
+fn confirm_imported_sidechain_block_during_maintenance_errs() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP);
+		run_to_block(1);
+		let enclave_signer = AccountKeyring::Eve.to_account_id();
+		let enclave =
+			register_sovereign_test_enclave(&enclave_signer, EnclaveFingerprint::default());
+		let shard = ShardIdentifier::from(enclave.fingerprint());
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(enclave.fingerprint()).with_maintenance_mode(true),
+			0,
+		));
+
+		let block_a =
+			SidechainBlockConfirmation { block_number: 1, block_header_hash: H256::default() };
+		assert_err!(
+			Sidechain::confirm_imported_sidechain_block(
+				RuntimeOrigin::signed(enclave_signer.clone()),
+				shard,
+				None,
+				block_a,
+			),
+			Error::<Test>::SidechainBlockConfirmationDuringMaintenance
+		);
+		assert!(Sidechain::latest_sidechain_block_confirmation(shard).is_none());
+
+		assert_ok!(EnclaveBridge::update_shard_config(
+			RuntimeOrigin::root(),
+			shard,
+			ShardConfig::new(enclave.fingerprint()),
+			0,
+		));
+		assert_ok!(Sidechain::confirm_imported_sidechain_block(
+			RuntimeOrigin::signed(enclave_signer),
+			shard,
+			None,
+			block_a,
+		));
+	})
+}
+
 fn register_ias_enclave7() {
 	register_ias_enclave(TEST7_SIGNER_PUB, TEST7_CERT);
 }
@@ -30,17 +30,23 @@ pub trait WeightInfo {
 pub struct IntegriteeWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
 	fn confirm_imported_sidechain_block() -> Weight {
-		Weight::from_parts(46_200_000, 0u64)
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(2))
+		// Not benchmarked yet, hand-written estimate to be replaced by running the benchmark.
+		// Reads the shard config, the enclave, the shard status and the latest confirmation and
+		// evicts `MAX_SHARD_STATUS_SIGNER_COUNT - 1` inactive signers from the shard status.
+		Weight::from_parts(55_000_000, 1800)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(12))
 	}
 }
 
 // For tests
 impl WeightInfo for () {
 	fn confirm_imported_sidechain_block() -> Weight {
-		Weight::from_parts(46_200_000, 0u64)
-			.saturating_add(RocksDbWeight::get().reads(1))
-			.saturating_add(RocksDbWeight::get().writes(2))
+		// Not benchmarked yet, hand-written estimate to be replaced by running the benchmark.
+		// Reads the shard config, the enclave, the shard status and the latest confirmation and
+		// evicts `MAX_SHARD_STATUS_SIGNER_COUNT - 1` inactive signers from the shard status.
+		Weight::from_parts(55_000_000, 1800)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(12))
 	}
 }
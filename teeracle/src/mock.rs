@@ -134,6 +134,10 @@ impl pallet_teerex::Config for Test {
 	type MomentsPerDay = MomentsPerDay;
 	type WeightInfo = ();
 	type MaxAttestationRenewalPeriod = MaxAttestationRenewalPeriod;
+	type SecurityFlagsOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollateralOrigin = frame_system::EnsureRoot<AccountId>;
+	type TrustAnchorOrigin = frame_system::EnsureRoot<AccountId>;
+	type AttestationPolicyOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 impl Config for Test {
@@ -0,0 +1,197 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! A minimal decoder for the subset of CBOR (RFC 8949) that attestation tokens use.
+//!
+//! Only definite lengths are supported, which COSE requires for the signed structures anyway.
+//! Byte and text strings borrow from the input.
+
+use crate::Error;
+use sp_std::{boxed::Box, prelude::*};
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// Tokens are flat, this only prevents a stack overflow on malicious input.
+const MAX_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value<'a> {
+	Unsigned(u64),
+	/// Represents `-1 - n`
+	Negative(u64),
+	Bytes(&'a [u8]),
+	Text(&'a str),
+	Array(Vec<Value<'a>>),
+	Map(Vec<(Value<'a>, Value<'a>)>),
+	Tag(u64, Box<Value<'a>>),
+	/// Simple values and floats, which are not interpreted
+	Simple(u64),
+}
+
+impl<'a> Value<'a> {
+	pub fn as_bytes(&self) -> Option<&'a [u8]> {
+		match self {
+			Value::Bytes(b) => Some(b),
+			_ => None,
+		}
+	}
+
+	pub fn as_text(&self) -> Option<&'a str> {
+		match self {
+			Value::Text(t) => Some(t),
+			_ => None,
+		}
+	}
+
+	pub fn as_i64(&self) -> Option<i64> {
+		match self {
+			Value::Unsigned(n) => i64::try_from(*n).ok(),
+			Value::Negative(n) => i64::try_from(*n).ok().map(|n| -1 - n),
+			_ => None,
+		}
+	}
+
+	/// Looks up an integer key in a map.
+	pub fn get(&self, key: i64) -> Option<&Value<'a>> {
+		match self {
+			Value::Map(entries) =>
+				entries.iter().find(|(k, _)| k.as_i64() == Some(key)).map(|(_, v)| v),
+			_ => None,
+		}
+	}
+}
+
+/// Decodes a single item, which must span the whole input.
+pub fn decode(input: &[u8]) -> Result<Value, Error> {
+	let mut decoder = Decoder { input, pos: 0 };
+	let value = decoder.value(0)?;
+	if decoder.pos == input.len() {
+		Ok(value)
+	} else {
+		Err(Error::CborDecodingError)
+	}
+}
+
+struct Decoder<'a> {
+	input: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+	fn value(&mut self, depth: usize) -> Result<Value<'a>, Error> {
+		if depth > MAX_DEPTH {
+			return Err(Error::CborDecodingError)
+		}
+		let (major, arg) = self.header()?;
+		Ok(match major {
+			MAJOR_UNSIGNED => Value::Unsigned(arg),
+			MAJOR_NEGATIVE => Value::Negative(arg),
+			MAJOR_BYTES => Value::Bytes(self.take(arg)?),
+			MAJOR_TEXT => Value::Text(
+				core::str::from_utf8(self.take(arg)?).map_err(|_| Error::CborDecodingError)?,
+			),
+			// Every item takes at least one byte, so the lengths are bounded by the input.
+			MAJOR_ARRAY => {
+				let mut items = Vec::new();
+				for _ in 0..arg {
+					items.push(self.value(depth + 1)?);
+				}
+				Value::Array(items)
+			},
+			MAJOR_MAP => {
+				let mut entries = Vec::new();
+				for _ in 0..arg {
+					entries.push((self.value(depth + 1)?, self.value(depth + 1)?));
+				}
+				Value::Map(entries)
+			},
+			MAJOR_TAG => Value::Tag(arg, Box::new(self.value(depth + 1)?)),
+			MAJOR_SIMPLE => Value::Simple(arg),
+			_ => return Err(Error::CborDecodingError),
+		})
+	}
+
+	/// Returns the major type and the argument, which is either a value or a length.
+	fn header(&mut self) -> Result<(u8, u64), Error> {
+		let initial = self.take(1)?[0];
+		let major = initial >> 5;
+		let arg = match initial & 0x1f {
+			info @ 0..=23 => info as u64,
+			24 => self.take(1)?[0] as u64,
+			25 => u16::from_be_bytes(self.take_array()?) as u64,
+			26 => u32::from_be_bytes(self.take_array()?) as u64,
+			27 => u64::from_be_bytes(self.take_array()?),
+			// reserved or indefinite length
+			_ => return Err(Error::CborDecodingError),
+		};
+		Ok((major, arg))
+	}
+
+	fn take(&mut self, len: u64) -> Result<&'a [u8], Error> {
+		let len = usize::try_from(len).map_err(|_| Error::CborDecodingError)?;
+		let end = self.pos.checked_add(len).ok_or(Error::CborDecodingError)?;
+		let slice = self.input.get(self.pos..end).ok_or(Error::CborDecodingError)?;
+		self.pos = end;
+		Ok(slice)
+	}
+
+	fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+		self.take(N as u64)?.try_into().map_err(|_| Error::CborDecodingError)
+	}
+}
+
+/// Appends the header of an item with a definite length to `out`.
+pub fn encode_header(major: u8, arg: u64, out: &mut Vec<u8>) {
+	let major = major << 5;
+	match arg {
+		0..=23 => out.push(major | arg as u8),
+		24..=0xff => out.extend_from_slice(&[major | 24, arg as u8]),
+		0x100..=0xffff => {
+			out.push(major | 25);
+			out.extend_from_slice(&(arg as u16).to_be_bytes())
+		},
+		0x1_0000..=0xffff_ffff => {
+			out.push(major | 26);
+			out.extend_from_slice(&(arg as u32).to_be_bytes())
+		},
+		_ => {
+			out.push(major | 27);
+			out.extend_from_slice(&arg.to_be_bytes())
+		},
+	}
+}
+
+pub fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+	encode_header(MAJOR_BYTES, bytes.len() as u64, out);
+	out.extend_from_slice(bytes);
+}
+
+pub fn encode_text(text: &str, out: &mut Vec<u8>) {
+	encode_header(MAJOR_TEXT, text.len() as u64, out);
+	out.extend_from_slice(text.as_bytes());
+}
+
+pub fn encode_array_header(len: u64, out: &mut Vec<u8>) {
+	encode_header(MAJOR_ARRAY, len, out);
+}
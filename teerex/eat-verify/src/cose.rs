@@ -0,0 +1,75 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! COSE_Sign1 messages, see RFC 9052, chapter 4.2.
+
+use crate::{
+	cbor::{self, Value},
+	Error,
+};
+use sp_std::prelude::*;
+
+const COSE_SIGN1_TAG: u64 = 18;
+const HEADER_ALG: i64 = 1;
+const SIGNATURE1_CONTEXT: &str = "Signature1";
+
+/// A decoded COSE_Sign1 message. The algorithm is only taken from the protected header, because
+/// the unprotected one is not covered by the signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoseSign1<'a> {
+	pub protected: &'a [u8],
+	pub alg: i64,
+	pub payload: &'a [u8],
+	pub signature: &'a [u8],
+}
+
+impl<'a> CoseSign1<'a> {
+	/// Decodes a tagged or untagged COSE_Sign1 message with an attached payload.
+	pub fn decode(message: &'a [u8]) -> Result<Self, Error> {
+		let value = cbor::decode(message)?;
+		let value = match value {
+			Value::Tag(COSE_SIGN1_TAG, inner) => *inner,
+			Value::Tag(..) => return Err(Error::CoseStructureIsInvalid),
+			untagged => untagged,
+		};
+		let (protected, payload, signature) = match &value {
+			Value::Array(items) => match items.as_slice() {
+				[Value::Bytes(protected), Value::Map(_), Value::Bytes(payload), Value::Bytes(signature)] =>
+					(*protected, *payload, *signature),
+				_ => return Err(Error::CoseStructureIsInvalid),
+			},
+			_ => return Err(Error::CoseStructureIsInvalid),
+		};
+		let alg = cbor::decode(protected)?
+			.get(HEADER_ALG)
+			.and_then(Value::as_i64)
+			.ok_or(Error::CoseStructureIsInvalid)?;
+		Ok(Self { protected, alg, payload, signature })
+	}
+
+	/// The `Sig_structure`, which is what the signature has been computed over. There is no
+	/// external data.
+	pub fn to_be_signed(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(self.protected.len() + self.payload.len() + 16);
+		cbor::encode_array_header(4, &mut out);
+		cbor::encode_text(SIGNATURE1_CONTEXT, &mut out);
+		cbor::encode_bytes(self.protected, &mut out);
+		cbor::encode_bytes(&[], &mut out);
+		cbor::encode_bytes(self.payload, &mut out);
+		out
+	}
+}
@@ -0,0 +1,125 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Contains the logic for verifying Entity Attestation Tokens (EAT), which are signed as
+//! COSE_Sign1 messages. This is the token format of Arm CCA and of Veraison-style verifiers.
+//!
+//! Which key may sign the tokens of a profile is up to the caller, this crate does not know any
+//! trust anchors. Token collections, like the one of Arm CCA, must be split by the caller.
+//!
+//! * RFC 9711 "The Entity Attestation Token (EAT)"
+//! * RFC 9052 "CBOR Object Signing and Encryption (COSE): Structures and Process"
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::ensure;
+use ring::signature;
+use sp_std::prelude::*;
+use teerex_primitives::{EatSignatureAlgorithm, MAX_EAT_MEASUREMENT_LEN, TEEREX};
+
+pub use cose::CoseSign1;
+pub use error::Error;
+
+pub mod cbor;
+mod cose;
+mod error;
+
+#[cfg(any(test, feature = "test-data"))]
+pub mod test_data;
+
+#[cfg(test)]
+mod tests;
+
+const CLAIM_IAT: i64 = 6;
+const CLAIM_NONCE: i64 = 10;
+const CLAIM_PROFILE: i64 = 265;
+
+/// RFC 9711 limits the size of a nonce.
+const MIN_NONCE_LEN: usize = 8;
+const MAX_NONCE_LEN: usize = 64;
+
+/// The claims of a verified token that matter to the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EatVerifiedToken {
+	pub nonce: Vec<u8>,
+	pub measurement: Vec<u8>,
+	/// `iat` claim, unix epoch in seconds
+	pub issued_at: Option<u64>,
+}
+
+/// Verifies the signature of `token` with `public_key` and extracts its claims.
+///
+/// The nonce claim must be a single byte string. If the token has a profile claim, it must equal
+/// `profile`. `measurement_claim` is the key of the claim which holds the measurement.
+pub fn verify_eat_token(
+	token: &[u8],
+	algorithm: EatSignatureAlgorithm,
+	public_key: &[u8],
+	profile: &[u8],
+	measurement_claim: i64,
+) -> Result<EatVerifiedToken, Error> {
+	let sign1 = CoseSign1::decode(token)?;
+	ensure!(sign1.alg == algorithm.cose_alg(), Error::SignatureAlgorithmMismatch);
+	let ring_algorithm = match algorithm {
+		EatSignatureAlgorithm::Es256 => &signature::ECDSA_P256_SHA256_FIXED,
+		EatSignatureAlgorithm::Es384 => &signature::ECDSA_P384_SHA384_FIXED,
+	};
+	signature::UnparsedPublicKey::new(ring_algorithm, public_key)
+		.verify(&sign1.to_be_signed(), sign1.signature)
+		.map_err(|_| Error::SignatureIsInvalid)?;
+	log::trace!(target: TEEREX, "EAT signature verified");
+
+	let claims = cbor::decode(sign1.payload).map_err(|_| Error::ClaimsDecodingError)?;
+
+	let nonce = claims
+		.get(CLAIM_NONCE)
+		.ok_or(Error::NonceClaimIsMissing)?
+		.as_bytes()
+		.ok_or(Error::NonceClaimIsInvalid)?;
+	ensure!((MIN_NONCE_LEN..=MAX_NONCE_LEN).contains(&nonce.len()), Error::NonceClaimIsInvalid);
+
+	let measurement = claims
+		.get(measurement_claim)
+		.ok_or(Error::MeasurementClaimIsMissing)?
+		.as_bytes()
+		.ok_or(Error::MeasurementClaimIsInvalid)?;
+	ensure!(
+		!measurement.is_empty() && measurement.len() <= MAX_EAT_MEASUREMENT_LEN as usize,
+		Error::MeasurementClaimIsInvalid
+	);
+
+	// The profile is either a URI or an OID
+	if let Some(claimed_profile) = claims.get(CLAIM_PROFILE) {
+		let claimed_profile = claimed_profile
+			.as_text()
+			.map(str::as_bytes)
+			.or_else(|| claimed_profile.as_bytes())
+			.ok_or(Error::ClaimsDecodingError)?;
+		ensure!(claimed_profile == profile, Error::ProfileMismatch);
+	}
+
+	let issued_at = match claims.get(CLAIM_IAT) {
+		Some(iat) => Some(
+			iat.as_i64()
+				.and_then(|iat| u64::try_from(iat).ok())
+				.ok_or(Error::ClaimsDecodingError)?,
+		),
+		None => None,
+	};
+
+	Ok(EatVerifiedToken { nonce: nonce.to_vec(), measurement: measurement.to_vec(), issued_at })
+}
@@ -0,0 +1,37 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! A synthetic token and the key it is signed with, generated with `test-data/generate.py`.
+
+use hex_literal::hex;
+
+pub const TEST_EAT_TOKEN: &[u8] = include_bytes!("../test-data/token.cbor");
+/// The uncompressed P-256 public key the token is signed with.
+pub const TEST_EAT_PUBLIC_KEY: &[u8] = include_bytes!("../test-data/public_key.bin");
+pub const TEST_EAT_PROFILE: &[u8] = b"tag:integritee.network,2024:eat-test";
+/// The claim key of the Arm CCA realm initial measurement.
+pub const TEST_EAT_MEASUREMENT_CLAIM: i64 = 44238;
+pub const TEST_EAT_MEASUREMENT: [u8; 32] = [0x3c; 32];
+/// The nonce claim, the public key of the account registering the enclave.
+pub const TEST_EAT_SIGNER: [u8; 32] =
+	hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+/// 2025-01-01T00:00:00Z, the `iat` claim in milliseconds.
+pub const TEST_EAT_TIMESTAMP: u64 = 1_735_689_600_000;
+
+/// Offsets into `TEST_EAT_TOKEN`
+pub const TEST_EAT_PAYLOAD_OFFSET: usize = 9;
+pub const TEST_EAT_SIGNATURE_OFFSET: usize = 0x83;
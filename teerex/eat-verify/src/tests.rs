@@ -0,0 +1,144 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use super::*;
+use crate::{cbor::Value, test_data::*};
+
+fn verify(token: &[u8]) -> Result<EatVerifiedToken, Error> {
+	verify_eat_token(
+		token,
+		EatSignatureAlgorithm::Es256,
+		TEST_EAT_PUBLIC_KEY,
+		TEST_EAT_PROFILE,
+		TEST_EAT_MEASUREMENT_CLAIM,
+	)
+}
+
+#[test]
+fn verify_eat_token_works() {
+	let token = verify(TEST_EAT_TOKEN).unwrap();
+	assert_eq!(token.nonce, TEST_EAT_SIGNER.to_vec());
+	assert_eq!(token.measurement, TEST_EAT_MEASUREMENT.to_vec());
+	assert_eq!(token.issued_at, Some(TEST_EAT_TIMESTAMP / 1000));
+}
+
+#[test]
+fn untagged_eat_token_is_accepted() {
+	assert_eq!(TEST_EAT_TOKEN[0], 0xd2);
+	assert!(verify(&TEST_EAT_TOKEN[1..]).is_ok());
+}
+
+#[test]
+fn tampered_eat_payload_is_rejected() {
+	let mut token = TEST_EAT_TOKEN.to_vec();
+	token[TEST_EAT_PAYLOAD_OFFSET + 4] ^= 0x01;
+	assert_eq!(verify(&token), Err(Error::SignatureIsInvalid));
+}
+
+#[test]
+fn tampered_eat_signature_is_rejected() {
+	let mut token = TEST_EAT_TOKEN.to_vec();
+	token[TEST_EAT_SIGNATURE_OFFSET] ^= 0x01;
+	assert_eq!(verify(&token), Err(Error::SignatureIsInvalid));
+}
+
+#[test]
+fn truncated_eat_token_is_rejected() {
+	assert_eq!(verify(&TEST_EAT_TOKEN[..TEST_EAT_TOKEN.len() - 1]), Err(Error::CborDecodingError));
+}
+
+#[test]
+fn eat_token_with_other_algorithm_is_rejected() {
+	assert_eq!(
+		verify_eat_token(
+			TEST_EAT_TOKEN,
+			EatSignatureAlgorithm::Es384,
+			TEST_EAT_PUBLIC_KEY,
+			TEST_EAT_PROFILE,
+			TEST_EAT_MEASUREMENT_CLAIM,
+		),
+		Err(Error::SignatureAlgorithmMismatch)
+	);
+}
+
+#[test]
+fn eat_token_of_other_profile_is_rejected() {
+	assert_eq!(
+		verify_eat_token(
+			TEST_EAT_TOKEN,
+			EatSignatureAlgorithm::Es256,
+			TEST_EAT_PUBLIC_KEY,
+			b"tag:arm.com,2023:realm#1.0.0",
+			TEST_EAT_MEASUREMENT_CLAIM,
+		),
+		Err(Error::ProfileMismatch)
+	);
+}
+
+#[test]
+fn eat_token_without_measurement_claim_is_rejected() {
+	assert_eq!(
+		verify_eat_token(
+			TEST_EAT_TOKEN,
+			EatSignatureAlgorithm::Es256,
+			TEST_EAT_PUBLIC_KEY,
+			TEST_EAT_PROFILE,
+			-75000,
+		),
+		Err(Error::MeasurementClaimIsMissing)
+	);
+}
+
+#[test]
+fn cbor_decode_works() {
+	// {1: -7, "a": [h'01', 24]}
+	let value =
+		cbor::decode(&[0xa2, 0x01, 0x26, 0x61, 0x61, 0x82, 0x41, 0x01, 0x18, 0x18]).unwrap();
+	assert_eq!(value.get(1), Some(&Value::Negative(6)));
+	assert_eq!(value.get(1).and_then(Value::as_i64), Some(-7));
+	assert_eq!(
+		value,
+		Value::Map(vec![
+			(Value::Unsigned(1), Value::Negative(6)),
+			(Value::Text("a"), Value::Array(vec![Value::Bytes(&[0x01]), Value::Unsigned(24)])),
+		])
+	);
+}
+
+#[test]
+fn cbor_decode_rejects_malformed_input() {
+	// indefinite length byte string
+	assert_eq!(cbor::decode(&[0x5f, 0x41, 0x01, 0xff]), Err(Error::CborDecodingError));
+	// trailing bytes
+	assert_eq!(cbor::decode(&[0x01, 0x01]), Err(Error::CborDecodingError));
+	// array longer than the input
+	assert_eq!(
+		cbor::decode(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+		Err(Error::CborDecodingError)
+	);
+	// nested too deeply
+	assert_eq!(cbor::decode(&[0x81; 32]), Err(Error::CborDecodingError));
+}
+
+#[test]
+fn cbor_encode_header_roundtrips() {
+	for arg in [0, 23, 24, 0xff, 0x100, 0xffff, 0x1_0000, 0xffff_ffff, 0x1_0000_0000, u64::MAX] {
+		let mut encoded = Vec::new();
+		cbor::encode_header(0, arg, &mut encoded);
+		assert_eq!(cbor::decode(&encoded), Ok(Value::Unsigned(arg)));
+	}
+}
@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+"""Generates a synthetic Entity Attestation Token (EAT), signed as COSE_Sign1 with ES256.
+
+The claims are the EAT nonce, profile and issued-at time, plus a measurement under the key of
+the Arm CCA realm initial measurement. Requires the `cryptography` package, the CBOR is encoded
+by hand to keep it deterministic.
+"""
+
+import struct
+
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.hazmat.primitives.asymmetric.utils import decode_dss_signature
+
+CLAIM_IAT = 6
+CLAIM_NONCE = 10
+CLAIM_PROFILE = 265
+CLAIM_MEASUREMENT = 44238
+COSE_HEADER_ALG = 1
+COSE_ALG_ES256 = -7
+COSE_SIGN1_TAG = 18
+
+# an sr25519/ed25519 public key, which decodes into the account id of the sender
+NONCE = bytes.fromhex("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+PROFILE = "tag:integritee.network,2024:eat-test"
+MEASUREMENT = bytes.fromhex("3c" * 32)
+# 2025-01-01T00:00:00Z
+IAT = 1_735_689_600
+
+
+def header(major, arg):
+    if arg < 24:
+        return bytes([major << 5 | arg])
+    if arg < 0x100:
+        return bytes([major << 5 | 24, arg])
+    if arg < 0x10000:
+        return bytes([major << 5 | 25]) + struct.pack(">H", arg)
+    if arg < 0x100000000:
+        return bytes([major << 5 | 26]) + struct.pack(">I", arg)
+    return bytes([major << 5 | 27]) + struct.pack(">Q", arg)
+
+
+def encode(value):
+    if isinstance(value, int):
+        return header(0, value) if value >= 0 else header(1, -1 - value)
+    if isinstance(value, bytes):
+        return header(2, len(value)) + value
+    if isinstance(value, str):
+        return header(3, len(value.encode())) + value.encode()
+    if isinstance(value, list):
+        return header(4, len(value)) + b"".join(encode(v) for v in value)
+    if isinstance(value, dict):
+        return header(5, len(value)) + b"".join(encode(k) + encode(v) for k, v in value.items())
+    raise TypeError(value)
+
+
+def main():
+    key = ec.generate_private_key(ec.SECP256R1())
+    public_key = key.public_key().public_bytes(
+        serialization.Encoding.X962, serialization.PublicFormat.UncompressedPoint
+    )
+
+    protected = encode({COSE_HEADER_ALG: COSE_ALG_ES256})
+    payload = encode({
+        CLAIM_NONCE: NONCE,
+        CLAIM_PROFILE: PROFILE,
+        CLAIM_IAT: IAT,
+        CLAIM_MEASUREMENT: MEASUREMENT,
+    })
+    to_be_signed = encode(["Signature1", protected, b"", payload])
+    r, s = decode_dss_signature(key.sign(to_be_signed, ec.ECDSA(hashes.SHA256())))
+    signature = r.to_bytes(32, "big") + s.to_bytes(32, "big")
+
+    token = header(6, COSE_SIGN1_TAG) + encode([protected, {}, payload, signature])
+    with open("token.cbor", "wb") as f:
+        f.write(token)
+    with open("public_key.bin", "wb") as f:
+        f.write(public_key)
+
+
+if __name__ == "__main__":
+    main()
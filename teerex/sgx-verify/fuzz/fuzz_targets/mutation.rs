@@ -0,0 +1,49 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use libfuzzer_sys::arbitrary::Arbitrary;
+
+/// Edits of a byte buffer that are not aware of its structure. Positions beyond the end of the
+/// buffer are clamped, such that every mutation can be applied to every buffer.
+#[derive(Arbitrary, Debug)]
+pub enum ByteMutation {
+	FlipByte { position: u16, xor: u8 },
+	Truncate { len: u16 },
+	Insert { position: u16, bytes: Vec<u8> },
+	Remove { position: u16, len: u8 },
+}
+
+impl ByteMutation {
+	pub fn apply(&self, data: &mut Vec<u8>) {
+		match self {
+			ByteMutation::FlipByte { position, xor } =>
+				if let Some(byte) = data.get_mut(*position as usize) {
+					*byte ^= xor;
+				},
+			ByteMutation::Truncate { len } => data.truncate(*len as usize),
+			ByteMutation::Insert { position, bytes } => {
+				let position = (*position as usize).min(data.len());
+				data.splice(position..position, bytes.iter().copied());
+			},
+			ByteMutation::Remove { position, len } => {
+				let start = (*position as usize).min(data.len());
+				let end = (start + *len as usize).min(data.len());
+				data.drain(start..end);
+			},
+		}
+	}
+}
@@ -0,0 +1,119 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#![no_main]
+
+//! Structure-aware fuzzing of the full DCAP verification.
+//!
+//! Random bytes hardly ever decode into a DCAP quote, so the fuzzer mutates the valid test quote
+//! instead. Besides arbitrary byte edits, it can set the length and type fields of the quote
+//! layout, which drives the verification past decoding and into the chain and signature checks.
+
+use libfuzzer_sys::{arbitrary::Arbitrary, fuzz_target};
+use mutation::ByteMutation;
+use sgx_verify::{
+	collateral::EnclaveIdentity,
+	extract_tcb_info_from_raw_dcap_quote, inspect,
+	test_data::dcap::{
+		QUOTING_ENCLAVE, TEST1_DCAP_QUOTE, TEST1_DCAP_QUOTE_MRENCLAVE,
+		TEST_VALID_COLLATERAL_TIMESTAMP,
+	},
+	verify_dcap_quote,
+};
+
+mod mutation;
+
+// Offsets of the fields in the quote layout, see chapter A.4 of Intel's ECDSA Quote Library API.
+const VERSION_OFFSET: usize = 0;
+const ATTESTATION_KEY_TYPE_OFFSET: usize = 2;
+const SIGNATURE_DATA_LEN_OFFSET: usize = 432;
+const QE_REPORT_ISV_SVN_OFFSET: usize = 436 + 64 + 64 + 258;
+const AUTHENTICATION_DATA_SIZE_OFFSET: usize = 436 + 64 + 64 + 384 + 64;
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum QuoteField {
+	Version,
+	AttestationKeyType,
+	SignatureDataLen,
+	QeReportIsvSvn,
+	AuthenticationDataSize,
+	CertificationDataType,
+	CertificationDataSize,
+}
+
+#[derive(Arbitrary, Debug)]
+enum QuoteMutation {
+	SetField { field: QuoteField, value: u32 },
+	Bytes(ByteMutation),
+}
+
+impl QuoteField {
+	/// Returns the offset and the size of the field in `quote`. The offsets of the certification
+	/// data depend on the size of the authentication data, which is read from the quote itself.
+	fn location(self, quote: &[u8]) -> Option<(usize, usize)> {
+		let certification_data_offset = || {
+			let size_offset = AUTHENTICATION_DATA_SIZE_OFFSET;
+			let size = quote.get(size_offset..size_offset + 2)?;
+			Some(size_offset + 2 + u16::from_le_bytes([size[0], size[1]]) as usize)
+		};
+		Some(match self {
+			QuoteField::Version => (VERSION_OFFSET, 2),
+			QuoteField::AttestationKeyType => (ATTESTATION_KEY_TYPE_OFFSET, 2),
+			QuoteField::SignatureDataLen => (SIGNATURE_DATA_LEN_OFFSET, 4),
+			QuoteField::QeReportIsvSvn => (QE_REPORT_ISV_SVN_OFFSET, 2),
+			QuoteField::AuthenticationDataSize => (AUTHENTICATION_DATA_SIZE_OFFSET, 2),
+			QuoteField::CertificationDataType => (certification_data_offset()?, 2),
+			QuoteField::CertificationDataSize => (certification_data_offset()? + 2, 4),
+		})
+	}
+}
+
+impl QuoteMutation {
+	fn apply(&self, quote: &mut Vec<u8>) {
+		match self {
+			QuoteMutation::SetField { field, value } => {
+				if let Some((offset, size)) = field.location(quote) {
+					if let Some(target) = quote.get_mut(offset..offset + size) {
+						target.copy_from_slice(&value.to_le_bytes()[..size]);
+					}
+				}
+			},
+			QuoteMutation::Bytes(mutation) => mutation.apply(quote),
+		}
+	}
+}
+
+fuzz_target!(|mutations: Vec<QuoteMutation>| {
+	let mut quote = TEST1_DCAP_QUOTE.to_vec();
+	for mutation in &mutations {
+		mutation.apply(&mut quote);
+	}
+
+	let _ = inspect::describe_dcap_quote(&quote);
+	let _ = extract_tcb_info_from_raw_dcap_quote(&quote);
+
+	let qe = serde_json::from_slice::<EnclaveIdentity>(QUOTING_ENCLAVE)
+		.unwrap()
+		.to_quoting_enclave(Default::default(), None);
+	// The enclave report is signed by the attestation key, which is certified by the QE report,
+	// which in turn is signed by Intel. No mutation must be able to pass off another enclave.
+	if let Ok((_fmspc, _tcb_info, report)) =
+		verify_dcap_quote(&quote, TEST_VALID_COLLATERAL_TIMESTAMP, &qe)
+	{
+		assert_eq!(report.mr_enclave, TEST1_DCAP_QUOTE_MRENCLAVE);
+	}
+});
@@ -0,0 +1,127 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#![no_main]
+
+//! Structure-aware fuzzing of the IAS report verification.
+//!
+//! The certificate of the test enclave is mutated to exercise the verification end to end. As the
+//! report is only parsed once Intel's signature has been verified, its parsing is additionally
+//! fuzzed directly with mutations of the test enclave's report.
+
+use libfuzzer_sys::{arbitrary::Arbitrary, fuzz_target};
+use mutation::ByteMutation;
+use serde_json::{Map, Value};
+use sgx_verify::{
+	fuzz_parse_report,
+	test_data::consts::{TEST4_CERT, TEST4_MRENCLAVE},
+	verify_ias_report,
+};
+
+mod mutation;
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum ReportField {
+	Id,
+	Timestamp,
+	Version,
+	IsvEnclaveQuoteStatus,
+	IsvEnclaveQuoteBody,
+}
+
+impl ReportField {
+	fn key(self) -> &'static str {
+		match self {
+			ReportField::Id => "id",
+			ReportField::Timestamp => "timestamp",
+			ReportField::Version => "version",
+			ReportField::IsvEnclaveQuoteStatus => "isvEnclaveQuoteStatus",
+			ReportField::IsvEnclaveQuoteBody => "isvEnclaveQuoteBody",
+		}
+	}
+}
+
+#[derive(Arbitrary, Debug)]
+enum ReportMutation {
+	SetString {
+		field: ReportField,
+		value: String,
+	},
+	SetNumber {
+		field: ReportField,
+		value: i64,
+	},
+	Remove(ReportField),
+	/// Mutates the base64 decoded quote body and encodes it again.
+	QuoteBody(ByteMutation),
+}
+
+impl ReportMutation {
+	fn apply(&self, report: &mut Map<String, Value>) {
+		match self {
+			ReportMutation::SetString { field, value } => {
+				report.insert(field.key().into(), value.clone().into());
+			},
+			ReportMutation::SetNumber { field, value } => {
+				report.insert(field.key().into(), (*value).into());
+			},
+			ReportMutation::Remove(field) => {
+				report.remove(field.key());
+			},
+			ReportMutation::QuoteBody(mutation) => {
+				let key = ReportField::IsvEnclaveQuoteBody.key();
+				if let Some(mut body) =
+					report.get(key).and_then(Value::as_str).and_then(|b| base64::decode(b).ok())
+				{
+					mutation.apply(&mut body);
+					report.insert(key.into(), base64::encode(body).into());
+				}
+			},
+		}
+	}
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+	certificate: Vec<ByteMutation>,
+	report: Vec<ReportMutation>,
+}
+
+/// The attestation report is embedded in the netscape comment of the certificate and is
+/// terminated by the separator of the signature.
+fn test_report() -> Map<String, Value> {
+	let start = TEST4_CERT.windows(5).position(|w| w == br#"{"id""#).unwrap();
+	let len = TEST4_CERT[start..].iter().position(|b| *b == b'|').unwrap();
+	serde_json::from_slice(&TEST4_CERT[start..start + len]).unwrap()
+}
+
+fuzz_target!(|input: Input| {
+	let mut certificate = TEST4_CERT.to_vec();
+	for mutation in &input.certificate {
+		mutation.apply(&mut certificate);
+	}
+	// The report is signed by Intel, so no mutation must be able to pass off another enclave.
+	if let Ok(report) = verify_ias_report(&certificate) {
+		assert_eq!(report.mr_enclave, TEST4_MRENCLAVE);
+	}
+
+	let mut report = test_report();
+	for mutation in &input.report {
+		mutation.apply(&mut report);
+	}
+	let _ = fuzz_parse_report(&serde_json::to_vec(&report).unwrap());
+});
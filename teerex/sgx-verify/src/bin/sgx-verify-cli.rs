@@ -0,0 +1,363 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Offline verification of SGX remote attestation material.
+//!
+//! Runs the same verification as `pallet-teerex` does upon registration, but locally and with
+//! a chosen verification time, so that failing registrations can be debugged without
+//! resubmitting extrinsics.
+//!
+//! The collateral files are expected in the format served by Intel's PCS, i.e.
+//! `{"tcbInfo":{..},"signature":"<hex>"}` and `{"enclaveIdentity":{..},"signature":"<hex>"}`.
+//! The issuer chains are the PEM chains served alongside.
+
+use sgx_verify::{
+	collateral::{EnclaveIdentity, EnclaveIdentitySigned, TcbInfo, TcbInfoSigned},
+	deserialize_enclave_identity, deserialize_tcb_info,
+	inspect::describe_dcap_quote,
+	verify_certificate_chain, verify_dcap_quote, verify_ias_report, CertificateChain,
+};
+use std::{collections::HashMap, env, fmt, fs, process::ExitCode, time::SystemTime};
+use teerex_primitives::{QeTcbStatusPolicy, SgxQuotingEnclave, SgxStatus};
+
+const USAGE: &str = "\
+USAGE:
+    sgx-verify-cli ias <cert.der>
+    sgx-verify-cli describe <quote.ra>
+    sgx-verify-cli dcap <quote.ra> --qe-identity <qe_identity.json> --qe-identity-chain <chain.pem>
+                   [--tcb-info <tcb_info.json> --tcb-info-chain <chain.pem>] [--timestamp <millis>]
+                   [--min-qe-isvsvn <isvsvn>] [--accept-qe-tcb-statuses <statuses>]
+    sgx-verify-cli tcb-info <tcb_info.json> --tcb-info-chain <chain.pem> [--timestamp <millis>]
+    sgx-verify-cli qe-identity <qe_identity.json> --qe-identity-chain <chain.pem> [--timestamp <millis>]
+                   [--min-qe-isvsvn <isvsvn>] [--accept-qe-tcb-statuses <statuses>]
+
+The verification time `--timestamp` is a unix timestamp in milliseconds and defaults to now.
+`--min-qe-isvsvn` mimics the on-chain floor for the ISVSVN of the quoting enclave.
+`--accept-qe-tcb-statuses` mimics the on-chain QE TCB status policy. It takes a comma separated
+list of `OutOfDate` and `SWHardeningNeeded`. Only `UpToDate` levels are accepted by default.";
+
+#[derive(Debug)]
+enum CliError {
+	Usage(String),
+	Io(String, std::io::Error),
+	Json(String, serde_json::Error),
+	Hex(String),
+	/// The exact error returned by `sgx_verify`, including its context
+	Verify(sgx_verify::VerificationError),
+	/// The collateral is well-formed and signed, but not valid at the verification time
+	CollateralIsNotValid {
+		issue_date: u64,
+		next_update: u64,
+		timestamp: u64,
+	},
+	FmspcMismatch {
+		quote: String,
+		tcb_info: String,
+	},
+	/// The quote's TCB is not covered by any level of the TCB info
+	TcbIsOutdated,
+	/// The TCB levels are not in descending order, as the chain would reject them
+	TcbLevelsAreNotOrdered,
+	/// No TCB level of the QE identity has an accepted status and is above the optional floor
+	NoAcceptableQeTcbLevel {
+		statuses: QeTcbStatusPolicy,
+		min_isvsvn: Option<u16>,
+	},
+}
+
+impl fmt::Display for CliError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CliError::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+			CliError::Io(path, e) => write!(f, "failed to read {}: {}", path, e),
+			CliError::Json(path, e) => write!(f, "failed to parse {}: {}", path, e),
+			CliError::Hex(what) => write!(f, "failed to decode hex signature of {}", what),
+			CliError::Verify(e) => write!(f, "sgx_verify::Error::{}", e),
+			CliError::CollateralIsNotValid { issue_date, next_update, timestamp } => write!(
+				f,
+				"collateral is not valid at {} (issue date: {}, next update: {})",
+				timestamp, issue_date, next_update
+			),
+			CliError::FmspcMismatch { quote, tcb_info } => write!(
+				f,
+				"FMSPC of the quote ({}) does not match the TCB info ({})",
+				quote, tcb_info
+			),
+			CliError::TcbIsOutdated =>
+				write!(f, "the TCB of the quote is outdated with respect to the TCB info"),
+			CliError::TcbLevelsAreNotOrdered =>
+				write!(f, "the TCB levels of the TCB info are not in descending order"),
+			CliError::NoAcceptableQeTcbLevel { statuses, min_isvsvn } => write!(
+				f,
+				"the QE identity has no acceptable TCB level (accepted statuses: {:?}, minimum isvsvn: {:?})",
+				statuses, min_isvsvn
+			),
+		}
+	}
+}
+
+impl From<sgx_verify::Error> for CliError {
+	fn from(e: sgx_verify::Error) -> Self {
+		CliError::Verify(e.into())
+	}
+}
+
+impl From<sgx_verify::VerificationError> for CliError {
+	fn from(e: sgx_verify::VerificationError) -> Self {
+		CliError::Verify(e)
+	}
+}
+
+/// Positional argument plus `--key value` options.
+struct Args {
+	positional: Option<String>,
+	options: HashMap<String, String>,
+}
+
+impl Args {
+	fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+		let mut positional = None;
+		let mut options = HashMap::new();
+		while let Some(arg) = args.next() {
+			if let Some(key) = arg.strip_prefix("--") {
+				let value = args
+					.next()
+					.ok_or_else(|| CliError::Usage(format!("missing value for --{}", key)))?;
+				options.insert(key.to_string(), value);
+			} else if positional.is_none() {
+				positional = Some(arg);
+			} else {
+				return Err(CliError::Usage(format!("unexpected argument: {}", arg)))
+			}
+		}
+		Ok(Self { positional, options })
+	}
+
+	fn positional(&self, name: &str) -> Result<&str, CliError> {
+		self.positional
+			.as_deref()
+			.ok_or_else(|| CliError::Usage(format!("missing argument <{}>", name)))
+	}
+
+	fn option(&self, key: &str) -> Option<&str> {
+		self.options.get(key).map(String::as_str)
+	}
+
+	fn required(&self, key: &str) -> Result<&str, CliError> {
+		self.option(key)
+			.ok_or_else(|| CliError::Usage(format!("missing option --{}", key)))
+	}
+
+	fn timestamp(&self) -> Result<u64, CliError> {
+		match self.option("timestamp") {
+			Some(t) => t.parse().map_err(|_| CliError::Usage(format!("invalid timestamp: {}", t))),
+			None => Ok(SystemTime::now()
+				.duration_since(SystemTime::UNIX_EPOCH)
+				.expect("system time is after the unix epoch")
+				.as_millis() as u64),
+		}
+	}
+
+	fn min_qe_isvsvn(&self) -> Result<Option<u16>, CliError> {
+		self.option("min-qe-isvsvn")
+			.map(|svn| svn.parse().map_err(|_| CliError::Usage(format!("invalid isvsvn: {}", svn))))
+			.transpose()
+	}
+
+	fn qe_tcb_statuses(&self) -> Result<QeTcbStatusPolicy, CliError> {
+		let mut statuses = QeTcbStatusPolicy::default();
+		for status in self.option("accept-qe-tcb-statuses").into_iter().flat_map(|s| s.split(',')) {
+			match status.trim() {
+				"OutOfDate" => statuses.accept_out_of_date = true,
+				"SWHardeningNeeded" => statuses.accept_sw_hardening_needed = true,
+				other =>
+					return Err(CliError::Usage(format!("unsupported QE TCB status: {}", other))),
+			}
+		}
+		Ok(statuses)
+	}
+}
+
+fn read(path: &str) -> Result<Vec<u8>, CliError> {
+	fs::read(path).map_err(|e| CliError::Io(path.to_string(), e))
+}
+
+/// Verifies the issuer chain of a collateral at `timestamp` and returns it.
+fn verified_issuer_chain(path: &str, timestamp: u64) -> Result<CertificateChain, CliError> {
+	let chain = CertificateChain::decode(&read(path)?)?;
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
+		.map_err(|_| sgx_verify::Error::LeafCertificateParsingError)?;
+	verify_certificate_chain(&leaf_cert, &chain.intermediates(), timestamp)?;
+	println!("issuer chain {}: {} certificates, valid", path, chain.certificates().len());
+	Ok(chain)
+}
+
+fn ensure_collateral_is_valid(
+	is_valid: bool,
+	issue_date: u64,
+	next_update: u64,
+	timestamp: u64,
+) -> Result<(), CliError> {
+	if is_valid {
+		Ok(())
+	} else {
+		Err(CliError::CollateralIsNotValid { issue_date, next_update, timestamp })
+	}
+}
+
+fn load_tcb_info(args: &Args, path: &str, timestamp: u64) -> Result<TcbInfo, CliError> {
+	let chain = verified_issuer_chain(args.required("tcb-info-chain")?, timestamp)?;
+	let signed: TcbInfoSigned =
+		serde_json::from_slice(&read(path)?).map_err(|e| CliError::Json(path.to_string(), e))?;
+	// re-serializing yields the exact bytes signed by Intel, as the PCS serves compact JSON
+	let data =
+		serde_json::to_vec(&signed.tcb_info).map_err(|e| CliError::Json(path.to_string(), e))?;
+	let signature = hex::decode(&signed.signature).map_err(|_| CliError::Hex(path.to_string()))?;
+
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
+		.map_err(|_| sgx_verify::Error::LeafCertificateParsingError)?;
+	let tcb_info = deserialize_tcb_info(&data, &signature, &leaf_cert)?;
+
+	let (fmspc, on_chain_info) = tcb_info.to_chain_tcb_info();
+	println!("TCB info {}: signature valid", path);
+	println!("  FMSPC: {}", hex::encode_upper(fmspc));
+	println!("  {:#?}", on_chain_info);
+	ensure_collateral_is_valid(
+		tcb_info.is_valid(timestamp as i64),
+		on_chain_info.issue_date,
+		on_chain_info.next_update,
+		timestamp,
+	)?;
+	if !on_chain_info.tcb_levels_are_ordered() {
+		return Err(CliError::TcbLevelsAreNotOrdered)
+	}
+	Ok(tcb_info)
+}
+
+fn load_qe_identity(
+	args: &Args,
+	path: &str,
+	timestamp: u64,
+) -> Result<SgxQuotingEnclave, CliError> {
+	let chain = verified_issuer_chain(args.required("qe-identity-chain")?, timestamp)?;
+	let signed: EnclaveIdentitySigned =
+		serde_json::from_slice(&read(path)?).map_err(|e| CliError::Json(path.to_string(), e))?;
+	let data = serde_json::to_vec(&signed.enclave_identity)
+		.map_err(|e| CliError::Json(path.to_string(), e))?;
+	let signature = hex::decode(&signed.signature).map_err(|_| CliError::Hex(path.to_string()))?;
+
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
+		.map_err(|_| sgx_verify::Error::LeafCertificateParsingError)?;
+	let identity: EnclaveIdentity = deserialize_enclave_identity(&data, &signature, &leaf_cert)?;
+
+	let statuses = args.qe_tcb_statuses()?;
+	let min_isvsvn = args.min_qe_isvsvn()?;
+	let quoting_enclave = identity.to_quoting_enclave(statuses, min_isvsvn);
+	println!("QE identity {}: signature valid", path);
+	println!("  {:#?}", quoting_enclave);
+	if quoting_enclave.tcb.is_empty() {
+		return Err(CliError::NoAcceptableQeTcbLevel { statuses, min_isvsvn })
+	}
+	ensure_collateral_is_valid(
+		identity.is_valid(timestamp as i64, statuses, min_isvsvn),
+		quoting_enclave.issue_date,
+		quoting_enclave.next_update,
+		timestamp,
+	)?;
+	Ok(quoting_enclave)
+}
+
+fn ias(args: &Args) -> Result<(), CliError> {
+	let report = verify_ias_report(&read(args.positional("cert.der")?)?)?;
+	println!("IAS report verified");
+	println!("{:#?}", report);
+	Ok(())
+}
+
+fn dcap(args: &Args) -> Result<(), CliError> {
+	let timestamp = args.timestamp()?;
+	println!("verification time: {}", timestamp);
+	let quote = read(args.positional("quote.ra")?)?;
+
+	// print the decoded quote, including the TCB of the PCK certificate, even if it fails to verify
+	match describe_dcap_quote(&quote) {
+		Ok(description) => println!("{}", description),
+		Err(e) => println!("could not decode the quote: {:?}", e),
+	}
+
+	let qe = load_qe_identity(args, args.required("qe-identity")?, timestamp)?;
+	let (fmspc, tcb, report) = verify_dcap_quote(&quote, timestamp, &qe)?;
+	println!("DCAP quote verified");
+	println!("{:#?}", report);
+
+	if let Some(path) = args.option("tcb-info") {
+		let tcb_info = load_tcb_info(args, path, timestamp)?;
+		let (tcb_info_fmspc, on_chain_info) = tcb_info.to_chain_tcb_info();
+		if fmspc != tcb_info_fmspc {
+			return Err(CliError::FmspcMismatch {
+				quote: hex::encode_upper(fmspc),
+				tcb_info: hex::encode_upper(tcb_info_fmspc),
+			})
+		}
+		let status: SgxStatus =
+			on_chain_info.verify_examinee(&tcb).ok_or(CliError::TcbIsOutdated)?;
+		println!("resulting SgxStatus: {:?}", status);
+	} else {
+		println!("no --tcb-info given, the resulting SgxStatus is not evaluated");
+	}
+	Ok(())
+}
+
+fn run() -> Result<(), CliError> {
+	let mut raw_args = env::args().skip(1);
+	let command = raw_args.next().ok_or_else(|| CliError::Usage("missing command".into()))?;
+	let args = Args::parse(raw_args)?;
+	match command.as_str() {
+		"ias" => ias(&args),
+		"dcap" => dcap(&args),
+		"describe" => {
+			println!("{}", describe_dcap_quote(&read(args.positional("quote.ra")?)?)?);
+			Ok(())
+		},
+		"tcb-info" => {
+			let timestamp = args.timestamp()?;
+			load_tcb_info(&args, args.positional("tcb_info.json")?, timestamp).map(|_| ())
+		},
+		"qe-identity" => {
+			let timestamp = args.timestamp()?;
+			load_qe_identity(&args, args.positional("qe_identity.json")?, timestamp).map(|_| ())
+		},
+		"-h" | "--help" | "help" => {
+			println!("{}", USAGE);
+			Ok(())
+		},
+		other => Err(CliError::Usage(format!("unknown command: {}", other))),
+	}
+}
+
+fn main() -> ExitCode {
+	match run() {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {}", e);
+			ExitCode::FAILURE
+		},
+	}
+}
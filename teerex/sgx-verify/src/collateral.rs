@@ -23,8 +23,8 @@ use chrono::prelude::{DateTime, Utc};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use sp_std::prelude::*;
 use teerex_primitives::{
-	Fmspc, MrSigner, Pcesvn, QeTcb, SgxQuotingEnclave, SgxTcbInfoOnChain, TcbStatus,
-	TcbVersionStatus, TEEREX,
+	Fmspc, MrSigner, Pcesvn, QeTcb, QeTcbStatusPolicy, SgxQuotingEnclave, SgxTcbInfoOnChain,
+	TcbStatus, TcbVersionStatus, TEEREX,
 };
 
 /// The data structures in here are designed such that they can be used to serialize/deserialize
@@ -37,10 +37,8 @@ pub struct Tcb {
 }
 
 impl Tcb {
-	pub fn is_valid(&self) -> bool {
-		// At the time of writing this code everything older than 6 is outdated
-		// Intel does the same check in their DCAP implementation
-		self.isvsvn >= 6
+	pub fn isvsvn(&self) -> u16 {
+		self.isvsvn
 	}
 }
 
@@ -57,6 +55,17 @@ pub struct TcbLevel {
 	advisory_ids: Option<Vec<String>>,
 }
 
+impl TcbLevel {
+	/// Whether a quoting enclave at this TCB level can be trusted. This is decided by Intel's
+	/// `tcbStatus`, so that new QE releases don't need a code change. By default only `UpToDate`
+	/// levels are trusted, `statuses` may extend this to `OutOfDate` or `SWHardeningNeeded`
+	/// levels. `min_isvsvn` is an optional floor on top of that.
+	pub fn is_acceptable(&self, statuses: QeTcbStatusPolicy, min_isvsvn: Option<u16>) -> bool {
+		statuses.accepts(self.tcb_status) &&
+			min_isvsvn.map_or(true, |min_isvsvn| self.tcb.isvsvn >= min_isvsvn)
+	}
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct TcbComponent {
 	svn: u8,
@@ -131,11 +140,16 @@ where
 
 impl EnclaveIdentity {
 	/// This extracts the necessary information into the struct that we actually store in the chain
-	pub fn to_quoting_enclave(&self) -> SgxQuotingEnclave {
-		let mut valid_tcbs: Vec<QeTcb> = Vec::new();
-		for tcb in &self.tcb_levels {
-			valid_tcbs.push(QeTcb::new(tcb.tcb.isvsvn));
-		}
+	/// Only the acceptable TCB levels (see `TcbLevel::is_acceptable`) are kept.
+	pub fn to_quoting_enclave(
+		&self,
+		statuses: QeTcbStatusPolicy,
+		min_isvsvn: Option<u16>,
+	) -> SgxQuotingEnclave {
+		let valid_tcbs: Vec<QeTcb> = self
+			.acceptable_tcb_levels(statuses, min_isvsvn)
+			.map(|tcb| QeTcb::new(tcb.tcb.isvsvn))
+			.collect();
 		SgxQuotingEnclave::new(
 			self.issue_date
 				.timestamp_millis()
@@ -155,11 +169,29 @@ impl EnclaveIdentity {
 		)
 	}
 
-	pub fn is_valid(&self, timestamp_millis: i64) -> bool {
+	/// Besides the validity period, this requires at least one acceptable TCB level. Otherwise no
+	/// quote could ever be verified against this identity.
+	pub fn is_valid(
+		&self,
+		timestamp_millis: i64,
+		statuses: QeTcbStatusPolicy,
+		min_isvsvn: Option<u16>,
+	) -> bool {
 		self.id == "QE" &&
 			self.version == 2 &&
 			self.issue_date.timestamp_millis() < timestamp_millis &&
-			timestamp_millis < self.next_update.timestamp_millis()
+			timestamp_millis < self.next_update.timestamp_millis() &&
+			self.acceptable_tcb_levels(statuses, min_isvsvn).next().is_some()
+	}
+
+	pub fn acceptable_tcb_levels(
+		&self,
+		statuses: QeTcbStatusPolicy,
+		min_isvsvn: Option<u16>,
+	) -> impl Iterator<Item = &TcbLevel> + '_ {
+		self.tcb_levels
+			.iter()
+			.filter(move |tcb| tcb.is_acceptable(statuses, min_isvsvn))
 	}
 }
 
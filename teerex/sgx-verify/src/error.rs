@@ -0,0 +1,153 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Errors of the verification.
+//!
+//! `Error` is the compact kind of an error. It is a `PalletError`, so pallets can nest it into
+//! their own errors. `VerificationError` additionally carries the context the error occurred in,
+//! which is meant for logs and tooling and never ends up on chain.
+
+use core::fmt;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+#[derive(
+	Debug, Encode, Decode, Copy, Clone, TypeInfo, frame_support::PalletError, PartialEq, Eq,
+)]
+pub enum Error {
+	CaVerificationFailed,
+	CertificateChainDecodingError,
+	CertificateChainIsInvalid,
+	CertificateChainIsTooShort,
+	CpuSvnDecodingError,
+	CpuSvnLengthMismatch,
+	CpuSvnOidIsMissing,
+	DcapKeyTypeMismatch,
+	DcapQuoteDecodingError,
+	DcapQuoteIsTooLong,
+	DcapQuoteVersionMismatch,
+	DerEncodingError,
+	EnclaveIdentityDecodingError,
+	EnclaveIdentitySignatureIsInvalid,
+	FmspcDecodingError,
+	FmspcLengthMismatch,
+	FmspcOidIsMissing,
+	IntelExtensionAmbiguity,
+	IntelExtensionCertificateDecodingError,
+	IntelExtensionDecodingError,
+	IsvEnclaveReportSignatureIsInvalid,
+	KeyLengthIsInvalid,
+	LeafCertificateParsingError,
+	NetscapeDecodingError,
+	NetscapeDerError,
+	PceIdDecodingError,
+	PceIdOidIsMissing,
+	PceSvnDecodingError,
+	PceSvnLengthMismatch,
+	PceSvnOidIsMissing,
+	PckCertFormatMismatch,
+	PlatformConfigurationDecodingError,
+	PlatformInstanceIdDecodingError,
+	PublicKeyIsInvalid,
+	QeHasRejectedEnclave,
+	QeReportHashMismatch,
+	QeReportSignatureIsInvalid,
+	QuoteBodyDecodingError,
+	QuoteBodyIsInvalid,
+	QuoteBodyMissing,
+	QuoteStatusMissing,
+	RsaSignatureIsInvalid,
+	SgxReportParsingError,
+	SgxTypeDecodingError,
+	SgxTypeOidIsMissing,
+	TcbInfoIsInvalid,
+	TimestampIsInvalid,
+	TimestampIsMissing,
+}
+
+/// Additional information about where and why the verification failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorContext {
+	None,
+	/// webpki rejected a certificate chain. `index` is the position of the offending certificate
+	/// in the chain, the leaf being 0. It is `None` if webpki's reason doesn't allow to tell.
+	Certificate {
+		index: Option<usize>,
+		reason: webpki::Error,
+	},
+	/// A field did not have the value we support.
+	Mismatch {
+		field: &'static str,
+		expected: u64,
+		actual: u64,
+	},
+}
+
+/// An `Error` together with its context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationError {
+	pub kind: Error,
+	pub context: ErrorContext,
+}
+
+impl VerificationError {
+	pub fn certificate(kind: Error, index: Option<usize>, reason: webpki::Error) -> Self {
+		Self { kind, context: ErrorContext::Certificate { index, reason } }
+	}
+
+	pub fn mismatch(
+		kind: Error,
+		field: &'static str,
+		expected: impl Into<u64>,
+		actual: impl Into<u64>,
+	) -> Self {
+		Self {
+			kind,
+			context: ErrorContext::Mismatch {
+				field,
+				expected: expected.into(),
+				actual: actual.into(),
+			},
+		}
+	}
+}
+
+impl From<Error> for VerificationError {
+	fn from(kind: Error) -> Self {
+		Self { kind, context: ErrorContext::None }
+	}
+}
+
+impl From<VerificationError> for Error {
+	fn from(e: VerificationError) -> Self {
+		e.kind
+	}
+}
+
+impl fmt::Display for VerificationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?}", self.kind)?;
+		match &self.context {
+			ErrorContext::None => Ok(()),
+			ErrorContext::Certificate { index: Some(index), reason } =>
+				write!(f, ": certificate {} of the chain (0 is the leaf): {:?}", index, reason),
+			ErrorContext::Certificate { index: None, reason } => write!(f, ": {:?}", reason),
+			ErrorContext::Mismatch { field, expected, actual } =>
+				write!(f, ": {} is {}, expected {}", field, actual, expected),
+		}
+	}
+}
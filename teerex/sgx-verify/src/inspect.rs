@@ -0,0 +1,191 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Human-readable descriptions of the DCAP quote structures.
+//!
+//! The quote structures are decoded with SCALE and their fields are raw byte arrays. The
+//! descriptions in here render them in the notation that Intel's documentation and collateral use
+//! (uppercase hex) and can be serialized, e.g. to JSON, for tests and tooling.
+
+use crate::{
+	decode_certificates, extract_tcb_info, DcapQuote, DcapQuoteHeader, Error, QeCertificationData,
+	SgxReportBody,
+};
+use parity_scale_codec::Decode;
+use serde::Serialize;
+use std::fmt;
+use teerex_primitives::SgxBuildMode;
+use x509_cert::Certificate;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct QuoteDescription {
+	pub header: QuoteHeaderDescription,
+	pub body: ReportBodyDescription,
+	pub qe_report: ReportBodyDescription,
+	pub certification_data: CertificationDataDescription,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct QuoteHeaderDescription {
+	pub version: u16,
+	pub attestation_key_type: u16,
+	pub qe_svn: u16,
+	pub pce_svn: u16,
+	pub qe_vendor_id: String,
+	pub user_data: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReportBodyDescription {
+	pub cpu_svn: String,
+	pub misc_select: String,
+	pub attributes_flags: String,
+	pub attributes_xfrm: String,
+	pub debug_mode: bool,
+	pub mr_enclave: String,
+	pub mr_signer: String,
+	pub isv_prod_id: u16,
+	pub isv_svn: u16,
+	pub config_svn: u16,
+	pub report_data: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CertificationDataDescription {
+	/// 5 denotes a PEM encoded PCK certificate chain, which is the only type we support.
+	pub certification_data_type: u16,
+	pub size: u32,
+	/// The embedded certificates, leaf (PCK certificate) first.
+	pub certificates: Vec<CertificateDescription>,
+	/// The TCB of the platform as stated in the PCK certificate. `None` if it can't be extracted.
+	pub pck_tcb: Option<PckTcbDescription>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CertificateDescription {
+	pub subject: String,
+	pub issuer: String,
+	pub serial_number: String,
+	/// unix epoch in milliseconds
+	pub not_before: u64,
+	/// unix epoch in milliseconds
+	pub not_after: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PckTcbDescription {
+	pub fmspc: String,
+	pub cpusvn: String,
+	pub pcesvn: u16,
+}
+
+impl fmt::Display for QuoteDescription {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let json = serde_json::to_string_pretty(self).map_err(|_| fmt::Error)?;
+		f.write_str(&json)
+	}
+}
+
+fn upper_hex(data: &[u8]) -> String {
+	hex::encode_upper(data)
+}
+
+/// Decodes a raw DCAP quote and describes it. Nothing is verified.
+pub fn describe_dcap_quote(dcap_quote_raw: &[u8]) -> Result<QuoteDescription, Error> {
+	let quote =
+		DcapQuote::decode(&mut &dcap_quote_raw[..]).map_err(|_| Error::DcapQuoteDecodingError)?;
+	Ok(quote.describe())
+}
+
+impl DcapQuote {
+	pub fn describe(&self) -> QuoteDescription {
+		QuoteDescription {
+			header: self.header.describe(),
+			body: self.body.describe(),
+			qe_report: self.quote_signature_data.qe_report.describe(),
+			certification_data: self.quote_signature_data.qe_certification_data.describe(),
+		}
+	}
+}
+
+impl DcapQuoteHeader {
+	pub fn describe(&self) -> QuoteHeaderDescription {
+		QuoteHeaderDescription {
+			version: self.version,
+			attestation_key_type: self.attestation_key_type,
+			qe_svn: self.qe_svn,
+			pce_svn: self.pce_svn,
+			qe_vendor_id: upper_hex(&self.qe_vendor_id),
+			user_data: upper_hex(&self.user_data),
+		}
+	}
+}
+
+impl SgxReportBody {
+	pub fn describe(&self) -> ReportBodyDescription {
+		ReportBodyDescription {
+			cpu_svn: upper_hex(&self.cpu_svn),
+			misc_select: upper_hex(&self.misc_select),
+			attributes_flags: format!("{:016x}", self.attributes.flags),
+			attributes_xfrm: format!("{:016x}", self.attributes.xfrm),
+			debug_mode: self.sgx_build_mode() == SgxBuildMode::Debug,
+			mr_enclave: upper_hex(&self.mr_enclave),
+			mr_signer: upper_hex(&self.mr_signer),
+			isv_prod_id: self.isv_prod_id,
+			isv_svn: self.isv_svn,
+			config_svn: self.config_svn,
+			report_data: upper_hex(&self.report_data.d),
+		}
+	}
+}
+
+impl QeCertificationData {
+	pub fn describe(&self) -> CertificationDataDescription {
+		let certs = decode_certificates(&self.certification_data).unwrap_or_default();
+		let pck_tcb =
+			certs.first().and_then(|leaf| extract_tcb_info(leaf).ok()).map(|(fmspc, tcb)| {
+				PckTcbDescription {
+					fmspc: upper_hex(&fmspc),
+					cpusvn: upper_hex(&tcb.cpusvn),
+					pcesvn: tcb.pcesvn,
+				}
+			});
+		CertificationDataDescription {
+			certification_data_type: self.certification_data_type,
+			size: self.size,
+			certificates: certs
+				.iter()
+				.map(Vec::as_slice)
+				.filter_map(describe_certificate)
+				.collect(),
+			pck_tcb,
+		}
+	}
+}
+
+/// Describes a DER encoded X.509 certificate. Returns `None` if it can't be decoded.
+pub fn describe_certificate(der_encoded: &[u8]) -> Option<CertificateDescription> {
+	let cert: Certificate = der::Decode::from_der(der_encoded).ok()?;
+	let tbs = &cert.tbs_certificate;
+	Some(CertificateDescription {
+		subject: tbs.subject.to_string(),
+		issuer: tbs.issuer.to_string(),
+		serial_number: upper_hex(tbs.serial_number.as_bytes()),
+		not_before: tbs.validity.not_before.to_unix_duration().as_millis() as u64,
+		not_after: tbs.validity.not_after.to_unix_duration().as_millis() as u64,
+	})
+}
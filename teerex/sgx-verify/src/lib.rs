@@ -34,12 +34,9 @@ pub extern crate alloc;
 use crate::{
 	collateral::{EnclaveIdentity, TcbInfo},
 	netscape_comment::NetscapeComment,
-	utils::length_from_raw_data,
 };
-use alloc::string::String;
 use chrono::DateTime;
 use core::time::Duration;
-use der::asn1::ObjectIdentifier;
 use frame_support::{ensure, traits::Len};
 use parity_scale_codec::{Decode, Encode, Input};
 use ring::signature::{self};
@@ -50,63 +47,27 @@ use sp_std::{
 	prelude::*,
 };
 use teerex_primitives::{
-	Cpusvn, Fmspc, MrEnclave, MrSigner, Pcesvn, SgxBuildMode, SgxQuotingEnclave, SgxReportData,
-	SgxStatus, TcbStatus, TcbVersionStatus, TEEREX,
+	Fmspc, MrEnclave, MrSigner, SgxBuildMode, SgxPlatformInfo, SgxQuotingEnclave, SgxReportData,
+	SgxStatus, TcbVersionStatus, TEEREX,
 };
 use x509_cert::Certificate;
 
+pub use error::{Error, ErrorContext, VerificationError};
+pub use pck::{decode_certificates, CertificateChain, PckCertificate};
+
 pub mod collateral;
 mod ephemeral_key;
+mod error;
+#[cfg(feature = "std")]
+pub mod inspect;
 mod netscape_comment;
+pub mod pck;
 #[cfg(any(test, feature = "test-data"))]
 pub mod test_data;
 #[cfg(test)]
 mod tests;
 mod utils;
 
-#[derive(Debug, Encode, Decode, Copy, Clone, TypeInfo, frame_support::PalletError, PartialEq)]
-pub enum Error {
-	CaVerificationFailed,
-	CertificateChainIsInvalid,
-	CertificateChainIsTooShort,
-	CpuSvnDecodingError,
-	CpuSvnLengthMismatch,
-	CpuSvnOidIsMissing,
-	DcapKeyTypeMismatch,
-	DcapQuoteDecodingError,
-	DcapQuoteIsTooLong,
-	DcapQuoteVersionMismatch,
-	DerEncodingError,
-	EnclaveIdentityDecodingError,
-	EnclaveIdentitySignatureIsInvalid,
-	FmspcDecodingError,
-	FmspcLengthMismatch,
-	FmspcOidIsMissing,
-	IntelExtensionAmbiguity,
-	IntelExtensionCertificateDecodingError,
-	IsvEnclaveReportSignatureIsInvalid,
-	KeyLengthIsInvalid,
-	LeafCertificateParsingError,
-	NetscapeDecodingError,
-	NetscapeDerError,
-	PceSvnDecodingError,
-	PceSvnLengthMismatch,
-	PceSvnOidIsMissing,
-	PckCertFormatMismatch,
-	PublicKeyIsInvalid,
-	QeHasRejectedEnclave,
-	QeReportHashMismatch,
-	QuoteBodyDecodingError,
-	QuoteBodyIsInvalid,
-	QuoteBodyMissing,
-	QuoteStatusMissing,
-	RsaSignatureIsInvalid,
-	SgxReportParsingError,
-	TcbInfoIsInvalid,
-	TimestampIsInvalid,
-	TimestampIsMissing,
-}
-
 #[derive(Debug, Encode, Decode, Copy, Clone, TypeInfo)]
 #[repr(C)]
 pub struct SGXAttributes {
@@ -124,6 +85,44 @@ pub struct DcapQuote {
 	quote_signature_data: EcdsaQuoteSignature,
 }
 
+impl DcapQuote {
+	/// We only support version 3 quotes with ECDSA-256-with-P-256 attestation keys and a PEM
+	/// formatted PCK certificate chain.
+	fn ensure_supported_format(&self) -> Result<(), VerificationError> {
+		let header = &self.header;
+		ensure!(
+			header.version == 3,
+			VerificationError::mismatch(
+				Error::DcapQuoteVersionMismatch,
+				"quote version",
+				3u16,
+				header.version
+			)
+		);
+		ensure!(
+			header.attestation_key_type == 2,
+			VerificationError::mismatch(
+				Error::DcapKeyTypeMismatch,
+				"attestation key type",
+				2u16,
+				header.attestation_key_type
+			)
+		);
+		let certification_data_type =
+			self.quote_signature_data.qe_certification_data.certification_data_type;
+		ensure!(
+			certification_data_type == 5,
+			VerificationError::mismatch(
+				Error::PckCertFormatMismatch,
+				"certification data type",
+				5u16,
+				certification_data_type
+			)
+		);
+		Ok(())
+	}
+}
+
 /// All the documentation about this can be found in the `PCK_Certificate_CRL_Spec-1.1` page 62.
 #[derive(Debug, Encode, Decode, Copy, Clone, TypeInfo)]
 #[repr(C)]
@@ -376,6 +375,8 @@ pub struct SgxVerifiedReport {
 	pub status: SgxStatus,
 	pub timestamp: u64, // unix timestamp in milliseconds
 	pub build_mode: SgxBuildMode,
+	/// Only DCAP quotes state the platform, in the PCK certificate
+	pub platform: Option<SgxPlatformInfo>,
 }
 
 type SignatureAlgorithms = &'static [&'static dyn webpki::types::SignatureVerificationAlgorithm];
@@ -515,26 +516,29 @@ pub fn deserialize_tcb_info(
 	res.map_err(|_| Error::TcbInfoIsInvalid)
 }
 
-/// Extract a list of certificates from a byte vec. The certificates must be separated by
-/// `-----BEGIN CERTIFICATE-----` and `-----END CERTIFICATE-----` markers
-pub fn extract_certs(cert_chain: &[u8]) -> Vec<Vec<u8>> {
-	// The certificates should be valid UTF-8 but if not we skip the invalid cert. The certificate verification
-	// will fail at a later point.
-	let certs_concat = String::from_utf8_lossy(cert_chain);
-	let certs_concat = certs_concat.replace('\n', "");
-	let certs_concat = certs_concat.replace("-----BEGIN CERTIFICATE-----", "");
-	// Use the end marker to split the string into certificates
-	let parts = certs_concat.split("-----END CERTIFICATE-----");
-	parts.filter(|p| !p.is_empty()).filter_map(|p| base64::decode(p).ok()).collect()
-}
-
 /// Verifies that the `leaf_cert` in combination with the `intermediate_certs` establishes
 /// a valid certificate chain that is rooted in one of the trust anchors that was compiled into to the pallet
 pub fn verify_certificate_chain<'a>(
 	leaf_cert: &webpki::EndEntityCert<'a>,
 	intermediate_certs: &[webpki::types::CertificateDer<'a>],
 	verification_time: u64,
-) -> Result<(), Error> {
+) -> Result<(), VerificationError> {
+	verify_certificate_chain_with_trust_anchors(
+		leaf_cert,
+		intermediate_certs,
+		verification_time,
+		DCAP_SERVER_ROOTS,
+	)
+}
+
+/// Like `verify_certificate_chain`, but rooted in the given `trust_anchors` instead of Intel's
+/// root CA. Only exposed through `test_data::synthetic`, production code must use Intel's root.
+pub(crate) fn verify_certificate_chain_with_trust_anchors<'a>(
+	leaf_cert: &webpki::EndEntityCert<'a>,
+	intermediate_certs: &[webpki::types::CertificateDer<'a>],
+	verification_time: u64,
+	trust_anchors: &[webpki::types::TrustAnchor],
+) -> Result<(), VerificationError> {
 	log::debug!(target: TEEREX, "Self::verify_certificate_chain.");
 	let time =
 		webpki::types::UnixTime::since_unix_epoch(Duration::from_secs(verification_time / 1000));
@@ -542,49 +546,86 @@ pub fn verify_certificate_chain<'a>(
 	leaf_cert
 		.verify_for_usage(
 			sig_algs,
-			DCAP_SERVER_ROOTS,
+			trust_anchors,
 			intermediate_certs,
 			time,
 			webpki::KeyUsage::client_auth(),
 			None,
 		)
 		.map_err(|e| {
-			log::warn!(target: TEEREX, "certificate chain is invalid: {:?}", e);
+			let index = rejected_certificate_index(&e, intermediate_certs, time.as_secs());
+			let error = VerificationError::certificate(Error::CertificateChainIsInvalid, index, e);
+			log::warn!(target: TEEREX, "certificate chain is invalid: {}", error);
 			#[cfg(test)]
-			println!("certificate chain is invalid: {:?}", e);
-			Error::CertificateChainIsInvalid
+			println!("certificate chain is invalid: {}", error);
+			error
 		})?;
 
 	log::debug!(target: TEEREX, "Self::verify_certificate_chain, is valid tls server cert.");
 	Ok(())
 }
 
+/// webpki does not tell which certificate of a chain it rejected. For validity period errors we
+/// can find out: if none of the intermediates is out of its validity period, it is the leaf.
+fn rejected_certificate_index(
+	reason: &webpki::Error,
+	intermediate_certs: &[webpki::types::CertificateDer],
+	time_secs: u64,
+) -> Option<usize> {
+	match reason {
+		webpki::Error::CertExpired | webpki::Error::CertNotValidYet => Some(
+			intermediate_certs
+				.iter()
+				.position(|c| !is_valid_at(c.as_ref(), time_secs))
+				.map_or(0, |i| i + 1),
+		),
+		_ => None,
+	}
+}
+
+fn is_valid_at(der_encoded: &[u8], time_secs: u64) -> bool {
+	let cert: Certificate = match der::Decode::from_der(der_encoded) {
+		Ok(cert) => cert,
+		// webpki would have complained about the encoding instead
+		Err(_) => return true,
+	};
+	let validity = cert.tbs_certificate.validity;
+	validity.not_before.to_unix_duration().as_secs() <= time_secs &&
+		time_secs <= validity.not_after.to_unix_duration().as_secs()
+}
+
 pub fn extract_tcb_info_from_raw_dcap_quote(
 	dcap_quote_raw: &[u8],
-) -> Result<(Fmspc, TcbVersionStatus), Error> {
+) -> Result<(Fmspc, TcbVersionStatus), VerificationError> {
 	let mut dcap_quote_clone = dcap_quote_raw;
 	let quote: DcapQuote =
 		Decode::decode(&mut dcap_quote_clone).map_err(|_| Error::DcapQuoteDecodingError)?;
 
-	ensure!(quote.header.version == 3, Error::DcapQuoteVersionMismatch);
-	ensure!(quote.header.attestation_key_type == 2, Error::DcapKeyTypeMismatch);
-	ensure!(
-		quote.quote_signature_data.qe_certification_data.certification_data_type == 5,
-		Error::PckCertFormatMismatch
-	);
-
-	let certs = extract_certs(&quote.quote_signature_data.qe_certification_data.certification_data);
+	quote.ensure_supported_format()?;
 
-	let (fmspc, tcb_info) = extract_tcb_info(&certs[0])?;
+	let chain = CertificateChain::decode(
+		&quote.quote_signature_data.qe_certification_data.certification_data,
+	)?;
 
-	Ok((fmspc, tcb_info))
+	Ok(extract_tcb_info(chain.leaf())?)
 }
 
 pub fn verify_dcap_quote(
 	dcap_quote_raw: &[u8],
 	verification_time: u64,
 	qe: &SgxQuotingEnclave,
-) -> Result<(Fmspc, TcbVersionStatus, SgxVerifiedReport), Error> {
+) -> Result<(Fmspc, TcbVersionStatus, SgxVerifiedReport), VerificationError> {
+	verify_dcap_quote_with_trust_anchors(dcap_quote_raw, verification_time, qe, DCAP_SERVER_ROOTS)
+}
+
+/// Like `verify_dcap_quote`, but the PCK certificate chain is rooted in the given `trust_anchors`
+/// instead of Intel's root CA. Only exposed through `test_data::synthetic`.
+pub(crate) fn verify_dcap_quote_with_trust_anchors(
+	dcap_quote_raw: &[u8],
+	verification_time: u64,
+	qe: &SgxQuotingEnclave,
+	trust_anchors: &[webpki::types::TrustAnchor],
+) -> Result<(Fmspc, TcbVersionStatus, SgxVerifiedReport), VerificationError> {
 	let mut dcap_quote_clone = dcap_quote_raw;
 	let quote: DcapQuote =
 		Decode::decode(&mut dcap_quote_clone).map_err(|_| Error::DcapQuoteDecodingError)?;
@@ -592,24 +633,24 @@ pub fn verify_dcap_quote(
 	#[cfg(test)]
 	println!("{:?}", quote);
 
-	ensure!(quote.header.version == 3, Error::DcapQuoteVersionMismatch);
-	ensure!(quote.header.attestation_key_type == 2, Error::DcapKeyTypeMismatch);
-	ensure!(
-		quote.quote_signature_data.qe_certification_data.certification_data_type == 5,
-		Error::PckCertFormatMismatch //Only support for PEM formatted PCK Cert Chain
-	);
+	quote.ensure_supported_format()?;
 	ensure!(quote.quote_signature_data.qe_report.verify(qe), Error::QeHasRejectedEnclave); //"Enclave rejected by quoting enclave"
 
-	let certs = extract_certs(&quote.quote_signature_data.qe_certification_data.certification_data);
-	ensure!(certs.len() >= 2, Error::CertificateChainIsTooShort); //"Certificate chain must have at least two certificates"
-	let intermediate_certificate_slices: Vec<webpki::types::CertificateDer> =
-		certs[1..].iter().map(|c| c.as_slice().into()).collect();
-	let leaf_cert_der = webpki::types::CertificateDer::from(certs[0].as_slice());
+	let chain = CertificateChain::decode(
+		&quote.quote_signature_data.qe_certification_data.certification_data,
+	)?;
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
 	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
 		.map_err(|_| Error::LeafCertificateParsingError)?;
-	verify_certificate_chain(&leaf_cert, &intermediate_certificate_slices, verification_time)?;
+	verify_certificate_chain_with_trust_anchors(
+		&leaf_cert,
+		&chain.intermediates(),
+		verification_time,
+		trust_anchors,
+	)?;
 
-	let (fmspc, tcb_info) = extract_tcb_info(&certs[0])?;
+	let pck = PckCertificate::from_der(chain.leaf())?;
+	let (fmspc, tcb_info) = (pck.fmspc, pck.tcb_version_status());
 
 	// For this part some understanding of the document (Especially chapter A.4: Quote Format)
 	// Intel® Software Guard Extensions (Intel® SGX) Data Center Attestation Primitives: ECDSA Quote Library API
@@ -629,14 +670,17 @@ pub fn verify_dcap_quote(
 		REPORT_SIZE +
 		REPORT_SIGNATURE_SIZE +
 		core::mem::size_of::<u16>(); //Size of the QE authentication data. We ignore this for now and assume 32. See AUTHENTICATION_DATA_SIZE
+
+	// The quote has been decoded successfully, but the offsets below assume a fixed authentication
+	// data size. Hence, they must be bounds-checked, or a crafted quote could make us panic.
+	let quote_slice = |offset: usize, len: usize| {
+		dcap_quote_raw.get(offset..(offset + len)).ok_or(Error::DcapQuoteDecodingError)
+	};
 	let mut hash_data = [0u8; ATTESTATION_KEY_SIZE + AUTHENTICATION_DATA_SIZE];
-	hash_data[0..ATTESTATION_KEY_SIZE].copy_from_slice(
-		&dcap_quote_raw[attestation_key_offset..(attestation_key_offset + ATTESTATION_KEY_SIZE)],
-	);
-	hash_data[ATTESTATION_KEY_SIZE..].copy_from_slice(
-		&dcap_quote_raw
-			[authentication_data_offset..(authentication_data_offset + AUTHENTICATION_DATA_SIZE)],
-	);
+	hash_data[0..ATTESTATION_KEY_SIZE]
+		.copy_from_slice(quote_slice(attestation_key_offset, ATTESTATION_KEY_SIZE)?);
+	hash_data[ATTESTATION_KEY_SIZE..]
+		.copy_from_slice(quote_slice(authentication_data_offset, AUTHENTICATION_DATA_SIZE)?);
 	// Ensure that the hash matches the intel signed hash in the QE report. This establishes trust into the attestation key.
 	let hash = ring::digest::digest(&ring::digest::SHA256, &hash_data);
 	ensure!(
@@ -645,13 +689,13 @@ pub fn verify_dcap_quote(
 	);
 
 	let qe_report_offset = attestation_key_offset + ATTESTATION_KEY_SIZE;
-	let qe_report_slice = &dcap_quote_raw[qe_report_offset..(qe_report_offset + REPORT_SIZE)];
+	let qe_report_slice = quote_slice(qe_report_offset, REPORT_SIZE)?;
 	let mut pub_key = [0x04u8; 65]; //Prepend 0x04 to specify uncompressed format
 	pub_key[1..].copy_from_slice(&quote.quote_signature_data.ecdsa_attestation_key);
 
 	let peer_public_key =
 		signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, pub_key);
-	let isv_report_slice = &dcap_quote_raw[0..(DCAP_QUOTE_HEADER_SIZE + REPORT_SIZE)];
+	let isv_report_slice = quote_slice(0, DCAP_QUOTE_HEADER_SIZE + REPORT_SIZE)?;
 	// Verify that the enclave data matches the signature generated by the trusted attestation key.
 	// This establishes trust into the data of the enclave we actually want to verify
 	peer_public_key
@@ -659,12 +703,9 @@ pub fn verify_dcap_quote(
 		.map_err(|_| Error::IsvEnclaveReportSignatureIsInvalid)?;
 
 	// Verify that the QE report was signed by Intel. This establishes trust into the QE report.
-	let asn1_signature = encode_as_der(&quote.quote_signature_data.qe_report_signature)?;
-	verify_signature(
-		&leaf_cert,
+	pck.verify_qe_report_signature(
 		qe_report_slice,
-		&asn1_signature,
-		webpki::ring::ECDSA_P256_SHA256,
+		&quote.quote_signature_data.qe_report_signature,
 	)?;
 	ensure!(dcap_quote_clone.is_empty(), Error::DcapQuoteIsTooLong);
 	let report = SgxVerifiedReport {
@@ -674,12 +715,13 @@ pub fn verify_dcap_quote(
 		status: SgxStatus::Invalid, // DCAP process will replace status later at 'verify_examinee'
 		timestamp: verification_time,
 		build_mode: quote.body.sgx_build_mode(),
+		platform: Some(pck.platform_info()),
 	};
 	Ok((fmspc, tcb_info, report))
 }
 
 // make sure this function doesn't panic!
-pub fn verify_ias_report(cert_der: &[u8]) -> Result<SgxVerifiedReport, Error> {
+pub fn verify_ias_report(cert_der: &[u8]) -> Result<SgxVerifiedReport, VerificationError> {
 	// Before we reach here, the runtime already verified the extrinsic is properly signed by the extrinsic sender
 	// Hence, we skip: EphemeralKey::try_from(cert)?;
 
@@ -703,7 +745,14 @@ pub fn verify_ias_report(cert_der: &[u8]) -> Result<SgxVerifiedReport, Error> {
 	let valid_until = webpki::types::UnixTime::since_unix_epoch(Duration::from_secs(1573419050));
 	verify_server_cert(&sig_cert, valid_until)?;
 
-	parse_report(netscape.attestation_raw)
+	Ok(parse_report(netscape.attestation_raw)?)
+}
+
+/// Exposes the parsing of the IAS report body to the fuzzer. It is unreachable otherwise, because
+/// the report is only parsed after its signature has been verified.
+#[cfg(fuzzing)]
+pub fn fuzz_parse_report(report_raw: &[u8]) -> Result<SgxVerifiedReport, Error> {
+	parse_report(report_raw)
 }
 
 fn parse_report(report_raw: &[u8]) -> Result<SgxVerifiedReport, Error> {
@@ -777,6 +826,7 @@ fn parse_report(report_raw: &[u8]) -> Result<SgxVerifiedReport, Error> {
 			report_data: sgx_quote.report_body.report_data,
 			timestamp: ra_timestamp,
 			build_mode: sgx_quote.report_body.sgx_build_mode(),
+			platform: None,
 		})
 	} else {
 		Err(Error::QuoteBodyMissing)
@@ -805,7 +855,7 @@ pub fn verify_signature(
 pub fn verify_server_cert(
 	sig_cert: &webpki::EndEntityCert,
 	timestamp_valid_until: webpki::types::UnixTime,
-) -> Result<(), Error> {
+) -> Result<(), VerificationError> {
 	let chain: Vec<webpki::types::CertificateDer> = Vec::new();
 	match sig_cert.verify_for_usage(
 		SUPPORTED_SIG_ALGS,
@@ -821,95 +871,14 @@ pub fn verify_server_cert(
 		},
 		Err(e) => {
 			log::info!(target: TEEREX, "CA ERROR: {}", e);
-			Err(Error::CaVerificationFailed)
+			let index = rejected_certificate_index(&e, &chain, timestamp_valid_until.as_secs());
+			Err(VerificationError::certificate(Error::CaVerificationFailed, index, e))
 		},
 	}
 }
 
-/// See document "Intel® Software Guard Extensions: PCK Certificate and Certificate Revocation List Profile Specification"
-/// https://download.01.org/intel-sgx/dcap-1.2/linux/docs/Intel_SGX_PCK_Certificate_CRL_Spec-1.1.pdf
-const INTEL_SGX_EXTENSION_OID: ObjectIdentifier =
-	ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1");
-const OID_FMSPC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.4");
-const OID_PCESVN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.2.17");
-const OID_CPUSVN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.2.18");
-
+/// Extracts the FMSPC and the TCB of the platform from a DER encoded PCK certificate.
 pub fn extract_tcb_info(cert: &[u8]) -> Result<(Fmspc, TcbVersionStatus), Error> {
-	let extension_section = get_intel_extension(cert)?;
-
-	let fmspc = get_fmspc(&extension_section)?;
-	let cpusvn = get_cpusvn(&extension_section)?;
-	let pcesvn = get_pcesvn(&extension_section)?;
-
-	Ok((fmspc, TcbVersionStatus::new(cpusvn, pcesvn, TcbStatus::Unknown)))
-}
-
-fn get_intel_extension(der_encoded: &[u8]) -> Result<Vec<u8>, Error> {
-	let cert: Certificate = der::Decode::from_der(der_encoded)
-		.map_err(|_| Error::IntelExtensionCertificateDecodingError)?;
-	let mut extension_iter = cert
-		.tbs_certificate
-		.extensions
-		.as_deref()
-		.unwrap_or(&[])
-		.iter()
-		.filter(|e| e.extn_id == INTEL_SGX_EXTENSION_OID)
-		.map(|e| e.extn_value);
-
-	let extension = extension_iter.next();
-	ensure!(
-		extension.is_some() && extension_iter.next().is_none(),
-		Error::IntelExtensionAmbiguity //"There should only be one section containing Intel extensions"
-	);
-	// SAFETY: Ensured above that extension.is_some() == true
-	Ok(extension.unwrap().to_vec())
-}
-
-fn get_fmspc(der: &[u8]) -> Result<Fmspc, Error> {
-	let bytes_oid = OID_FMSPC.as_bytes();
-	let mut offset = der
-		.windows(bytes_oid.len())
-		.position(|window| window == bytes_oid)
-		.ok_or(Error::FmspcOidIsMissing)?;
-	offset += 12; // length oid (10) + asn1 tag (1) + asn1 length10 (1)
-
-	let fmspc_size = core::mem::size_of::<Fmspc>() / core::mem::size_of::<u8>();
-	let data = der.get(offset..offset + fmspc_size).ok_or(Error::FmspcLengthMismatch)?;
-	data.try_into().map_err(|_| Error::FmspcDecodingError)
-}
-
-fn get_cpusvn(der: &[u8]) -> Result<Cpusvn, Error> {
-	let bytes_oid = OID_CPUSVN.as_bytes();
-	let mut offset = der
-		.windows(bytes_oid.len())
-		.position(|window| window == bytes_oid)
-		.ok_or(Error::CpuSvnOidIsMissing)?;
-	offset += 13; // length oid (11) + asn1 tag (1) + asn1 length10 (1)
-
-	// CPUSVN is specified to have length 16
-	let len = 16;
-	let data = der.get(offset..offset + len).ok_or(Error::CpuSvnLengthMismatch)?;
-	data.try_into().map_err(|_| Error::CpuSvnDecodingError)
-}
-
-fn get_pcesvn(der: &[u8]) -> Result<Pcesvn, Error> {
-	let bytes_oid = OID_PCESVN.as_bytes();
-	let mut offset = der
-		.windows(bytes_oid.len())
-		.position(|window| window == bytes_oid)
-		.ok_or(Error::PceSvnOidIsMissing)?;
-	// length oid + asn1 tag (1 byte)
-	offset += bytes_oid.len() + 1;
-	// PCESVN can be 1 or 2 bytes
-	let len = length_from_raw_data(der, &mut offset).map_err(|_| Error::PceSvnDecodingError)?;
-	offset += 1; // length_from_raw_data does not move the offset when the length is encoded in a single byte
-	ensure!(len == 1 || len == 2, Error::PceSvnLengthMismatch);
-	let data = der.get(offset..offset + len).ok_or(Error::PceSvnLengthMismatch)?;
-	if data.len() == 1 {
-		Ok(u16::from(data[0]))
-	} else {
-		// Unwrap is fine here as we check the length above
-		// DER integers are encoded in big endian
-		Ok(u16::from_be_bytes(data.try_into().unwrap()))
-	}
+	let pck = PckCertificate::from_der(cert)?;
+	Ok((pck.fmspc, pck.tcb_version_status()))
 }
@@ -0,0 +1,569 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! PCK certificates and the certificate chains they are shipped in.
+//!
+//! The PCK certificate is the leaf of the chain in a DCAP quote. Intel states the TCB of the
+//! platform in a custom extension, which the `PCK_Certificate_CRL_Spec-1.1` describes.
+//! The chains of the collateral use the same encoding, hence they are decoded here too.
+
+use crate::{Error, VerificationError};
+use der::asn1::ObjectIdentifier;
+use frame_support::ensure;
+use ring::signature;
+use sp_std::{convert::TryInto, prelude::*};
+use teerex_primitives::{
+	Cpusvn, Fmspc, PceId, Pcesvn, PlatformInstanceId, SgxPlatformConfiguration, SgxPlatformInfo,
+	SgxType, TcbStatus, TcbVersionStatus,
+};
+use x509_cert::Certificate;
+
+const PEM_BEGIN: &[u8] = b"-----BEGIN CERTIFICATE-----";
+const PEM_END: &[u8] = b"-----END CERTIFICATE-----";
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_ENUMERATED: u8 = 0x0a;
+const TAG_SEQUENCE: u8 = 0x30;
+
+const OID_SGX_EXTENSION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1");
+const OID_TCB: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.2");
+const OID_PCESVN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.2.17");
+const OID_CPUSVN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.2.18");
+const OID_PCEID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.3");
+const OID_FMSPC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.4");
+const OID_SGX_TYPE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.5");
+const OID_PLATFORM_INSTANCE_ID: ObjectIdentifier =
+	ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.6");
+const OID_CONFIGURATION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.7");
+const OID_DYNAMIC_PLATFORM: ObjectIdentifier =
+	ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.7.1");
+const OID_CACHED_KEYS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.7.2");
+const OID_SMT_ENABLED: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113741.1.13.1.7.3");
+
+/// Decodes a certificate chain into its DER encoded certificates, in the order they appear.
+///
+/// The chain is either a concatenation of DER encoded certificates or PEM encoded. Text outside
+/// of the PEM blocks is ignored, e.g. the terminating null byte Intel appends in DCAP quotes.
+pub fn decode_certificates(chain: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+	if chain.first() == Some(&TAG_SEQUENCE) {
+		decode_der_certificates(chain)
+	} else {
+		decode_pem_certificates(chain)
+	}
+}
+
+fn decode_der_certificates(mut chain: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+	let mut certificates = Vec::new();
+	while !chain.is_empty() {
+		let (tlv, rest) = split_tlv(chain).ok_or(Error::CertificateChainDecodingError)?;
+		ensure!(tlv.tag == TAG_SEQUENCE, Error::CertificateChainDecodingError);
+		certificates.push(chain[..chain.len() - rest.len()].to_vec());
+		chain = rest;
+	}
+	Ok(certificates)
+}
+
+fn decode_pem_certificates(chain: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+	let mut certificates = Vec::new();
+	let mut block: Option<Vec<u8>> = None;
+	for line in chain.split(|b| *b == b'\n').map(trim) {
+		if line == PEM_BEGIN {
+			ensure!(block.is_none(), Error::CertificateChainDecodingError);
+			block = Some(Vec::new());
+		} else if line == PEM_END {
+			let encoded = block.take().ok_or(Error::CertificateChainDecodingError)?;
+			certificates
+				.push(base64::decode(encoded).map_err(|_| Error::CertificateChainDecodingError)?);
+		} else if let Some(encoded) = block.as_mut() {
+			encoded.extend_from_slice(line);
+		}
+	}
+	ensure!(block.is_none(), Error::CertificateChainDecodingError);
+	Ok(certificates)
+}
+
+fn trim(line: &[u8]) -> &[u8] {
+	let is_padding = |b: &u8| b.is_ascii_whitespace() || *b == 0;
+	let start = line.iter().position(|b| !is_padding(b)).unwrap_or(line.len());
+	let end = line.iter().rposition(|b| !is_padding(b)).map_or(start, |i| i + 1);
+	&line[start..end]
+}
+
+/// A certificate chain as Intel ships it: leaf first, followed by at least one issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateChain {
+	certificates: Vec<Vec<u8>>,
+}
+
+impl CertificateChain {
+	/// Decodes a PEM or DER encoded chain, see `decode_certificates`.
+	pub fn decode(chain: &[u8]) -> Result<Self, VerificationError> {
+		let certificates = decode_certificates(chain)?;
+		ensure!(
+			certificates.len() >= 2,
+			VerificationError::mismatch(
+				Error::CertificateChainIsTooShort,
+				"certificate count",
+				2u64,
+				certificates.len() as u64
+			)
+		);
+		Ok(Self { certificates })
+	}
+
+	/// The DER encoded leaf certificate.
+	pub fn leaf(&self) -> &[u8] {
+		&self.certificates[0]
+	}
+
+	/// The issuers of the leaf, in the form webpki takes them.
+	pub fn intermediates(&self) -> Vec<webpki::types::CertificateDer<'_>> {
+		self.certificates[1..].iter().map(|c| c.as_slice().into()).collect()
+	}
+
+	/// All DER encoded certificates, leaf first.
+	pub fn certificates(&self) -> &[Vec<u8>] {
+		&self.certificates
+	}
+}
+
+/// The content of a PCK certificate that matters to the verification of a quote.
+///
+/// Decoding it does not verify anything, the chain it came in has to be verified separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PckCertificate {
+	pub fmspc: Fmspc,
+	pub pce_id: PceId,
+	pub cpusvn: Cpusvn,
+	/// The components of the CPUSVN as Intel lists them individually. They are the same as the
+	/// bytes of `cpusvn` for all platforms we know.
+	pub cpusvn_components: [u8; 16],
+	pub pcesvn: Pcesvn,
+	pub sgx_type: SgxType,
+	/// Only present in certificates issued by the Platform CA.
+	pub platform_instance_id: Option<PlatformInstanceId>,
+	/// Only present in certificates issued by the Platform CA.
+	pub configuration: Option<SgxPlatformConfiguration>,
+	/// unix epoch in milliseconds
+	pub not_before: u64,
+	/// unix epoch in milliseconds
+	pub not_after: u64,
+	pub serial_number: Vec<u8>,
+	/// uncompressed P-256 point
+	pub public_key: Vec<u8>,
+}
+
+impl PckCertificate {
+	pub fn from_der(der_encoded: &[u8]) -> Result<Self, Error> {
+		let cert: Certificate = der::Decode::from_der(der_encoded)
+			.map_err(|_| Error::IntelExtensionCertificateDecodingError)?;
+		let tbs = &cert.tbs_certificate;
+
+		let mut extension_iter = tbs
+			.extensions
+			.as_deref()
+			.unwrap_or(&[])
+			.iter()
+			.filter(|e| e.extn_id == OID_SGX_EXTENSION)
+			.map(|e| e.extn_value);
+		let extension = extension_iter.next();
+		ensure!(
+			extension.is_some() && extension_iter.next().is_none(),
+			Error::IntelExtensionAmbiguity //"There should only be one section containing Intel extensions"
+		);
+		// SAFETY: Ensured above that extension.is_some() == true
+		let (sgx_extensions, rest) =
+			split_tlv(extension.unwrap()).ok_or(Error::IntelExtensionDecodingError)?;
+		ensure!(rest.is_empty(), Error::IntelExtensionDecodingError);
+		let sgx_extensions = ExtensionEntries::decode(&sgx_extensions)?;
+		let tcb = match sgx_extensions.find(&OID_TCB) {
+			Some(tcb) => ExtensionEntries::decode(tcb)?,
+			None => ExtensionEntries::default(),
+		};
+
+		let fmspc = sgx_extensions
+			.find(&OID_FMSPC)
+			.ok_or(Error::FmspcOidIsMissing)?
+			.octets(Error::FmspcDecodingError, Error::FmspcLengthMismatch)?;
+		let pce_id = sgx_extensions
+			.find(&OID_PCEID)
+			.ok_or(Error::PceIdOidIsMissing)?
+			.octets(Error::PceIdDecodingError, Error::PceIdDecodingError)?;
+		let cpusvn = tcb
+			.find(&OID_CPUSVN)
+			.ok_or(Error::CpuSvnOidIsMissing)?
+			.octets(Error::CpuSvnDecodingError, Error::CpuSvnLengthMismatch)?;
+		let mut cpusvn_components = [0u8; 16];
+		for (i, component) in cpusvn_components.iter_mut().enumerate() {
+			let value = tcb
+				.find_component(i as u8 + 1)
+				.ok_or(Error::CpuSvnOidIsMissing)?
+				.unsigned(TAG_INTEGER)
+				.ok_or(Error::CpuSvnDecodingError)?;
+			*component = value.try_into().map_err(|_| Error::CpuSvnDecodingError)?;
+		}
+		let pcesvn: Pcesvn = tcb
+			.find(&OID_PCESVN)
+			.ok_or(Error::PceSvnOidIsMissing)?
+			.unsigned(TAG_INTEGER)
+			.ok_or(Error::PceSvnDecodingError)?
+			.try_into()
+			.map_err(|_| Error::PceSvnLengthMismatch)?;
+		let sgx_type = match sgx_extensions
+			.find(&OID_SGX_TYPE)
+			.ok_or(Error::SgxTypeOidIsMissing)?
+			.unsigned(TAG_ENUMERATED)
+		{
+			Some(0) => SgxType::Standard,
+			Some(1) => SgxType::Scalable,
+			Some(2) => SgxType::ScalableWithIntegrity,
+			_ => return Err(Error::SgxTypeDecodingError),
+		};
+		let platform_instance_id = sgx_extensions
+			.find(&OID_PLATFORM_INSTANCE_ID)
+			.map(|id| {
+				id.octets(
+					Error::PlatformInstanceIdDecodingError,
+					Error::PlatformInstanceIdDecodingError,
+				)
+			})
+			.transpose()?;
+		let configuration = sgx_extensions
+			.find(&OID_CONFIGURATION)
+			.map(|c| -> Result<_, Error> {
+				let flags = ExtensionEntries::decode(c)?;
+				let flag = |oid: &ObjectIdentifier| -> Result<bool, Error> {
+					flags
+						.find(oid)
+						.ok_or(Error::PlatformConfigurationDecodingError)?
+						.boolean()
+						.ok_or(Error::PlatformConfigurationDecodingError)
+				};
+				Ok(SgxPlatformConfiguration {
+					dynamic_platform: flag(&OID_DYNAMIC_PLATFORM)?,
+					cached_keys: flag(&OID_CACHED_KEYS)?,
+					smt_enabled: flag(&OID_SMT_ENABLED)?,
+				})
+			})
+			.transpose()?;
+
+		Ok(Self {
+			fmspc,
+			pce_id,
+			cpusvn,
+			cpusvn_components,
+			pcesvn,
+			sgx_type,
+			platform_instance_id,
+			configuration,
+			not_before: tbs.validity.not_before.to_unix_duration().as_millis() as u64,
+			not_after: tbs.validity.not_after.to_unix_duration().as_millis() as u64,
+			serial_number: tbs.serial_number.as_bytes().to_vec(),
+			public_key: tbs.subject_public_key_info.subject_public_key.to_vec(),
+		})
+	}
+
+	/// The TCB of the platform, its status is only known once it is looked up in the TCB info.
+	pub fn tcb_version_status(&self) -> TcbVersionStatus {
+		TcbVersionStatus::new(self.cpusvn, self.pcesvn, TcbStatus::Unknown)
+	}
+
+	/// What the certificate states about the platform, to be recorded with the enclave.
+	pub fn platform_info(&self) -> SgxPlatformInfo {
+		SgxPlatformInfo {
+			sgx_type: self.sgx_type,
+			pce_id: self.pce_id,
+			platform_instance_id: self.platform_instance_id,
+			configuration: self.configuration,
+		}
+	}
+
+	/// Verifies that the quoting enclave's report has been signed by this certificate's key.
+	///
+	/// * `signature` - r and s, 32 bytes each, as they are in the quote.
+	pub fn verify_qe_report_signature(
+		&self,
+		qe_report: &[u8],
+		signature: &[u8],
+	) -> Result<(), Error> {
+		signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, &self.public_key)
+			.verify(qe_report, signature)
+			.map_err(|_| Error::QeReportSignatureIsInvalid)
+	}
+}
+
+/// A DER encoded value, split into its tag and contents.
+struct Tlv<'a> {
+	tag: u8,
+	value: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+	/// The contents of an OCTET STRING of exactly `N` bytes.
+	fn octets<const N: usize>(
+		&self,
+		decoding_error: Error,
+		length_error: Error,
+	) -> Result<[u8; N], Error> {
+		ensure!(self.tag == TAG_OCTET_STRING, decoding_error);
+		self.value.try_into().map_err(|_| length_error)
+	}
+
+	/// A BOOLEAN, which DER encodes in a single byte.
+	fn boolean(&self) -> Option<bool> {
+		match (self.tag, self.value) {
+			(TAG_BOOLEAN, [0x00]) => Some(false),
+			(TAG_BOOLEAN, [0xff]) => Some(true),
+			_ => None,
+		}
+	}
+
+	/// A non-negative INTEGER or ENUMERATED that fits into 64 bits.
+	fn unsigned(&self, tag: u8) -> Option<u64> {
+		let (first, _) = self.value.split_first()?;
+		if self.tag != tag || first & 0x80 != 0 {
+			return None
+		}
+		let value = if *first == 0 { &self.value[1..] } else { self.value };
+		if value.len() > 8 {
+			return None
+		}
+		Some(value.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+	}
+}
+
+/// Splits the first value off `data`. Only single byte tags and definite lengths of up to four
+/// bytes are supported, which is all certificates need.
+fn split_tlv(data: &[u8]) -> Option<(Tlv, &[u8])> {
+	let (tag, rest) = data.split_first()?;
+	let (first, rest) = rest.split_first()?;
+	let (len, rest) = if *first < 0x80 {
+		(*first as usize, rest)
+	} else {
+		let len_size = (first & 0x7f) as usize;
+		if len_size == 0 || len_size > 4 || rest.len() < len_size {
+			return None
+		}
+		let (len, rest) = rest.split_at(len_size);
+		(len.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize), rest)
+	};
+	if rest.len() < len {
+		return None
+	}
+	let (value, rest) = rest.split_at(len);
+	Some((Tlv { tag: *tag, value }, rest))
+}
+
+/// The Intel SGX extension is a SEQUENCE of SEQUENCEs, each consisting of an OID and a value.
+#[derive(Default)]
+struct ExtensionEntries<'a> {
+	entries: Vec<(&'a [u8], Tlv<'a>)>,
+}
+
+impl<'a> ExtensionEntries<'a> {
+	fn decode(sequence: &Tlv<'a>) -> Result<Self, Error> {
+		ensure!(sequence.tag == TAG_SEQUENCE, Error::IntelExtensionDecodingError);
+		let mut entries = Vec::new();
+		let mut data = sequence.value;
+		while !data.is_empty() {
+			let (entry, rest) = split_tlv(data).ok_or(Error::IntelExtensionDecodingError)?;
+			ensure!(entry.tag == TAG_SEQUENCE, Error::IntelExtensionDecodingError);
+			let (oid, value) = split_tlv(entry.value).ok_or(Error::IntelExtensionDecodingError)?;
+			let (value, tail) = split_tlv(value).ok_or(Error::IntelExtensionDecodingError)?;
+			ensure!(oid.tag == TAG_OID && tail.is_empty(), Error::IntelExtensionDecodingError);
+			entries.push((oid.value, value));
+			data = rest;
+		}
+		Ok(Self { entries })
+	}
+
+	fn find(&self, oid: &ObjectIdentifier) -> Option<&Tlv<'a>> {
+		self.find_by_bytes(oid.as_bytes())
+	}
+
+	/// The CPUSVN components are numbered 1 to 16 below the TCB OID.
+	fn find_component(&self, number: u8) -> Option<&Tlv<'a>> {
+		self.find_by_bytes(&[OID_TCB.as_bytes(), &[number][..]].concat())
+	}
+
+	fn find_by_bytes(&self, oid: &[u8]) -> Option<&Tlv<'a>> {
+		self.entries.iter().find(|(id, _)| *id == oid).map(|(_, value)| value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test_data::{
+		dcap::{DCAP_QUOTE_CERT, QE_IDENTITY_CERT, QE_IDENTITY_ISSUER_CHAIN},
+		synthetic::{MultiPackagePlatform, PlatformTcb, SyntheticPki},
+	};
+	use frame_support::assert_err;
+	use hex_literal::hex;
+
+	fn dcap_quote_cert() -> Vec<u8> {
+		base64::decode(DCAP_QUOTE_CERT.replace('\n', "")).unwrap()
+	}
+
+	#[test]
+	fn decode_pem_chain_works() {
+		let certs = decode_certificates(QE_IDENTITY_ISSUER_CHAIN).unwrap();
+		assert_eq!(2, certs.len());
+		for cert in &certs {
+			assert!(<Certificate as der::Decode>::from_der(cert).is_ok());
+		}
+	}
+
+	#[test]
+	fn decode_der_chain_equals_pem_chain() {
+		let certs = decode_certificates(QE_IDENTITY_ISSUER_CHAIN).unwrap();
+		assert_eq!(certs, decode_certificates(&certs.concat()).unwrap());
+	}
+
+	#[test]
+	fn decode_pem_chain_ignores_padding_and_line_endings() {
+		let pem = String::from_utf8(QE_IDENTITY_ISSUER_CHAIN.to_vec()).unwrap();
+		let padded = format!("\n{}\n\0", pem.replace('\n', "\r\n"));
+		assert_eq!(
+			decode_certificates(QE_IDENTITY_ISSUER_CHAIN).unwrap(),
+			decode_certificates(padded.as_bytes()).unwrap()
+		);
+	}
+
+	#[test]
+	fn decode_malformed_chain_fails() {
+		let unterminated = &QE_IDENTITY_ISSUER_CHAIN[..QE_IDENTITY_ISSUER_CHAIN.len() - 30];
+		assert_err!(decode_certificates(unterminated), Error::CertificateChainDecodingError);
+
+		let invalid_base64 = b"-----BEGIN CERTIFICATE-----\n!!!!\n-----END CERTIFICATE-----\n";
+		assert_err!(decode_certificates(invalid_base64), Error::CertificateChainDecodingError);
+
+		let der = dcap_quote_cert();
+		assert_err!(
+			decode_certificates(&der[..der.len() - 1]),
+			Error::CertificateChainDecodingError
+		);
+	}
+
+	#[test]
+	fn certificate_chain_needs_an_issuer() {
+		let leaf = dcap_quote_cert();
+		assert_eq!(
+			CertificateChain::decode(&leaf).unwrap_err(),
+			VerificationError::mismatch(
+				Error::CertificateChainIsTooShort,
+				"certificate count",
+				2u64,
+				1u64
+			)
+		);
+
+		let chain = CertificateChain::decode(QE_IDENTITY_ISSUER_CHAIN).unwrap();
+		assert_eq!(chain.leaf(), chain.certificates()[0].as_slice());
+		assert_eq!(1, chain.intermediates().len());
+	}
+
+	#[test]
+	fn parse_pck_certificate_works() {
+		let pck = PckCertificate::from_der(&dcap_quote_cert()).unwrap();
+		assert_eq!(hex!("00906EA10000"), pck.fmspc);
+		assert_eq!([0, 0], pck.pce_id);
+		assert_eq!(hex!("11110204018007000000000000000000"), pck.cpusvn);
+		assert_eq!(pck.cpusvn, pck.cpusvn_components);
+		assert_eq!(11, pck.pcesvn);
+		assert_eq!(SgxType::Standard, pck.sgx_type);
+		// issued by the Processor CA
+		assert_eq!(None, pck.platform_instance_id);
+		assert_eq!(None, pck.configuration);
+		assert_eq!(1_653_318_424_000, pck.not_before);
+		assert_eq!(1_874_243_224_000, pck.not_after);
+		assert_eq!(hex!("CC96AA50F79A4C6ED85858B8FB6B0A9D9B21DD3A").to_vec(), pck.serial_number);
+		assert_eq!(65, pck.public_key.len());
+		assert_eq!(
+			TcbVersionStatus::new(pck.cpusvn, pck.pcesvn, TcbStatus::Unknown),
+			pck.tcb_version_status()
+		);
+	}
+
+	#[test]
+	fn parse_synthetic_pck_certificate_works() {
+		let tcb = PlatformTcb { fmspc: hex!("00A067110000"), cpusvn: [0x90u8; 16], pcesvn: 300 };
+		let pki = SyntheticPki::new(tcb, 1_672_531_200_000, 1_988_150_400_000);
+		let chain = CertificateChain::decode(&pki.pck_certificate_chain()).unwrap();
+
+		let pck = PckCertificate::from_der(chain.leaf()).unwrap();
+		assert_eq!(tcb.fmspc, pck.fmspc);
+		assert_eq!(tcb.cpusvn, pck.cpusvn);
+		assert_eq!(tcb.cpusvn, pck.cpusvn_components);
+		assert_eq!(tcb.pcesvn, pck.pcesvn);
+		assert_eq!(1_672_531_200_000, pck.not_before);
+		assert_eq!(None, pck.configuration);
+	}
+
+	#[test]
+	fn parse_multi_package_pck_certificate_works() {
+		let platform = MultiPackagePlatform {
+			sgx_type: SgxType::ScalableWithIntegrity,
+			platform_instance_id: [0xab; 16],
+			configuration: SgxPlatformConfiguration {
+				dynamic_platform: true,
+				cached_keys: false,
+				smt_enabled: true,
+			},
+		};
+		let pki = SyntheticPki::new_multi_package(
+			PlatformTcb::default(),
+			platform,
+			1_672_531_200_000,
+			1_988_150_400_000,
+		);
+		let chain = CertificateChain::decode(&pki.pck_certificate_chain()).unwrap();
+
+		let pck = PckCertificate::from_der(chain.leaf()).unwrap();
+		assert_eq!(
+			SgxPlatformInfo {
+				sgx_type: SgxType::ScalableWithIntegrity,
+				pce_id: [0, 0],
+				platform_instance_id: Some([0xab; 16]),
+				configuration: Some(platform.configuration),
+			},
+			pck.platform_info()
+		);
+	}
+
+	#[test]
+	fn certificate_without_sgx_extension_is_rejected() {
+		let cert = base64::decode(QE_IDENTITY_CERT.replace('\n', "")).unwrap();
+		assert_err!(PckCertificate::from_der(&cert), Error::IntelExtensionAmbiguity);
+		assert_err!(
+			PckCertificate::from_der(&[0x30, 0x00]),
+			Error::IntelExtensionCertificateDecodingError
+		);
+	}
+
+	#[test]
+	fn qe_report_signature_must_match_pck_key() {
+		let pck = PckCertificate::from_der(&dcap_quote_cert()).unwrap();
+		assert_err!(
+			pck.verify_qe_report_signature(&[0u8; 384], &[1u8; 64]),
+			Error::QeReportSignatureIsInvalid
+		);
+	}
+}
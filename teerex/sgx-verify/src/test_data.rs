@@ -179,3 +179,6 @@ pub mod consts {
 	pub const URL: &[u8] =
 		&[119, 115, 58, 47, 47, 49, 50, 55, 46, 48, 46, 48, 46, 49, 58, 57, 57, 57, 49];
 }
+
+#[cfg(feature = "std")]
+pub mod synthetic;
@@ -0,0 +1,704 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Synthetic DCAP collateral signed by a throwaway PKI.
+//!
+//! The captured test data expires and pins us to one FMSPC and one point in time. This module
+//! mimics Intel's PKI instead: a root CA, a PCK platform CA with a PCK leaf carrying the SGX
+//! extensions, and a collateral signing certificate. It signs TCB infos and QE identities and
+//! builds DCAP v3 quotes for arbitrary enclaves.
+//!
+//! Everything produced here is only valid when verified against `SyntheticPki::trust_anchor`,
+//! e.g. with `SyntheticPki::verify_dcap_quote`.
+
+use crate::{
+	encode_as_der, verify_dcap_quote_with_trust_anchors, DcapQuoteHeader, SGXAttributes,
+	SgxReportBody, SgxVerifiedReport, VerificationError, ATTESTATION_KEY_SIZE, SGX_FLAGS_DEBUG,
+	SGX_REPORT_BODY_RESERVED1_BYTES, SGX_REPORT_BODY_RESERVED2_BYTES,
+	SGX_REPORT_BODY_RESERVED3_BYTES, SGX_REPORT_BODY_RESERVED4_BYTES,
+};
+use chrono::{Datelike, NaiveDateTime};
+use parity_scale_codec::Encode;
+use ring::{
+	rand::SystemRandom,
+	signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde_json::json;
+use teerex_primitives::{
+	Cpusvn, Fmspc, MrEnclave, MrSigner, Pcesvn, PlatformInstanceId, SgxBuildMode,
+	SgxPlatformConfiguration, SgxQuotingEnclave, SgxReportData, SgxType, TcbStatus,
+	TcbVersionStatus,
+};
+
+/// MRSIGNER of the synthetic quoting enclave.
+pub const SYNTHETIC_QE_MRSIGNER: MrSigner = [0x51; 32];
+/// ISVPRODID of the synthetic quoting enclave, equal to Intel's.
+pub const SYNTHETIC_QE_ISVPRODID: u16 = 1;
+/// ISVSVN of the synthetic quoting enclave.
+pub const SYNTHETIC_QE_ISVSVN: u16 = 8;
+
+const QE_AUTHENTICATION_DATA: [u8; 32] = [0x42; 32];
+/// PEM formatted PCK certificate chain
+const CERTIFICATION_DATA_TYPE_PCK_CHAIN: u16 = 5;
+
+/// The TCB of the synthetic platform, as stated in its PCK certificate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlatformTcb {
+	pub fmspc: Fmspc,
+	pub cpusvn: Cpusvn,
+	pub pcesvn: Pcesvn,
+}
+
+impl Default for PlatformTcb {
+	fn default() -> Self {
+		Self { fmspc: [0x00, 0x90, 0x6E, 0xA1, 0x00, 0x00], cpusvn: [3u8; 16], pcesvn: 11 }
+	}
+}
+
+/// The extensions only the PCK certificates of multi-package platforms carry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MultiPackagePlatform {
+	pub sgx_type: SgxType,
+	pub platform_instance_id: PlatformInstanceId,
+	pub configuration: SgxPlatformConfiguration,
+}
+
+/// A TCB info or QE identity body together with its raw (r || s) signature, i.e. the arguments of
+/// `deserialize_tcb_info` and `deserialize_enclave_identity`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedCollateral {
+	pub data: Vec<u8>,
+	pub signature: [u8; 64],
+}
+
+/// The enclave to be attested by a synthetic quote.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct QuotedEnclave {
+	pub mr_enclave: MrEnclave,
+	pub mr_signer: MrSigner,
+	pub report_data: SgxReportData,
+	pub build_mode: SgxBuildMode,
+	pub isv_prod_id: u16,
+	pub isv_svn: u16,
+}
+
+impl QuotedEnclave {
+	pub fn new(mr_enclave: MrEnclave, report_data: SgxReportData) -> Self {
+		Self {
+			mr_enclave,
+			mr_signer: [0x33; 32],
+			report_data,
+			build_mode: SgxBuildMode::Production,
+			isv_prod_id: 0,
+			isv_svn: 0,
+		}
+	}
+
+	pub fn with_build_mode(mut self, build_mode: SgxBuildMode) -> Self {
+		self.build_mode = build_mode;
+		self
+	}
+}
+
+struct Issuer {
+	key: EcdsaKeyPair,
+	/// DER encoded `Name`
+	name: Vec<u8>,
+	certificate: Vec<u8>,
+}
+
+/// A throwaway PKI modelled after Intel's SGX PKI.
+pub struct SyntheticPki {
+	platform_tcb: PlatformTcb,
+	root: Issuer,
+	pck_platform_ca: Issuer,
+	pck: Issuer,
+	collateral_signer: Issuer,
+	attestation_key: EcdsaKeyPair,
+}
+
+impl SyntheticPki {
+	/// Generates fresh keys and certificates that are valid in `[not_before, not_after]`
+	/// (unix epoch in milliseconds).
+	pub fn new(platform_tcb: PlatformTcb, not_before: u64, not_after: u64) -> Self {
+		Self::build(platform_tcb, None, not_before, not_after)
+	}
+
+	/// Like `new`, but the PCK certificate is the one of a multi-package platform.
+	pub fn new_multi_package(
+		platform_tcb: PlatformTcb,
+		platform: MultiPackagePlatform,
+		not_before: u64,
+		not_after: u64,
+	) -> Self {
+		Self::build(platform_tcb, Some(platform), not_before, not_after)
+	}
+
+	fn build(
+		platform_tcb: PlatformTcb,
+		multi_package: Option<MultiPackagePlatform>,
+		not_before: u64,
+		not_after: u64,
+	) -> Self {
+		let validity = asn1::validity(not_before, not_after);
+
+		let root_key = generate_key();
+		let root_name = asn1::name("Synthetic SGX Root CA");
+		let root_certificate = asn1::certificate(
+			1,
+			&root_name,
+			&root_name,
+			&validity,
+			&root_key,
+			&root_key,
+			&[asn1::basic_constraints_ca()],
+		);
+		let root = Issuer { key: root_key, name: root_name, certificate: root_certificate };
+
+		let pck_platform_ca = root.issue(2, "Synthetic SGX PCK Platform CA", &validity, true, &[]);
+		let pck = pck_platform_ca.issue(
+			3,
+			"Synthetic SGX PCK Certificate",
+			&validity,
+			false,
+			&[asn1::sgx_extensions(&platform_tcb, multi_package.as_ref())],
+		);
+		let collateral_signer = root.issue(4, "Synthetic SGX TCB Signing", &validity, false, &[]);
+
+		Self {
+			platform_tcb,
+			root,
+			pck_platform_ca,
+			pck,
+			collateral_signer,
+			attestation_key: generate_key(),
+		}
+	}
+
+	pub fn platform_tcb(&self) -> PlatformTcb {
+		self.platform_tcb
+	}
+
+	/// The trust anchor to verify all synthetic certificate chains against.
+	pub fn trust_anchor(&self) -> webpki::types::TrustAnchor<'_> {
+		let (subject, spki) = asn1::trust_anchor_parts(&self.root.certificate);
+		webpki::types::TrustAnchor {
+			subject: webpki::types::Der::from_slice(subject),
+			subject_public_key_info: webpki::types::Der::from_slice(spki),
+			name_constraints: None,
+		}
+	}
+
+	/// Like `verify_dcap_quote`, but rooted in `trust_anchor` instead of Intel's root CA.
+	pub fn verify_dcap_quote(
+		&self,
+		dcap_quote_raw: &[u8],
+		verification_time: u64,
+		qe: &SgxQuotingEnclave,
+	) -> Result<(Fmspc, TcbVersionStatus, SgxVerifiedReport), VerificationError> {
+		verify_dcap_quote_with_trust_anchors(
+			dcap_quote_raw,
+			verification_time,
+			qe,
+			&[self.trust_anchor()],
+		)
+	}
+
+	/// The PEM chain embedded into quotes: PCK certificate, PCK platform CA, root CA.
+	pub fn pck_certificate_chain(&self) -> Vec<u8> {
+		pem_chain(&[
+			self.pck.certificate.as_slice(),
+			self.pck_platform_ca.certificate.as_slice(),
+			self.root.certificate.as_slice(),
+		])
+	}
+
+	/// The PEM chain of the collateral signer, as needed for `register_tcb_info` and
+	/// `register_quoting_enclave`.
+	pub fn collateral_certificate_chain(&self) -> Vec<u8> {
+		pem_chain(&[
+			self.collateral_signer.certificate.as_slice(),
+			self.root.certificate.as_slice(),
+		])
+	}
+
+	/// Signs a TCB info for this platform's FMSPC. `tcb_levels` must be ordered from the most
+	/// recent to the oldest level, like Intel does.
+	pub fn sign_tcb_info(
+		&self,
+		tcb_levels: &[(Cpusvn, Pcesvn, TcbStatus)],
+		issue_date: u64,
+		next_update: u64,
+	) -> SignedCollateral {
+		let tcb_levels: Vec<_> = tcb_levels
+			.iter()
+			.map(|(cpusvn, pcesvn, status)| {
+				let components: Vec<_> = cpusvn.iter().map(|svn| json!({ "svn": svn })).collect();
+				json!({
+					"tcb": { "sgxtcbcomponents": components, "pcesvn": pcesvn },
+					"tcbDate": rfc3339(issue_date),
+					"tcbStatus": status,
+				})
+			})
+			.collect();
+		let tcb_info = json!({
+			"id": "SGX",
+			"version": 3,
+			"issueDate": rfc3339(issue_date),
+			"nextUpdate": rfc3339(next_update),
+			"fmspc": hex::encode_upper(self.platform_tcb.fmspc),
+			"pceId": "0000",
+			"tcbType": 0,
+			"tcbEvaluationDataNumber": 1,
+			"tcbLevels": tcb_levels,
+		});
+		self.sign_collateral(serde_json::to_vec(&tcb_info).expect("json values serialize"))
+	}
+
+	/// Signs a TCB info with a single `UpToDate` level matching this platform exactly.
+	pub fn sign_up_to_date_tcb_info(&self, issue_date: u64, next_update: u64) -> SignedCollateral {
+		let tcb = self.platform_tcb;
+		self.sign_tcb_info(
+			&[(tcb.cpusvn, tcb.pcesvn, TcbStatus::UpToDate)],
+			issue_date,
+			next_update,
+		)
+	}
+
+	/// Signs the identity of the synthetic quoting enclave.
+	pub fn sign_qe_identity(&self, issue_date: u64, next_update: u64) -> SignedCollateral {
+		let qe_identity = json!({
+			"id": "QE",
+			"version": 2,
+			"issueDate": rfc3339(issue_date),
+			"nextUpdate": rfc3339(next_update),
+			"tcbEvaluationDataNumber": 1,
+			"miscselect": "00000000",
+			"miscselectMask": "FFFFFFFF",
+			"attributes": "11000000000000000000000000000000",
+			"attributesMask": "FBFFFFFFFFFFFFFF0000000000000000",
+			"mrsigner": hex::encode_upper(SYNTHETIC_QE_MRSIGNER),
+			"isvprodid": SYNTHETIC_QE_ISVPRODID,
+			"tcbLevels": [{
+				"tcb": { "isvsvn": SYNTHETIC_QE_ISVSVN },
+				"tcbDate": rfc3339(issue_date),
+				"tcbStatus": TcbStatus::UpToDate,
+			}],
+		});
+		self.sign_collateral(serde_json::to_vec(&qe_identity).expect("json values serialize"))
+	}
+
+	/// Builds a DCAP v3 quote for `enclave` that is signed by the attestation key of the
+	/// synthetic quoting enclave.
+	pub fn build_quote(&self, enclave: &QuotedEnclave) -> Vec<u8> {
+		let header = DcapQuoteHeader {
+			version: 3,
+			attestation_key_type: 2,
+			reserved: 0,
+			qe_svn: SYNTHETIC_QE_ISVSVN,
+			pce_svn: self.platform_tcb.pcesvn,
+			qe_vendor_id: [0x93; 16],
+			user_data: [0u8; 20],
+		};
+		let mut flags = 0x05; // INIT | MODE64BIT
+		if enclave.build_mode == SgxBuildMode::Debug {
+			flags |= SGX_FLAGS_DEBUG;
+		}
+		let body = report_body(
+			self.platform_tcb.cpusvn,
+			flags,
+			enclave.mr_enclave,
+			enclave.mr_signer,
+			enclave.isv_prod_id,
+			enclave.isv_svn,
+			enclave.report_data,
+		);
+		let mut signed_part = header.encode();
+		signed_part.extend(body.encode());
+		let isv_enclave_report_signature = sign(&self.attestation_key, &signed_part);
+
+		let attestation_key: [u8; ATTESTATION_KEY_SIZE] =
+			self.attestation_key.public_key().as_ref()[1..]
+				.try_into()
+				.expect("uncompressed P-256 public keys have 65 bytes");
+		let mut qe_report_data = SgxReportData::default();
+		let hash = ring::digest::digest(
+			&ring::digest::SHA256,
+			&[&attestation_key[..], &QE_AUTHENTICATION_DATA[..]].concat(),
+		);
+		qe_report_data.d[..32].copy_from_slice(hash.as_ref());
+		let qe_report = report_body(
+			self.platform_tcb.cpusvn,
+			0x11,
+			[0x22; 32],
+			SYNTHETIC_QE_MRSIGNER,
+			SYNTHETIC_QE_ISVPRODID,
+			SYNTHETIC_QE_ISVSVN,
+			qe_report_data,
+		)
+		.encode();
+		let qe_report_signature = sign(&self.pck.key, &qe_report);
+
+		let certification_data = self.pck_certificate_chain();
+		let mut signature_data = Vec::new();
+		signature_data.extend(isv_enclave_report_signature);
+		signature_data.extend(attestation_key);
+		signature_data.extend(qe_report);
+		signature_data.extend(qe_report_signature);
+		signature_data.extend((QE_AUTHENTICATION_DATA.len() as u16).to_le_bytes());
+		signature_data.extend(QE_AUTHENTICATION_DATA);
+		signature_data.extend(CERTIFICATION_DATA_TYPE_PCK_CHAIN.to_le_bytes());
+		signature_data.extend((certification_data.len() as u32).to_le_bytes());
+		signature_data.extend(certification_data);
+
+		let mut quote = signed_part;
+		quote.extend((signature_data.len() as u32).to_le_bytes());
+		quote.extend(signature_data);
+		quote
+	}
+
+	fn sign_collateral(&self, data: Vec<u8>) -> SignedCollateral {
+		let signature = sign(&self.collateral_signer.key, &data);
+		SignedCollateral { data, signature }
+	}
+}
+
+impl Issuer {
+	fn issue(
+		&self,
+		serial: u8,
+		common_name: &str,
+		validity: &[u8],
+		is_ca: bool,
+		extensions: &[Vec<u8>],
+	) -> Issuer {
+		let key = generate_key();
+		let name = asn1::name(common_name);
+		let mut extensions = extensions.to_vec();
+		if is_ca {
+			extensions.push(asn1::basic_constraints_ca());
+		}
+		let certificate =
+			asn1::certificate(serial, &self.name, &name, validity, &key, &self.key, &extensions);
+		Issuer { key, name, certificate }
+	}
+}
+
+fn report_body(
+	cpu_svn: Cpusvn,
+	attributes_flags: u64,
+	mr_enclave: MrEnclave,
+	mr_signer: MrSigner,
+	isv_prod_id: u16,
+	isv_svn: u16,
+	report_data: SgxReportData,
+) -> SgxReportBody {
+	SgxReportBody {
+		cpu_svn,
+		misc_select: [0u8; 4],
+		reserved1: [0u8; SGX_REPORT_BODY_RESERVED1_BYTES],
+		isv_ext_prod_id: [0u8; 16],
+		attributes: SGXAttributes { flags: attributes_flags, xfrm: 0x03 },
+		mr_enclave,
+		reserved2: [0u8; SGX_REPORT_BODY_RESERVED2_BYTES],
+		mr_signer,
+		reserved3: [0u8; SGX_REPORT_BODY_RESERVED3_BYTES],
+		config_id: [0u8; 64],
+		isv_prod_id,
+		isv_svn,
+		config_svn: 0,
+		reserved4: [0u8; SGX_REPORT_BODY_RESERVED4_BYTES],
+		isv_family_id: [0u8; 16],
+		report_data,
+	}
+}
+
+fn generate_key() -> EcdsaKeyPair {
+	let pkcs8 =
+		EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new())
+			.expect("key generation works");
+	EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref())
+		.expect("generated key is valid")
+}
+
+/// Raw (r || s) ECDSA P-256 signature over the SHA-256 digest of `data`.
+fn sign(key: &EcdsaKeyPair, data: &[u8]) -> [u8; 64] {
+	key.sign(&SystemRandom::new(), data)
+		.expect("signing works")
+		.as_ref()
+		.try_into()
+		.expect("fixed P-256 signatures have 64 bytes")
+}
+
+fn rfc3339(millis: u64) -> String {
+	datetime(millis).format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn datetime(millis: u64) -> NaiveDateTime {
+	NaiveDateTime::from_timestamp_millis(millis as i64).expect("timestamp is in range")
+}
+
+fn pem_chain(certificates: &[&[u8]]) -> Vec<u8> {
+	let mut pem = String::new();
+	for certificate in certificates {
+		pem.push_str("-----BEGIN CERTIFICATE-----\n");
+		let encoded = base64::encode(certificate);
+		for line in encoded.as_bytes().chunks(64) {
+			pem.push_str(core::str::from_utf8(line).expect("base64 is ascii"));
+			pem.push('\n');
+		}
+		pem.push_str("-----END CERTIFICATE-----\n");
+	}
+	pem.into_bytes()
+}
+
+/// Just enough DER to build the certificates above.
+mod asn1 {
+	use super::{
+		datetime, encode_as_der, sign, Datelike, EcdsaKeyPair, KeyPair, MultiPackagePlatform,
+		PlatformTcb, SgxType,
+	};
+
+	const TAG_BOOLEAN: u8 = 0x01;
+	const TAG_INTEGER: u8 = 0x02;
+	const TAG_BIT_STRING: u8 = 0x03;
+	const TAG_OCTET_STRING: u8 = 0x04;
+	const TAG_OID: u8 = 0x06;
+	const TAG_ENUMERATED: u8 = 0x0a;
+	const TAG_UTF8_STRING: u8 = 0x0c;
+	const TAG_UTC_TIME: u8 = 0x17;
+	const TAG_GENERALIZED_TIME: u8 = 0x18;
+	const TAG_SEQUENCE: u8 = 0x30;
+	const TAG_SET: u8 = 0x31;
+
+	const OID_COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+	const OID_BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+	const OID_EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+	const OID_PRIME256V1: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+	const OID_ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+	const OID_SGX_EXTENSIONS: &[u64] = &[1, 2, 840, 113741, 1, 13, 1];
+	const OID_SGX_TCB: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 2];
+	const OID_SGX_PCESVN: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 2, 17];
+	const OID_SGX_CPUSVN: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 2, 18];
+	const OID_SGX_PCEID: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 3];
+	const OID_SGX_FMSPC: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 4];
+	const OID_SGX_TYPE: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 5];
+	const OID_SGX_PLATFORM_INSTANCE_ID: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 6];
+	const OID_SGX_CONFIGURATION: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 7];
+	const OID_SGX_DYNAMIC_PLATFORM: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 7, 1];
+	const OID_SGX_CACHED_KEYS: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 7, 2];
+	const OID_SGX_SMT_ENABLED: &[u64] = &[1, 2, 840, 113741, 1, 13, 1, 7, 3];
+
+	fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+		let mut out = vec![tag];
+		let len = content.len();
+		if len < 0x80 {
+			out.push(len as u8);
+		} else {
+			let len_bytes: Vec<u8> =
+				len.to_be_bytes().iter().copied().skip_while(|b| *b == 0).collect();
+			out.push(0x80 | len_bytes.len() as u8);
+			out.extend(len_bytes);
+		}
+		out.extend_from_slice(content);
+		out
+	}
+
+	fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+		tlv(TAG_SEQUENCE, &parts.concat())
+	}
+
+	fn oid(arcs: &[u64]) -> Vec<u8> {
+		let mut content = vec![(arcs[0] * 40 + arcs[1]) as u8];
+		for arc in &arcs[2..] {
+			let mut base128 = vec![(arc & 0x7f) as u8];
+			let mut rest = arc >> 7;
+			while rest > 0 {
+				base128.push(0x80 | (rest & 0x7f) as u8);
+				rest >>= 7;
+			}
+			content.extend(base128.iter().rev());
+		}
+		tlv(TAG_OID, &content)
+	}
+
+	/// Unsigned big endian integer.
+	fn integer(value: &[u8]) -> Vec<u8> {
+		let mut content: Vec<u8> = value.iter().copied().skip_while(|b| *b == 0).collect();
+		if content.first().map_or(true, |b| b & 0x80 != 0) {
+			content.insert(0, 0);
+		}
+		tlv(TAG_INTEGER, &content)
+	}
+
+	fn bit_string(content: &[u8]) -> Vec<u8> {
+		tlv(TAG_BIT_STRING, &[&[0u8][..], content].concat())
+	}
+
+	fn time(millis: u64) -> Vec<u8> {
+		let t = datetime(millis);
+		// RFC 5280: UTCTime until 2049, GeneralizedTime afterwards
+		if t.year() < 2050 {
+			tlv(TAG_UTC_TIME, t.format("%y%m%d%H%M%SZ").to_string().as_bytes())
+		} else {
+			tlv(TAG_GENERALIZED_TIME, t.format("%Y%m%d%H%M%SZ").to_string().as_bytes())
+		}
+	}
+
+	pub(super) fn validity(not_before: u64, not_after: u64) -> Vec<u8> {
+		sequence(&[&time(not_before), &time(not_after)])
+	}
+
+	pub(super) fn name(common_name: &str) -> Vec<u8> {
+		let attribute =
+			sequence(&[&oid(OID_COMMON_NAME), &tlv(TAG_UTF8_STRING, common_name.as_bytes())]);
+		sequence(&[&tlv(TAG_SET, &attribute)])
+	}
+
+	fn extension(id: &[u64], critical: bool, value: &[u8]) -> Vec<u8> {
+		if critical {
+			sequence(&[&oid(id), &tlv(TAG_BOOLEAN, &[0xff]), &tlv(TAG_OCTET_STRING, value)])
+		} else {
+			sequence(&[&oid(id), &tlv(TAG_OCTET_STRING, value)])
+		}
+	}
+
+	pub(super) fn basic_constraints_ca() -> Vec<u8> {
+		extension(OID_BASIC_CONSTRAINTS, true, &sequence(&[&tlv(TAG_BOOLEAN, &[0xff])]))
+	}
+
+	/// The Intel SGX extensions of a PCK certificate. The mandatory ones only, unless the
+	/// platform is a multi-package one.
+	pub(super) fn sgx_extensions(
+		tcb: &PlatformTcb,
+		multi_package: Option<&MultiPackagePlatform>,
+	) -> Vec<u8> {
+		let mut tcb_components: Vec<Vec<u8>> = tcb
+			.cpusvn
+			.iter()
+			.zip(1u64..)
+			.map(|(svn, i)| {
+				let id = [OID_SGX_TCB, &[i][..]].concat();
+				sequence(&[&oid(&id), &integer(&[*svn])])
+			})
+			.collect();
+		tcb_components.push(sequence(&[&oid(OID_SGX_PCESVN), &integer(&tcb.pcesvn.to_be_bytes())]));
+		tcb_components.push(sequence(&[&oid(OID_SGX_CPUSVN), &tlv(TAG_OCTET_STRING, &tcb.cpusvn)]));
+		let tcb_components: Vec<&[u8]> = tcb_components.iter().map(Vec::as_slice).collect();
+		let sgx_tcb = sequence(&[&oid(OID_SGX_TCB), &sequence(&tcb_components)]);
+		let pce_id = sequence(&[&oid(OID_SGX_PCEID), &tlv(TAG_OCTET_STRING, &[0, 0])]);
+		let fmspc = sequence(&[&oid(OID_SGX_FMSPC), &tlv(TAG_OCTET_STRING, &tcb.fmspc)]);
+		let sgx_type = match multi_package.map(|p| p.sgx_type) {
+			None | Some(SgxType::Standard) => 0,
+			Some(SgxType::Scalable) => 1,
+			Some(SgxType::ScalableWithIntegrity) => 2,
+		};
+		let sgx_type = sequence(&[&oid(OID_SGX_TYPE), &tlv(TAG_ENUMERATED, &[sgx_type])]);
+		let mut entries = vec![sgx_tcb, pce_id, fmspc, sgx_type];
+		if let Some(platform) = multi_package {
+			entries.push(sequence(&[
+				&oid(OID_SGX_PLATFORM_INSTANCE_ID),
+				&tlv(TAG_OCTET_STRING, &platform.platform_instance_id),
+			]));
+			let flag = |id: &[u64], value: bool| {
+				sequence(&[&oid(id), &tlv(TAG_BOOLEAN, &[if value { 0xff } else { 0x00 }])])
+			};
+			let configuration = &platform.configuration;
+			entries.push(sequence(&[
+				&oid(OID_SGX_CONFIGURATION),
+				&sequence(&[
+					&flag(OID_SGX_DYNAMIC_PLATFORM, configuration.dynamic_platform),
+					&flag(OID_SGX_CACHED_KEYS, configuration.cached_keys),
+					&flag(OID_SGX_SMT_ENABLED, configuration.smt_enabled),
+				]),
+			]));
+		}
+		let entries: Vec<&[u8]> = entries.iter().map(Vec::as_slice).collect();
+		extension(OID_SGX_EXTENSIONS, false, &sequence(&entries))
+	}
+
+	fn subject_public_key_info(key: &EcdsaKeyPair) -> Vec<u8> {
+		let algorithm = sequence(&[&oid(OID_EC_PUBLIC_KEY), &oid(OID_PRIME256V1)]);
+		sequence(&[&algorithm, &bit_string(key.public_key().as_ref())])
+	}
+
+	pub(super) fn certificate(
+		serial: u8,
+		issuer: &[u8],
+		subject: &[u8],
+		validity: &[u8],
+		subject_key: &EcdsaKeyPair,
+		issuer_key: &EcdsaKeyPair,
+		extensions: &[Vec<u8>],
+	) -> Vec<u8> {
+		let version = tlv(0xa0, &integer(&[2]));
+		let signature_algorithm = sequence(&[&oid(OID_ECDSA_WITH_SHA256)]);
+		let extensions = tlv(0xa3, &tlv(TAG_SEQUENCE, &extensions.concat()));
+		let tbs = sequence(&[
+			&version,
+			&integer(&[serial]),
+			&signature_algorithm,
+			issuer,
+			validity,
+			subject,
+			&subject_public_key_info(subject_key),
+			&extensions,
+		]);
+		let signature =
+			encode_as_der(&sign(issuer_key, &tbs)).expect("P-256 signatures have 64 bytes");
+		sequence(&[&tbs, &signature_algorithm, &bit_string(&signature)])
+	}
+
+	/// Splits the contents of the subject and the subject public key info out of a certificate
+	/// built by `certificate`, which is what a `TrustAnchor` consists of.
+	pub(super) fn trust_anchor_parts(certificate: &[u8]) -> (&[u8], &[u8]) {
+		let certificate = contents(certificate);
+		let tbs = contents(certificate);
+		// version, serial, signature algorithm, issuer, validity, subject, spki
+		let mut fields = Vec::new();
+		let mut rest = tbs;
+		for _ in 0..7 {
+			let (field, tail) = split_first_tlv(rest);
+			fields.push(field);
+			rest = tail;
+		}
+		(contents(fields[5]), contents(fields[6]))
+	}
+
+	/// The contents of the first TLV in `data`.
+	fn contents(data: &[u8]) -> &[u8] {
+		let (header_len, len) = header(data);
+		&data[header_len..header_len + len]
+	}
+
+	fn split_first_tlv(data: &[u8]) -> (&[u8], &[u8]) {
+		let (header_len, len) = header(data);
+		data.split_at(header_len + len)
+	}
+
+	/// Returns the length of the tag and length bytes and the length of the contents.
+	fn header(data: &[u8]) -> (usize, usize) {
+		let first = data[1];
+		if first < 0x80 {
+			(2, first as usize)
+		} else {
+			let n = (first & 0x7f) as usize;
+			let len = data[2..2 + n].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+			(2 + n, len)
+		}
+	}
+}
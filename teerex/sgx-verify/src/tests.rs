@@ -21,14 +21,16 @@ use crate::{
 	test_data::{
 		consts::{TEST4_CERT, TEST4_MRENCLAVE, TEST4_SIGNER_PUB, TEST4_TIMESTAMP, TEST8_CERT},
 		dcap::{
-			DCAP_QUOTE_CERT, PCK_CRL, QE_IDENTITY_CERT, TEST2_DCAP_QUOTE_HEX,
-			TEST_VALID_COLLATERAL_TIMESTAMP,
+			DCAP_QUOTE_CERT, PCK_CRL, QE_IDENTITY_CERT, QUOTING_ENCLAVE, TEST1_DCAP_QUOTE,
+			TEST1_DCAP_QUOTE_MRENCLAVE, TEST2_DCAP_QUOTE_HEX, TEST_VALID_COLLATERAL_TIMESTAMP,
 		},
+		synthetic::{PlatformTcb, QuotedEnclave, SyntheticPki},
 	},
 };
 use frame_support::assert_err;
 use hex_literal::hex;
 use parity_scale_codec::Decode;
+use teerex_primitives::{QeTcb, QeTcbStatusPolicy, SgxType, TcbStatus};
 
 #[test]
 fn verify_ias_report_should_work() {
@@ -49,6 +51,22 @@ fn decoding_dcap_quote_works() {
 	assert!(quote.is_ok());
 }
 
+#[test]
+fn describe_dcap_quote_works() {
+	let description = inspect::describe_dcap_quote(TEST1_DCAP_QUOTE).unwrap();
+	assert_eq!(description.header.version, 3);
+	assert_eq!(description.body.mr_enclave, hex::encode_upper(TEST1_DCAP_QUOTE_MRENCLAVE));
+
+	let certification_data = description.certification_data;
+	assert_eq!(certification_data.certification_data_type, 5);
+	assert!(certification_data.certificates.len() >= 2);
+	assert!(certification_data.certificates[0].subject.contains("Intel SGX PCK Certificate"));
+	assert_eq!(certification_data.pck_tcb.unwrap().fmspc, "00906EA10000");
+
+	// the description is meant to be consumed by tooling
+	assert!(serde_json::to_string(&description.body).is_ok());
+}
+
 #[test]
 fn verify_zero_length_cert_returns_err() {
 	assert!(verify_ias_report(&Vec::new()[..]).is_err())
@@ -132,10 +150,11 @@ fn decode_qe_certification_data() {
 
 #[test]
 fn deserialize_qe_identity_works() {
-	let certs = extract_certs(include_bytes!("../test-data/dcap/qe_identity_issuer_chain.pem"));
-	let intermediate_slices: Vec<webpki::types::CertificateDer> =
-		certs[1..].iter().map(|c| c.as_slice().into()).collect();
-	let leaf_cert_der = webpki::types::CertificateDer::from(certs[0].as_slice());
+	let chain =
+		CertificateChain::decode(include_bytes!("../test-data/dcap/qe_identity_issuer_chain.pem"))
+			.unwrap();
+	let intermediate_slices = chain.intermediates();
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
 	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der).unwrap();
 	verify_certificate_chain(&leaf_cert, &intermediate_slices, TEST_VALID_COLLATERAL_TIMESTAMP)
 		.unwrap();
@@ -149,12 +168,139 @@ fn deserialize_qe_identity_works() {
 	assert_eq!(5, e.tcb_levels.len());
 }
 
+#[test]
+fn qe_identity_acceptability_follows_tcb_status() {
+	let identity: collateral::EnclaveIdentity = serde_json::from_slice(QUOTING_ENCLAVE).unwrap();
+	let timestamp = TEST_VALID_COLLATERAL_TIMESTAMP as i64;
+	let up_to_date_only = QeTcbStatusPolicy::default();
+
+	// only the isvsvn 6 level is `UpToDate`, the others are `OutOfDate`
+	assert!(identity.is_valid(timestamp, up_to_date_only, None));
+	assert_eq!(identity.to_quoting_enclave(up_to_date_only, None).tcb, vec![QeTcb::new(6)]);
+	assert!(identity.is_valid(timestamp, up_to_date_only, Some(6)));
+	assert_eq!(identity.to_quoting_enclave(up_to_date_only, Some(6)).tcb, vec![QeTcb::new(6)]);
+
+	// a floor above the up to date level leaves nothing acceptable
+	assert!(!identity.is_valid(timestamp, up_to_date_only, Some(7)));
+	assert!(identity.to_quoting_enclave(up_to_date_only, Some(7)).tcb.is_empty());
+}
+
+#[test]
+fn out_of_date_qe_identity_levels_are_only_accepted_if_allowed() {
+	let identity: collateral::EnclaveIdentity = serde_json::from_slice(QUOTING_ENCLAVE).unwrap();
+	let timestamp = TEST_VALID_COLLATERAL_TIMESTAMP as i64;
+
+	// All levels used to be stored. `OutOfDate` levels are rejected by default now, so quotes of
+	// quoting enclaves with isvsvn < 6 no longer verify against this identity.
+	let qe = identity.to_quoting_enclave(QeTcbStatusPolicy::default(), None);
+	assert!(!qe.tcb.contains(&QeTcb::new(5)));
+
+	let statuses = QeTcbStatusPolicy { accept_out_of_date: true, ..Default::default() };
+	assert!(identity.is_valid(timestamp, statuses, None));
+	assert_eq!(
+		identity.to_quoting_enclave(statuses, None).tcb,
+		vec![QeTcb::new(6), QeTcb::new(5), QeTcb::new(4), QeTcb::new(2), QeTcb::new(1)]
+	);
+	// the floor still applies on top of the accepted statuses
+	assert_eq!(
+		identity.to_quoting_enclave(statuses, Some(5)).tcb,
+		vec![QeTcb::new(6), QeTcb::new(5)]
+	);
+
+	// `SWHardeningNeeded` does not imply `OutOfDate`
+	let statuses = QeTcbStatusPolicy { accept_sw_hardening_needed: true, ..Default::default() };
+	assert_eq!(identity.to_quoting_enclave(statuses, None).tcb, vec![QeTcb::new(6)]);
+}
+
+#[test]
+fn revoked_qe_tcb_status_is_never_accepted() {
+	let statuses = QeTcbStatusPolicy { accept_sw_hardening_needed: true, accept_out_of_date: true };
+	assert!(!statuses.accepts(TcbStatus::Revoked));
+	assert!(!statuses.accepts(TcbStatus::Unknown));
+	assert!(!statuses.accepts(TcbStatus::OutOfDateConfigurationNeeded));
+}
+
+#[test]
+fn expired_certificate_chain_reports_leaf() {
+	let chain =
+		CertificateChain::decode(include_bytes!("../test-data/dcap/qe_identity_issuer_chain.pem"))
+			.unwrap();
+	let intermediate_slices = chain.intermediates();
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der).unwrap();
+
+	// 2030-01-01: the TCB signing certificate has expired, Intel's root has not
+	let error =
+		verify_certificate_chain(&leaf_cert, &intermediate_slices, 1_893_456_000_000).unwrap_err();
+	assert_eq!(
+		error,
+		VerificationError::certificate(
+			Error::CertificateChainIsInvalid,
+			Some(0),
+			webpki::Error::CertExpired
+		)
+	);
+	assert_eq!(Error::from(error), Error::CertificateChainIsInvalid);
+}
+
+#[test]
+fn unsupported_dcap_quote_version_reports_mismatch() {
+	let mut quote = TEST1_DCAP_QUOTE.to_vec();
+	quote[0..2].copy_from_slice(&4u16.to_le_bytes());
+
+	let expected =
+		VerificationError::mismatch(Error::DcapQuoteVersionMismatch, "quote version", 3u16, 4u16);
+	assert_eq!(
+		verify_dcap_quote(&quote, TEST_VALID_COLLATERAL_TIMESTAMP, &Default::default())
+			.unwrap_err(),
+		expected
+	);
+	assert_eq!(extract_tcb_info_from_raw_dcap_quote(&quote).unwrap_err(), expected);
+	assert_eq!(expected.to_string(), "DcapQuoteVersionMismatch: quote version is 4, expected 3");
+}
+
+#[test]
+fn truncated_dcap_quote_is_rejected() {
+	let qe = serde_json::from_slice::<collateral::EnclaveIdentity>(QUOTING_ENCLAVE)
+		.unwrap()
+		.to_quoting_enclave(Default::default(), None);
+	for len in 0..TEST1_DCAP_QUOTE.len() {
+		let quote = &TEST1_DCAP_QUOTE[..len];
+		assert!(verify_dcap_quote(quote, TEST_VALID_COLLATERAL_TIMESTAMP, &qe).is_err());
+		assert!(extract_tcb_info_from_raw_dcap_quote(quote).is_err());
+	}
+}
+
+#[test]
+fn dcap_quote_with_resized_authentication_data_is_rejected() {
+	const AUTHENTICATION_DATA_SIZE_OFFSET: usize = 1012;
+	let qe = serde_json::from_slice::<collateral::EnclaveIdentity>(QUOTING_ENCLAVE)
+		.unwrap()
+		.to_quoting_enclave(Default::default(), None);
+
+	// Drop the authentication data. The quote still decodes, but the data that is hashed into the
+	// QE report is gone.
+	let mut quote = TEST1_DCAP_QUOTE.to_vec();
+	quote[AUTHENTICATION_DATA_SIZE_OFFSET..AUTHENTICATION_DATA_SIZE_OFFSET + 2]
+		.copy_from_slice(&0u16.to_le_bytes());
+	quote.drain(AUTHENTICATION_DATA_SIZE_OFFSET + 2..AUTHENTICATION_DATA_SIZE_OFFSET + 2 + 32);
+	assert!(DcapQuote::decode(&mut quote.as_slice()).is_ok());
+
+	assert_eq!(
+		verify_dcap_quote(&quote, TEST_VALID_COLLATERAL_TIMESTAMP, &qe)
+			.unwrap_err()
+			.kind,
+		Error::QeReportHashMismatch
+	);
+}
+
 #[test]
 fn deserialize_tcb_info_works() {
-	let certs = extract_certs(include_bytes!("../test-data/dcap/tcb_info_issuer_chain.pem"));
-	let intermediate_slices: Vec<webpki::types::CertificateDer> =
-		certs[1..].iter().map(|c| c.as_slice().into()).collect();
-	let leaf_cert_der = webpki::types::CertificateDer::from(certs[0].as_slice());
+	let chain =
+		CertificateChain::decode(include_bytes!("../test-data/dcap/tcb_info_issuer_chain.pem"))
+			.unwrap();
+	let intermediate_slices = chain.intermediates();
+	let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
 	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der).unwrap();
 	verify_certificate_chain(&leaf_cert, &intermediate_slices, TEST_VALID_COLLATERAL_TIMESTAMP)
 		.unwrap();
@@ -164,8 +310,9 @@ fn deserialize_tcb_info_works() {
 	let json_data = serde_json::to_vec(&json.tcb_info).unwrap();
 	let signature = hex::decode(json.signature).unwrap();
 
-	let _e = deserialize_tcb_info(&json_data, &signature, &leaf_cert).unwrap();
+	let tcb_info = deserialize_tcb_info(&json_data, &signature, &leaf_cert).unwrap();
 	assert_eq!(hex!("00906EA10000"), json.tcb_info.fmspc);
+	assert!(tcb_info.to_chain_tcb_info().1.tcb_levels_are_ordered());
 }
 
 #[test]
@@ -203,15 +350,100 @@ fn parse_pck_certificate() {
 	let der = DCAP_QUOTE_CERT.replace('\n', "");
 	let der = base64::decode(&der).unwrap();
 
-	let ext = get_intel_extension(&der).unwrap();
-	assert_eq!(453, ext.len());
-
-	let fmspc = get_fmspc(&ext).unwrap();
+	let (fmspc, tcb) = extract_tcb_info(&der).unwrap();
 	assert_eq!(hex!("00906EA10000"), fmspc);
+	assert_eq!(hex!("11110204018007000000000000000000"), tcb.cpusvn);
+	assert_eq!(u16::from_be_bytes(hex!("000B")), tcb.pcesvn);
+}
+
+// 2023-01-01 and 2033-01-01
+const SYNTHETIC_NOT_BEFORE: u64 = 1_672_531_200_000;
+const SYNTHETIC_NOT_AFTER: u64 = 1_988_150_400_000;
+const SYNTHETIC_NOW: u64 = 1_700_000_000_000;
+
+fn synthetic_pki() -> SyntheticPki {
+	let tcb = PlatformTcb { fmspc: hex!("00A067110000"), cpusvn: [7u8; 16], pcesvn: 13 };
+	SyntheticPki::new(tcb, SYNTHETIC_NOT_BEFORE, SYNTHETIC_NOT_AFTER)
+}
+
+fn collateral_signer(pki: &SyntheticPki) -> Vec<u8> {
+	CertificateChain::decode(&pki.collateral_certificate_chain())
+		.unwrap()
+		.leaf()
+		.to_vec()
+}
+
+fn synthetic_quoting_enclave(pki: &SyntheticPki) -> SgxQuotingEnclave {
+	let identity = pki.sign_qe_identity(SYNTHETIC_NOT_BEFORE, SYNTHETIC_NOT_AFTER);
+	let leaf_der = collateral_signer(pki);
+	let leaf_der = webpki::types::CertificateDer::from(leaf_der.as_slice());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_der).unwrap();
+	deserialize_enclave_identity(&identity.data, &identity.signature, &leaf_cert)
+		.unwrap()
+		.to_quoting_enclave(Default::default(), None)
+}
 
-	let cpusvn = get_cpusvn(&ext).unwrap();
-	assert_eq!(hex!("11110204018007000000000000000000"), cpusvn);
+#[test]
+fn synthetic_collateral_verifies_against_synthetic_root() {
+	let pki = synthetic_pki();
+	let chain = CertificateChain::decode(&pki.collateral_certificate_chain()).unwrap();
+	let intermediates = chain.intermediates();
+	let leaf_der = webpki::types::CertificateDer::from(chain.leaf());
+	let leaf_cert = webpki::EndEntityCert::try_from(&leaf_der).unwrap();
+
+	assert!(verify_certificate_chain_with_trust_anchors(
+		&leaf_cert,
+		&intermediates,
+		SYNTHETIC_NOW,
+		&[pki.trust_anchor()]
+	)
+	.is_ok());
+	// Intel's root must not accept it
+	assert!(verify_certificate_chain(&leaf_cert, &intermediates, SYNTHETIC_NOW).is_err());
+
+	let tcb_info = pki.sign_up_to_date_tcb_info(SYNTHETIC_NOT_BEFORE, SYNTHETIC_NOT_AFTER);
+	let tcb_info = deserialize_tcb_info(&tcb_info.data, &tcb_info.signature, &leaf_cert).unwrap();
+	let (fmspc, on_chain) = tcb_info.to_chain_tcb_info();
+	assert_eq!(fmspc, pki.platform_tcb().fmspc);
+	let tcb = pki.platform_tcb();
+	let examinee = TcbVersionStatus::new(tcb.cpusvn, tcb.pcesvn, TcbStatus::Unknown);
+	assert_eq!(on_chain.verify_examinee(&examinee), Some(SgxStatus::Ok));
+
+	let qe = synthetic_quoting_enclave(&pki);
+	assert_eq!(qe.isvprodid, 1);
+}
+
+#[test]
+fn synthetic_dcap_quote_verifies() {
+	let pki = synthetic_pki();
+	let qe = synthetic_quoting_enclave(&pki);
+	let enclave = QuotedEnclave::new([9u8; 32], SgxReportData { d: [5u8; 64] });
+	let quote = pki.build_quote(&enclave);
+
+	let (fmspc, tcb, report) = pki.verify_dcap_quote(&quote, SYNTHETIC_NOW, &qe).unwrap();
+	assert_eq!(fmspc, pki.platform_tcb().fmspc);
+	assert_eq!(tcb.cpusvn, pki.platform_tcb().cpusvn);
+	assert_eq!(tcb.pcesvn, pki.platform_tcb().pcesvn);
+	assert_eq!(report.mr_enclave, enclave.mr_enclave);
+	assert_eq!(report.report_data, enclave.report_data);
+	assert_eq!(report.build_mode, SgxBuildMode::Production);
+	let platform = report.platform.unwrap();
+	assert_eq!(platform.sgx_type, SgxType::Standard);
+	assert_eq!(platform.configuration, None);
+
+	let debug_quote = pki.build_quote(&enclave.with_build_mode(SgxBuildMode::Debug));
+	let (_, _, report) = pki.verify_dcap_quote(&debug_quote, SYNTHETIC_NOW, &qe).unwrap();
+	assert_eq!(report.build_mode, SgxBuildMode::Debug);
+
+	assert!(verify_dcap_quote(&quote, SYNTHETIC_NOW, &qe).is_err());
+}
+
+#[test]
+fn synthetic_dcap_quote_from_other_pki_is_rejected() {
+	let pki = synthetic_pki();
+	let other_pki = synthetic_pki();
+	let qe = synthetic_quoting_enclave(&pki);
+	let quote = pki.build_quote(&QuotedEnclave::new([9u8; 32], SgxReportData::default()));
 
-	let pcesvn = get_pcesvn(&ext).unwrap();
-	assert_eq!(u16::from_be_bytes(hex!("000B")), pcesvn);
+	assert!(other_pki.verify_dcap_quote(&quote, SYNTHETIC_NOW, &qe).is_err());
 }
@@ -0,0 +1,41 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Errors of the verification. It is a `PalletError`, so pallets can nest it into their own errors.
+#[derive(
+	Debug, Encode, Decode, Copy, Clone, TypeInfo, frame_support::PalletError, PartialEq, Eq,
+)]
+pub enum Error {
+	CertificateDecodingError,
+	CertificateIsNotValidAtTime,
+	CertificateIssuerMismatch,
+	CertificateSignatureAlgorithmIsUnsupported,
+	CertificateSignatureIsInvalid,
+	ChipIdMismatch,
+	ReportDecodingError,
+	ReportSignatureAlgorithmIsUnsupported,
+	ReportSignatureIsInvalid,
+	ReportVersionIsUnsupported,
+	SigningKeyIsNotVcek,
+	TcbVersionIsTooLow,
+	VcekExtensionDecodingError,
+	VcekExtensionIsMissing,
+	VcekTcbMismatch,
+}
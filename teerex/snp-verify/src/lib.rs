@@ -0,0 +1,209 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Contains all the logic for understanding and verifying AMD SEV-SNP attestation reports.
+//!
+//! A report is signed by the VCEK, a key which is derived for a specific chip and TCB version.
+//! The VCEK certificate is signed by the ASK, which in turn is signed by the ARK, AMD's root key
+//! of a product line. The ARK is not built in, the caller passes the one it trusts.
+//!
+//! AMD's documentation:
+//!
+//! "SEV Secure Nested Paging Firmware ABI Specification", further denoted as
+//! `SNP_Firmware_ABI_Spec`, chapter 7.3 describes the attestation report.
+//!
+//! * https://www.amd.com/content/dam/amd/en/documents/epyc-technical-docs/specifications/56860.pdf
+//!
+//! "Versioned Chip Endorsement Key (VCEK) Certificate and KDS Interface Specification" describes
+//! the certificates and their extensions.
+//!
+//! * https://www.amd.com/content/dam/amd/en/documents/epyc-technical-docs/specifications/57230.pdf
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use der::{
+	asn1::{ObjectIdentifier, OctetStringRef},
+	Decode, Encode,
+};
+use frame_support::ensure;
+use ring::signature;
+use sp_std::prelude::*;
+use teerex_primitives::{SnpMeasurement, SnpTcbVersion, TEEREX};
+use x509_cert::Certificate;
+
+pub use error::Error;
+pub use report::{SnpAttestationReport, SNP_REPORT_SIZE};
+
+mod error;
+mod report;
+
+#[cfg(any(test, feature = "test-data"))]
+pub mod test_data;
+
+#[cfg(test)]
+mod tests;
+
+const OID_RSASSA_PSS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.10");
+const OID_BOOTLOADER_SPL: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.3704.1.3.1");
+const OID_TEE_SPL: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.3704.1.3.2");
+const OID_SNP_SPL: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.3704.1.3.3");
+const OID_MICROCODE_SPL: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.3704.1.3.8");
+const OID_HW_ID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.3704.1.4");
+
+/// The content of a verified attestation report that matters to the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnpVerifiedReport {
+	pub report_data: [u8; 64],
+	pub measurement: SnpMeasurement,
+	pub id_key_digest: [u8; 48],
+	pub guest_svn: u32,
+	pub policy: u64,
+	pub debug_allowed: bool,
+	pub reported_tcb: SnpTcbVersion,
+}
+
+/// Verifies that `ark_der` is a self-signed root certificate valid at `verification_time`
+/// (unix epoch in milliseconds). Meant to be called before a root is trusted.
+pub fn verify_root_certificate(ark_der: &[u8], verification_time: u64) -> Result<(), Error> {
+	let ark = decode_certificate(ark_der)?;
+	verify_issued_by(&ark, &ark, verification_time)
+}
+
+/// Verifies a raw attestation report and the VCEK -> ASK -> ARK chain, all of them DER encoded.
+///
+/// The TCB version the VCEK was derived for must match the one in the report and, if given, be at
+/// least `min_tcb` in every component.
+pub fn verify_snp_report(
+	report_raw: &[u8],
+	vcek_der: &[u8],
+	ask_der: &[u8],
+	ark_der: &[u8],
+	verification_time: u64,
+	min_tcb: Option<SnpTcbVersion>,
+) -> Result<SnpVerifiedReport, Error> {
+	let report = SnpAttestationReport::decode_raw(report_raw)?;
+	ensure!(report.is_signed_by_vcek(), Error::SigningKeyIsNotVcek);
+
+	let ark = decode_certificate(ark_der)?;
+	let ask = decode_certificate(ask_der)?;
+	let vcek = decode_certificate(vcek_der)?;
+	verify_issued_by(&ark, &ark, verification_time)?;
+	verify_issued_by(&ask, &ark, verification_time)?;
+	verify_issued_by(&vcek, &ask, verification_time)?;
+	log::trace!(target: TEEREX, "SNP certificate chain verified");
+
+	// The VCEK is derived from the chip's secrets and the TCB version, so a report signed by it
+	// can only originate from a chip running this TCB version.
+	let reported_tcb = report.reported_tcb();
+	ensure!(vcek_tcb_version(&vcek)? == reported_tcb, Error::VcekTcbMismatch);
+	if let Some(chip_id) = report.chip_id() {
+		ensure!(vcek_extension_octets(&vcek, &OID_HW_ID)? == chip_id, Error::ChipIdMismatch);
+	}
+
+	let signature = report.signature().to_fixed()?;
+	signature::UnparsedPublicKey::new(
+		&signature::ECDSA_P384_SHA384_FIXED,
+		vcek.tbs_certificate.subject_public_key_info.subject_public_key,
+	)
+	.verify(&report_raw[..report::SNP_SIGNED_REPORT_SIZE], &signature)
+	.map_err(|_| Error::ReportSignatureIsInvalid)?;
+	log::trace!(target: TEEREX, "SNP report signature verified");
+
+	if let Some(min_tcb) = min_tcb {
+		ensure!(reported_tcb.is_at_least(&min_tcb), Error::TcbVersionIsTooLow);
+	}
+
+	Ok(SnpVerifiedReport {
+		report_data: *report.report_data(),
+		measurement: *report.measurement(),
+		id_key_digest: *report.id_key_digest(),
+		guest_svn: report.guest_svn(),
+		policy: report.policy(),
+		debug_allowed: report.debug_allowed(),
+		reported_tcb,
+	})
+}
+
+fn decode_certificate(der_encoded: &[u8]) -> Result<Certificate, Error> {
+	Certificate::from_der(der_encoded).map_err(|_| Error::CertificateDecodingError)
+}
+
+/// Verifies that `cert` has been signed by `issuer` with RSASSA-PSS, which AMD uses for the ARK
+/// and the ASK, and that it is valid at `verification_time`.
+fn verify_issued_by(
+	cert: &Certificate,
+	issuer: &Certificate,
+	verification_time: u64,
+) -> Result<(), Error> {
+	ensure!(is_valid_at(cert, verification_time / 1000), Error::CertificateIsNotValidAtTime);
+	ensure!(
+		cert.tbs_certificate.issuer == issuer.tbs_certificate.subject,
+		Error::CertificateIssuerMismatch
+	);
+	ensure!(
+		cert.signature_algorithm.oid == OID_RSASSA_PSS,
+		Error::CertificateSignatureAlgorithmIsUnsupported
+	);
+	let tbs = cert.tbs_certificate.to_vec().map_err(|_| Error::CertificateDecodingError)?;
+	let signature = cert.signature.as_bytes().ok_or(Error::CertificateDecodingError)?;
+	signature::UnparsedPublicKey::new(
+		&signature::RSA_PSS_2048_8192_SHA384,
+		issuer.tbs_certificate.subject_public_key_info.subject_public_key,
+	)
+	.verify(&tbs, signature)
+	.map_err(|_| Error::CertificateSignatureIsInvalid)
+}
+
+fn is_valid_at(cert: &Certificate, time_secs: u64) -> bool {
+	let validity = &cert.tbs_certificate.validity;
+	validity.not_before.to_unix_duration().as_secs() <= time_secs &&
+		time_secs <= validity.not_after.to_unix_duration().as_secs()
+}
+
+fn vcek_extension<'a>(vcek: &'a Certificate, oid: &ObjectIdentifier) -> Result<&'a [u8], Error> {
+	vcek.tbs_certificate
+		.extensions
+		.as_deref()
+		.unwrap_or(&[])
+		.iter()
+		.find(|e| e.extn_id == *oid)
+		.map(|e| e.extn_value)
+		.ok_or(Error::VcekExtensionIsMissing)
+}
+
+fn vcek_extension_octets<'a>(
+	vcek: &'a Certificate,
+	oid: &ObjectIdentifier,
+) -> Result<&'a [u8], Error> {
+	OctetStringRef::from_der(vcek_extension(vcek, oid)?)
+		.map(|octets| octets.as_bytes())
+		.map_err(|_| Error::VcekExtensionDecodingError)
+}
+
+/// The security patch levels are DER encoded integers.
+fn vcek_spl(vcek: &Certificate, oid: &ObjectIdentifier) -> Result<u8, Error> {
+	u8::from_der(vcek_extension(vcek, oid)?).map_err(|_| Error::VcekExtensionDecodingError)
+}
+
+fn vcek_tcb_version(vcek: &Certificate) -> Result<SnpTcbVersion, Error> {
+	Ok(SnpTcbVersion::new(
+		vcek_spl(vcek, &OID_BOOTLOADER_SPL)?,
+		vcek_spl(vcek, &OID_TEE_SPL)?,
+		vcek_spl(vcek, &OID_SNP_SPL)?,
+		vcek_spl(vcek, &OID_MICROCODE_SPL)?,
+	))
+}
@@ -0,0 +1,165 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! The attestation report, see table 21 of the `SNP_Firmware_ABI_Spec`.
+//!
+//! All fields are little endian, so the report can be decoded with SCALE.
+
+use crate::Error;
+use frame_support::ensure;
+use parity_scale_codec::{Decode, Encode};
+use teerex_primitives::{SnpMeasurement, SnpTcbVersion};
+
+/// Size of the attestation report in bytes.
+pub const SNP_REPORT_SIZE: usize = 0x4A0;
+/// The signature covers the report up to the signature itself.
+pub const SNP_SIGNED_REPORT_SIZE: usize = 0x2A0;
+
+/// ECDSA P-384 with SHA-384, the only signature algorithm AMD defines so far.
+const SIGNATURE_ALGO_ECDSA_P384_SHA384: u32 = 1;
+/// Version 2 is the first one with the current layout. Later versions only assign reserved fields.
+const MIN_REPORT_VERSION: u32 = 2;
+/// Bit 19 of the guest policy allows the hypervisor to debug the guest.
+const POLICY_DEBUG: u64 = 1 << 19;
+/// Bit 1 of the flags is set if the chip id has been masked with zeros.
+const FLAGS_MASK_CHIP_ID: u32 = 1 << 1;
+/// Bits 2 to 4 of the flags select the key that signed the report. 0 is the VCEK.
+const FLAGS_SIGNING_KEY_SHIFT: u32 = 2;
+const FLAGS_SIGNING_KEY_MASK: u32 = 0b111;
+
+/// The TCB version as encoded in the report and in the VCEK. Only the Milan and Genoa layout is
+/// supported: boot loader, TEE, 4 reserved bytes, SNP firmware and microcode.
+pub type RawTcbVersion = [u8; 8];
+
+pub fn tcb_version_from_raw(raw: &RawTcbVersion) -> SnpTcbVersion {
+	SnpTcbVersion::new(raw[0], raw[1], raw[6], raw[7])
+}
+
+#[derive(Debug, Encode, Decode, Clone)]
+#[repr(C)]
+pub struct SnpSignature {
+	/// little endian, zero extended
+	r: [u8; 72],
+	/// little endian, zero extended
+	s: [u8; 72],
+	reserved: [u8; 368],
+}
+
+impl SnpSignature {
+	/// The big endian concatenation of r and s, as ring expects it.
+	pub fn to_fixed(&self) -> Result<[u8; 96], Error> {
+		ensure!(
+			self.r[48..].iter().chain(self.s[48..].iter()).all(|b| *b == 0),
+			Error::ReportSignatureIsInvalid
+		);
+		let mut fixed = [0u8; 96];
+		for (dst, src) in fixed[..48].iter_mut().zip(self.r[..48].iter().rev()) {
+			*dst = *src;
+		}
+		for (dst, src) in fixed[48..].iter_mut().zip(self.s[..48].iter().rev()) {
+			*dst = *src;
+		}
+		Ok(fixed)
+	}
+}
+
+#[derive(Debug, Encode, Decode, Clone)]
+#[repr(C)]
+pub struct SnpAttestationReport {
+	version: u32,
+	guest_svn: u32,
+	policy: u64,
+	family_id: [u8; 16],
+	image_id: [u8; 16],
+	vmpl: u32,
+	signature_algo: u32,
+	current_tcb: RawTcbVersion,
+	platform_info: u64,
+	flags: u32,
+	reserved0: u32,
+	report_data: [u8; 64],
+	measurement: SnpMeasurement,
+	host_data: [u8; 32],
+	id_key_digest: [u8; 48],
+	author_key_digest: [u8; 48],
+	report_id: [u8; 32],
+	report_id_ma: [u8; 32],
+	reported_tcb: RawTcbVersion,
+	reserved1: [u8; 24],
+	chip_id: [u8; 64],
+	committed_tcb: RawTcbVersion,
+	/// build, minor and major of the current and the committed firmware
+	firmware_versions: [u8; 8],
+	launch_tcb: RawTcbVersion,
+	reserved2: [u8; 168],
+	signature: SnpSignature,
+}
+
+impl SnpAttestationReport {
+	pub fn decode_raw(report_raw: &[u8]) -> Result<Self, Error> {
+		ensure!(report_raw.len() == SNP_REPORT_SIZE, Error::ReportDecodingError);
+		let report = Self::decode(&mut &report_raw[..]).map_err(|_| Error::ReportDecodingError)?;
+		ensure!(report.version >= MIN_REPORT_VERSION, Error::ReportVersionIsUnsupported);
+		ensure!(
+			report.signature_algo == SIGNATURE_ALGO_ECDSA_P384_SHA384,
+			Error::ReportSignatureAlgorithmIsUnsupported
+		);
+		Ok(report)
+	}
+
+	pub fn signature(&self) -> &SnpSignature {
+		&self.signature
+	}
+
+	pub fn report_data(&self) -> &[u8; 64] {
+		&self.report_data
+	}
+
+	pub fn measurement(&self) -> &SnpMeasurement {
+		&self.measurement
+	}
+
+	pub fn id_key_digest(&self) -> &[u8; 48] {
+		&self.id_key_digest
+	}
+
+	pub fn guest_svn(&self) -> u32 {
+		self.guest_svn
+	}
+
+	pub fn policy(&self) -> u64 {
+		self.policy
+	}
+
+	pub fn debug_allowed(&self) -> bool {
+		self.policy & POLICY_DEBUG != 0
+	}
+
+	/// The TCB version the VCEK was derived for.
+	pub fn reported_tcb(&self) -> SnpTcbVersion {
+		tcb_version_from_raw(&self.reported_tcb)
+	}
+
+	/// `None` if the chip id has been masked by the guest.
+	pub fn chip_id(&self) -> Option<&[u8; 64]> {
+		(self.flags & FLAGS_MASK_CHIP_ID == 0).then_some(&self.chip_id)
+	}
+
+	pub fn is_signed_by_vcek(&self) -> bool {
+		(self.flags >> FLAGS_SIGNING_KEY_SHIFT) & FLAGS_SIGNING_KEY_MASK == 0
+	}
+}
@@ -0,0 +1,37 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! A synthetic certificate chain and a report signed by its VCEK, generated with
+//! `test-data/generate.py`. The chain is not rooted in AMD's ARK.
+
+use hex_literal::hex;
+use teerex_primitives::{SnpMeasurement, SnpTcbVersion};
+
+pub const TEST_SNP_ARK: &[u8] = include_bytes!("../test-data/ark.der");
+pub const TEST_SNP_ASK: &[u8] = include_bytes!("../test-data/ask.der");
+pub const TEST_SNP_VCEK: &[u8] = include_bytes!("../test-data/vcek.der");
+pub const TEST_SNP_REPORT: &[u8] = include_bytes!("../test-data/report.bin");
+
+/// The first 32 bytes of the report data, the public key of the account registering the enclave.
+pub const TEST_SNP_REPORT_SIGNER: [u8; 32] =
+	hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+pub const TEST_SNP_MEASUREMENT: SnpMeasurement = [0x5e; 48];
+pub const TEST_SNP_ID_KEY_DIGEST: [u8; 48] = [0x1d; 48];
+pub const TEST_SNP_TCB: SnpTcbVersion =
+	SnpTcbVersion { bootloader: 3, tee: 0, snp: 14, microcode: 209 };
+/// 2025-01-01T00:00:00Z, within the validity of all certificates.
+pub const TEST_SNP_TIMESTAMP: u64 = 1_735_689_600_000;
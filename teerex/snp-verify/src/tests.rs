@@ -0,0 +1,112 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the MICROSOFT REFERENCE SOURCE LICENSE (MS-RSL) (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		https://referencesource.microsoft.com/license.html
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use super::*;
+use crate::test_data::*;
+
+fn verify(report: &[u8], min_tcb: Option<SnpTcbVersion>) -> Result<SnpVerifiedReport, Error> {
+	verify_snp_report(
+		report,
+		TEST_SNP_VCEK,
+		TEST_SNP_ASK,
+		TEST_SNP_ARK,
+		TEST_SNP_TIMESTAMP,
+		min_tcb,
+	)
+}
+
+#[test]
+fn verify_snp_report_works() {
+	let report = verify(TEST_SNP_REPORT, None).unwrap();
+	assert_eq!(report.report_data[..32], TEST_SNP_REPORT_SIGNER);
+	assert_eq!(report.measurement, TEST_SNP_MEASUREMENT);
+	assert_eq!(report.id_key_digest, TEST_SNP_ID_KEY_DIGEST);
+	assert_eq!(report.guest_svn, 1);
+	assert_eq!(report.reported_tcb, TEST_SNP_TCB);
+	assert!(!report.debug_allowed);
+}
+
+#[test]
+fn tampered_snp_report_is_rejected() {
+	let mut report = TEST_SNP_REPORT.to_vec();
+	// first byte of the measurement
+	report[0x90] ^= 1;
+	assert_eq!(verify(&report, None), Err(Error::ReportSignatureIsInvalid));
+}
+
+#[test]
+fn truncated_snp_report_is_rejected() {
+	for len in 0..TEST_SNP_REPORT.len() {
+		assert_eq!(verify(&TEST_SNP_REPORT[..len], None), Err(Error::ReportDecodingError));
+	}
+}
+
+#[test]
+fn snp_report_with_other_reported_tcb_is_rejected() {
+	let mut report = TEST_SNP_REPORT.to_vec();
+	// microcode of the reported TCB
+	report[0x187] -= 1;
+	assert_eq!(verify(&report, None), Err(Error::VcekTcbMismatch));
+}
+
+#[test]
+fn snp_report_below_min_tcb_is_rejected() {
+	assert!(verify(TEST_SNP_REPORT, Some(TEST_SNP_TCB)).is_ok());
+
+	let min_tcb = SnpTcbVersion { snp: TEST_SNP_TCB.snp + 1, ..TEST_SNP_TCB };
+	assert_eq!(verify(TEST_SNP_REPORT, Some(min_tcb)), Err(Error::TcbVersionIsTooLow));
+}
+
+#[test]
+fn snp_chain_with_swapped_certificates_is_rejected() {
+	assert_eq!(
+		verify_snp_report(
+			TEST_SNP_REPORT,
+			TEST_SNP_VCEK,
+			TEST_SNP_ARK,
+			TEST_SNP_ARK,
+			TEST_SNP_TIMESTAMP,
+			None
+		),
+		Err(Error::CertificateIssuerMismatch)
+	);
+}
+
+#[test]
+fn snp_chain_is_rejected_outside_its_validity() {
+	// 2023-01-01T00:00:00Z, before the certificates have been issued
+	assert_eq!(
+		verify_snp_report(
+			TEST_SNP_REPORT,
+			TEST_SNP_VCEK,
+			TEST_SNP_ASK,
+			TEST_SNP_ARK,
+			1_672_531_200_000,
+			None
+		),
+		Err(Error::CertificateIsNotValidAtTime)
+	);
+}
+
+#[test]
+fn verify_root_certificate_works() {
+	assert_eq!(verify_root_certificate(TEST_SNP_ARK, TEST_SNP_TIMESTAMP), Ok(()));
+	assert_eq!(
+		verify_root_certificate(TEST_SNP_ASK, TEST_SNP_TIMESTAMP),
+		Err(Error::CertificateIssuerMismatch)
+	);
+}
@@ -0,0 +1,130 @@
+#!/usr/bin/env python3
+"""Generates a synthetic AMD SEV-SNP certificate chain and a signed attestation report.
+
+The chain mimics AMD's: a self-signed ARK and an ASK, both RSA and signed with RSASSA-PSS
+(SHA-384), and an ECDSA P-384 VCEK which carries the TCB and chip id extensions. The report is
+signed by the VCEK. Requires the `cryptography` package.
+"""
+
+import datetime
+import struct
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec, padding, rsa
+from cryptography.hazmat.primitives.asymmetric.utils import decode_dss_signature
+from cryptography.x509.oid import NameOID
+
+NOT_BEFORE = datetime.datetime(2024, 1, 1, tzinfo=datetime.timezone.utc)
+NOT_AFTER = datetime.datetime(2049, 1, 1, tzinfo=datetime.timezone.utc)
+
+# bootloader, tee, snp, microcode
+TCB = (3, 0, 14, 209)
+CHIP_ID = bytes(range(64))
+MEASUREMENT = bytes.fromhex("5e" * 48)
+ID_KEY_DIGEST = bytes.fromhex("1d" * 48)
+# an sr25519/ed25519 public key, which decodes into the account id of the sender
+REPORT_DATA = bytes.fromhex("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d") + bytes(32)
+# SMT allowed, reserved bit 17 set, debugging not allowed
+POLICY = 0x30000
+
+PSS = padding.PSS(mgf=padding.MGF1(hashes.SHA384()), salt_length=48)
+
+
+def name(cn):
+    return x509.Name([
+        x509.NameAttribute(NameOID.ORGANIZATIONAL_UNIT_NAME, "Engineering"),
+        x509.NameAttribute(NameOID.COUNTRY_NAME, "US"),
+        x509.NameAttribute(NameOID.ORGANIZATION_NAME, "Synthetic Micro Devices"),
+        x509.NameAttribute(NameOID.COMMON_NAME, cn),
+    ])
+
+
+def certificate(subject, issuer, public_key, signing_key, serial, ca, extensions=()):
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(name(subject))
+        .issuer_name(name(issuer))
+        .public_key(public_key)
+        .serial_number(serial)
+        .not_valid_before(NOT_BEFORE)
+        .not_valid_after(NOT_AFTER)
+        .add_extension(x509.BasicConstraints(ca=ca, path_length=None), critical=True)
+    )
+    for extension in extensions:
+        builder = builder.add_extension(extension, critical=False)
+    return builder.sign(signing_key, hashes.SHA384(), rsa_padding=PSS)
+
+
+def der_integer(value):
+    return bytes([0x02, 0x01, value]) if value < 0x80 else bytes([0x02, 0x02, 0x00, value])
+
+
+def amd_extension(oid, value):
+    return x509.UnrecognizedExtension(x509.ObjectIdentifier("1.3.6.1.4.1.3704.1." + oid), value)
+
+
+def tcb_version(tcb):
+    bootloader, tee, snp, microcode = tcb
+    return bytes([bootloader, tee, 0, 0, 0, 0, snp, microcode])
+
+
+def le_component(value):
+    return value.to_bytes(48, "little") + bytes(24)
+
+
+def main():
+    ark_key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    ask_key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    vcek_key = ec.generate_private_key(ec.SECP384R1())
+
+    ark = certificate("ARK-Synthetic", "ARK-Synthetic", ark_key.public_key(), ark_key, 1, True)
+    ask = certificate("SEV-Synthetic", "ARK-Synthetic", ask_key.public_key(), ark_key, 2, True)
+    vcek = certificate(
+        "SEV-VCEK", "SEV-Synthetic", vcek_key.public_key(), ask_key, 3, False,
+        [
+            amd_extension("3.1", der_integer(TCB[0])),
+            amd_extension("3.2", der_integer(TCB[1])),
+            amd_extension("3.3", der_integer(TCB[2])),
+            amd_extension("3.8", der_integer(TCB[3])),
+            amd_extension("4", bytes([0x04, 0x40]) + CHIP_ID),
+        ],
+    )
+
+    tcb = tcb_version(TCB)
+    report = b"".join([
+        struct.pack("<IIQ", 2, 1, POLICY),
+        bytes(16),  # family id
+        bytes(16),  # image id
+        struct.pack("<II", 0, 1),  # vmpl, signature algorithm
+        tcb,  # current tcb
+        struct.pack("<QII", 0, 0, 0),  # platform info, flags, reserved
+        REPORT_DATA,
+        MEASUREMENT,
+        bytes(32),  # host data
+        ID_KEY_DIGEST,
+        bytes(48),  # author key digest
+        bytes(32),  # report id
+        bytes(32),  # report id of the migration agent
+        tcb,  # reported tcb
+        bytes(24),
+        CHIP_ID,
+        tcb,  # committed tcb
+        bytes([7, 55, 1, 0, 7, 55, 1, 0]),  # current and committed build, minor, major
+        tcb,  # launch tcb
+        bytes(168),
+    ])
+    assert len(report) == 0x2A0
+    r, s = decode_dss_signature(vcek_key.sign(report, ec.ECDSA(hashes.SHA384())))
+    report += le_component(r) + le_component(s) + bytes(368)
+    assert len(report) == 0x4A0
+
+    for file, cert in [("ark.der", ark), ("ask.der", ask), ("vcek.der", vcek)]:
+        with open(file, "wb") as f:
+            f.write(cert.public_bytes(serialization.Encoding.DER))
+    with open("report.bin", "wb") as f:
+        f.write(report)
+
+
+if __name__ == "__main__":
+    main()
@@ -22,18 +22,26 @@
 use super::*;
 
 use crate::{
-	test_helpers::{get_test_tcb_info, register_test_quoting_enclave, register_test_tcb_info},
+	test_helpers::{
+		get_test_tcb_info, insert_test_tcb_levels, pad_certificate_chain, pad_dcap_quote,
+		register_test_quoting_enclave, register_test_tcb_info,
+	},
 	Pallet as Teerex,
 };
-use frame_benchmarking::{account, benchmarks};
+use frame_benchmarking::{account, benchmarks, BenchmarkError};
+use frame_support::traits::EnsureOrigin;
 use frame_system::RawOrigin;
 use sp_runtime::traits::CheckedConversion;
 use test_utils::{
+	eat_test_data::*,
 	get_signer,
+	snp_test_data::*,
 	test_data::{consts::*, dcap::*, ias::*},
 };
 
 const MAX_SILENCE_TIME: u64 = 172_800_000; // 48h
+/// Collateral is not bounded, the weight is linear in its length.
+const MAX_COLLATERAL_PADDING: u32 = 10_000;
 
 fn generate_accounts<T: Config>(amount: u32) -> Vec<T::AccountId> {
 	(0..amount).map(|n| account("dummy name", n, n)).collect()
@@ -49,27 +57,46 @@ benchmarks! {
 
 	// Benchmark `register_sgx_enclave` with the worst possible conditions (DCAP sovereign is more involved than Ias or proxied DCAP):
 	// * dcap registration succeeds with `proxied: false`
+	// * `p`: the length of the quote, padded up to the maximal proof length
+	// * `l`: the number of TCB levels, of which only the last one matches
 	register_sgx_enclave {
+		let p in (TEST1_DCAP_QUOTE.len() as u32) .. (SGX_RA_PROOF_MAX_LEN as u32);
+		let l in 1 .. MAX_TCB_LEVELS;
 		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP.checked_into().unwrap());
 		let signer: T::AccountId = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+		let origin = T::CollateralOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
 
-		register_test_quoting_enclave::<T>(signer.clone());
-		register_test_tcb_info::<T>(signer.clone());
+		register_test_quoting_enclave::<T>(origin.clone());
+		register_test_tcb_info::<T>(origin);
+		insert_test_tcb_levels::<T>(l);
+		let proof = pad_dcap_quote(TEST1_DCAP_QUOTE, p as usize);
 
-	}: _(RawOrigin::Signed(signer), TEST1_DCAP_QUOTE.to_vec(), Some(URL.to_vec()), SgxAttestationMethod::Dcap { proxied: false })
+	}: _(RawOrigin::Signed(signer), proof, Some(URL.to_vec()), SgxAttestationMethod::Dcap { proxied: false })
 	verify {
 		let enclave_vec = <SovereignEnclaves<T>>::iter()
 		.collect::<Vec<(T::AccountId, MultiEnclave<Vec<u8>>)>>();
 		assert_eq!(enclave_vec.len(), 1);
 	}
 
+	// Benchmark `register_sgx_enclave` when attestation is skipped. Early rejections are
+	// refunded to this weight.
+	register_sgx_enclave_skip {
+		pallet_timestamp::Pallet::<T>::set_timestamp(TEST4_TIMESTAMP.checked_into().unwrap());
+		crate::AllowSkippingAttestation::<T>::put(true);
+		let signer: T::AccountId = get_signer(TEST4_SIGNER_PUB);
+
+	}: register_sgx_enclave(RawOrigin::Signed(signer.clone()), TEST4_MRENCLAVE.to_vec(), Some(URL.to_vec()), SgxAttestationMethod::Skip { proxied: false })
+	verify {
+		assert!(crate::SovereignEnclaves::<T>::contains_key(&signer));
+	}
+
 	// Benchmark `register_quoting_enclave` with the worst possible conditions:
 	// * quoting enclave registration succeeds
 	register_quoting_enclave {
 		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP.checked_into().unwrap());
-		let signer: T::AccountId = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+		let origin = T::CollateralOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
 
-	}: _(RawOrigin::Signed(signer), QUOTING_ENCLAVE.to_vec(), QUOTING_ENCLAVE_SIGNATURE.to_vec(), QE_IDENTITY_ISSUER_CHAIN.to_vec())
+	}: _<T::RuntimeOrigin>(origin, QUOTING_ENCLAVE.to_vec(), QUOTING_ENCLAVE_SIGNATURE.to_vec(), QE_IDENTITY_ISSUER_CHAIN.to_vec())
 	verify {
 		let qe = Pallet::<T>::quoting_enclave();
 		assert_eq!(qe.isvprodid, 1);
@@ -77,12 +104,18 @@ benchmarks! {
 
 	// Benchmark `register_tcb_info` with the worst possible conditions:
 	// * tcb registration succeeds
+	// * `x`: the length of all arguments, the certificate chain is padded
 	register_tcb_info {
+		let x in (test_tcb_info_len()) .. (test_tcb_info_len() + MAX_COLLATERAL_PADDING);
 		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP.checked_into().unwrap());
-		let signer: T::AccountId = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
-		register_test_quoting_enclave::<T>(signer.clone());
+		let origin = T::CollateralOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		register_test_quoting_enclave::<T>(origin.clone());
+		let certificate_chain = pad_certificate_chain(
+			TCB_INFO_CERTIFICATE_CHAIN,
+			TCB_INFO_CERTIFICATE_CHAIN.len() + (x - test_tcb_info_len()) as usize,
+		);
 
-	}: _(RawOrigin::Signed(signer), TCB_INFO.to_vec(), TCB_INFO_SIGNATURE.to_vec(), TCB_INFO_CERTIFICATE_CHAIN.to_vec())
+	}: _<T::RuntimeOrigin>(origin, TCB_INFO.to_vec(), TCB_INFO_SIGNATURE.to_vec(), certificate_chain)
 	verify {
 		// This is the date that the is registered in register_tcb_info and represents the date 2023-04-16T12:45:32Z
 		assert_eq!(get_test_tcb_info::<T>().next_update, 1681649132000);
@@ -119,11 +152,138 @@ benchmarks! {
 	}
 
 	set_security_flags {
-	}: _(RawOrigin::Root, false, true)
+		let origin = T::SecurityFlagsOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, false, true)
 	verify {
 		assert_eq!(crate::AllowSkippingAttestation::<T>::get(), false);
 		assert_eq!(crate::SgxAllowDebugMode::<T>::get(), true);
 	}
+
+	set_attestation_freshness_policy {
+		let origin = T::AttestationPolicyOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let policy = AttestationFreshnessPolicy::new(MAX_SILENCE_TIME, MAX_SILENCE_TIME);
+	}: _<T::RuntimeOrigin>(origin, SgxAttestationMethod::Dcap { proxied: true }.into(), Some(policy))
+	verify {
+		assert_eq!(
+			crate::AttestationFreshnessPolicies::<T>::get(AttestationMethod::from(SgxAttestationMethod::Dcap { proxied: true })),
+			Some(policy)
+		);
+	}
+
+	set_security_flags_override {
+		let origin = T::SecurityFlagsOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let fingerprint = EnclaveFingerprint::from(TEST4_SETUP.mrenclave);
+	}: _<T::RuntimeOrigin>(origin, fingerprint, true, true)
+	verify {
+		assert_eq!(crate::SecurityFlagsOverrides::<T>::get(fingerprint), Some(SecurityFlags::new(true, true)));
+	}
+
+	clear_security_flags_override {
+		let origin = T::SecurityFlagsOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let fingerprint = EnclaveFingerprint::from(TEST4_SETUP.mrenclave);
+		crate::SecurityFlagsOverrides::<T>::insert(fingerprint, SecurityFlags::new(true, true));
+	}: _<T::RuntimeOrigin>(origin, fingerprint)
+	verify {
+		assert!(!crate::SecurityFlagsOverrides::<T>::contains_key(fingerprint));
+	}
+
+	set_quoting_enclave_min_isvsvn {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, Some(6))
+	verify {
+		assert_eq!(crate::QuotingEnclaveMinIsvsvn::<T>::get(), Some(6));
+	}
+
+	// Benchmark `register_snp_enclave` with the worst possible conditions:
+	// * registration succeeds, including the min TCB check
+	register_snp_enclave {
+		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_SNP_TIMESTAMP.checked_into().unwrap());
+		let signer: T::AccountId = get_signer(&TEST_SNP_REPORT_SIGNER);
+		crate::SnpRootCertificate::<T>::put(TEST_SNP_ARK.to_vec());
+		crate::SnpMinTcb::<T>::put(TEST_SNP_TCB);
+
+	}: _(RawOrigin::Signed(signer.clone()), TEST_SNP_REPORT.to_vec(), TEST_SNP_VCEK.to_vec(), TEST_SNP_ASK.to_vec(), Some(URL.to_vec()))
+	verify {
+		assert!(crate::SovereignEnclaves::<T>::contains_key(&signer));
+	}
+
+	set_snp_root_certificate {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_SNP_TIMESTAMP.checked_into().unwrap());
+	}: _<T::RuntimeOrigin>(origin, TEST_SNP_ARK.to_vec())
+	verify {
+		assert_eq!(crate::SnpRootCertificate::<T>::get(), Some(TEST_SNP_ARK.to_vec()));
+	}
+
+	set_snp_min_tcb {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, Some(TEST_SNP_TCB))
+	verify {
+		assert_eq!(crate::SnpMinTcb::<T>::get(), Some(TEST_SNP_TCB));
+	}
+
+	// Benchmark `register_eat_enclave` with the worst possible conditions:
+	// * registration succeeds
+	register_eat_enclave {
+		pallet_timestamp::Pallet::<T>::set_timestamp(TEST_EAT_TIMESTAMP.checked_into().unwrap());
+		let signer: T::AccountId = get_signer(&TEST_EAT_SIGNER);
+		let key = test_eat_trusted_key();
+		crate::EatTrustedKeys::<T>::insert(test_eat_profile(), key.key_id(), &key);
+
+	}: _(RawOrigin::Signed(signer.clone()), test_eat_profile(), key.key_id(), TEST_EAT_TOKEN.to_vec(), Some(URL.to_vec()))
+	verify {
+		assert!(crate::SovereignEnclaves::<T>::contains_key(&signer));
+	}
+
+	add_eat_trusted_key {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let key = test_eat_trusted_key();
+	}: _<T::RuntimeOrigin>(origin, test_eat_profile(), key.clone())
+	verify {
+		assert_eq!(crate::EatTrustedKeys::<T>::get(test_eat_profile(), key.key_id()), Some(key));
+	}
+
+	remove_eat_trusted_key {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let key = test_eat_trusted_key();
+		crate::EatTrustedKeys::<T>::insert(test_eat_profile(), key.key_id(), &key);
+	}: _<T::RuntimeOrigin>(origin, test_eat_profile(), key.key_id())
+	verify {
+		assert!(!crate::EatTrustedKeys::<T>::contains_key(test_eat_profile(), key.key_id()));
+	}
+
+	set_quoting_enclave_tcb_status_policy {
+		let origin = T::TrustAnchorOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let policy = QeTcbStatusPolicy { accept_out_of_date: true, ..Default::default() };
+	}: _<T::RuntimeOrigin>(origin, policy)
+	verify {
+		assert_eq!(crate::QuotingEnclaveTcbStatusPolicy::<T>::get(), policy);
+	}
+
+	set_allowed_sgx_platforms {
+		let origin = T::AttestationPolicyOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let policy = SgxPlatformPolicy { allow_smt: false, ..Default::default() };
+	}: _<T::RuntimeOrigin>(origin, policy)
+	verify {
+		assert_eq!(crate::AllowedSgxPlatforms::<T>::get(), policy);
+	}
+}
+
+/// The length of all arguments of `register_tcb_info` with the test collateral.
+fn test_tcb_info_len() -> u32 {
+	(TCB_INFO.len() + TCB_INFO_SIGNATURE.len() + TCB_INFO_CERTIFICATE_CHAIN.len()) as u32
+}
+
+fn test_eat_profile() -> EatProfile {
+	EatProfile::try_from(TEST_EAT_PROFILE.to_vec()).unwrap()
+}
+
+fn test_eat_trusted_key() -> EatTrustedKey {
+	EatTrustedKey {
+		algorithm: EatSignatureAlgorithm::Es256,
+		public_key: TEST_EAT_PUBLIC_KEY.to_vec().try_into().unwrap(),
+		measurement_claim: TEST_EAT_MEASUREMENT_CLAIM,
+	}
 }
 
 fn add_sovereign_enclaves_to_registry<T: Config>(accounts: &[T::AccountId]) {
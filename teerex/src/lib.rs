@@ -18,7 +18,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-	dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo},
+	dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo, WithPostDispatchInfo},
 	ensure,
 	pallet_prelude::StorageVersion,
 	traits::Get,
@@ -26,9 +26,9 @@ use frame_support::{
 use frame_system::{self, ensure_signed};
 use parity_scale_codec::Encode;
 use sgx_verify::{
-	deserialize_enclave_identity, deserialize_tcb_info, extract_certs, verify_certificate_chain,
+	deserialize_enclave_identity, deserialize_tcb_info, verify_certificate_chain, CertificateChain,
 };
-use sp_runtime::{traits::SaturatedConversion, Saturating};
+use sp_runtime::traits::SaturatedConversion;
 use sp_std::{prelude::*, str, vec};
 use teerex_primitives::*;
 
@@ -42,9 +42,18 @@ pub use pallet::*;
 
 const SGX_RA_PROOF_MAX_LEN: usize = 5000;
 
+/// Intel lists about 20 TCB levels per FMSPC. The weight of `register_sgx_enclave` is based on
+/// this bound.
+const MAX_TCB_LEVELS: u32 = 64;
+
+/// Upper bound for the SEV-SNP report and each of its certificates.
+const SNP_PROOF_MAX_LEN: usize = 8000;
+
+const EAT_TOKEN_MAX_LEN: usize = 8000;
+
 const MAX_URL_LEN: usize = 256;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -64,14 +73,33 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + pallet_timestamp::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// Default maximal age of an attestation at registration time. Can be overridden per
+		/// attestation method with `set_attestation_freshness_policy`.
 		#[pallet::constant]
 		type MomentsPerDay: Get<Self::Moment>;
 
 		type WeightInfo: WeightInfo;
 
 		/// If a worker does not re-register within `MaxAttestationRenewalPeriod`, it can be unregistered by anyone.
+		/// Can be overridden per attestation method with `set_attestation_freshness_policy`.
 		#[pallet::constant]
 		type MaxAttestationRenewalPeriod: Get<Self::Moment>;
+
+		/// The origin allowed to change the global security flags, e.g. root or a technical committee.
+		type SecurityFlagsOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin allowed to register quoting enclave identities and TCB infos.
+		/// Use `EnsureSigned` to keep collateral registration permissionless.
+		type CollateralOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin allowed to set trust anchors which are not verified on chain: the SEV-SNP
+		/// root certificate, trusted EAT keys and the TCB floors for quoting enclaves and
+		/// SEV-SNP. Must never be permissionless, unlike `CollateralOrigin`.
+		type TrustAnchorOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin allowed to set the attestation freshness policies and the SGX platforms
+		/// allowed to register.
+		type AttestationPolicyOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 	}
 
 	#[pallet::event]
@@ -94,6 +122,47 @@ pub mod pallet {
 		SgxQuotingEnclaveRegistered { quoting_enclave: SgxQuotingEnclave },
 		/// the enclave registry security flags have been updated
 		UpdatedSecurityFlags { allow_skipping_attestation: bool, sgx_allow_debug_mode: bool },
+		/// the freshness policy for an attestation method has been updated. `None` means that the defaults apply
+		UpdatedAttestationFreshnessPolicy {
+			attestation_method: AttestationMethod,
+			policy: Option<AttestationFreshnessPolicy>,
+		},
+		/// the security flags have been overridden for enclaves with a specific fingerprint
+		UpdatedSecurityFlagsOverride {
+			fingerprint: EnclaveFingerprint,
+			allow_skipping_attestation: bool,
+			sgx_allow_debug_mode: bool,
+		},
+		/// the security flags override for a fingerprint has been removed. The global flags apply again
+		ClearedSecurityFlagsOverride { fingerprint: EnclaveFingerprint },
+		/// the minimal ISVSVN for quoting enclaves has been updated. `None` means that only Intel's tcbStatus applies
+		UpdatedQuotingEnclaveMinIsvsvn { min_isvsvn: Option<u16> },
+		/// the tcbStatus values under which quoting enclaves are trusted have been updated
+		UpdatedQuotingEnclaveTcbStatusPolicy { policy: QeTcbStatusPolicy },
+		/// An AMD SEV-SNP enclave has been added to the enclave registry
+		AddedSnpEnclave {
+			registered_by: T::AccountId,
+			worker_url: Option<Vec<u8>>,
+			fingerprint: EnclaveFingerprint,
+			reported_tcb: SnpTcbVersion,
+		},
+		/// the trusted AMD root key (ARK) certificate has been updated
+		UpdatedSnpRootCertificate,
+		/// the minimal SEV-SNP TCB version has been updated. `None` means that any TCB version is accepted
+		UpdatedSnpMinTcb { min_tcb: Option<SnpTcbVersion> },
+		/// An enclave attested by an Entity Attestation Token has been added to the enclave registry
+		AddedEatEnclave {
+			registered_by: T::AccountId,
+			worker_url: Option<Vec<u8>>,
+			profile: EatProfile,
+			fingerprint: EnclaveFingerprint,
+		},
+		/// a key has been trusted to sign attestation tokens of a profile
+		AddedEatTrustedKey { profile: EatProfile, key_id: EatKeyId },
+		/// a key is no longer trusted to sign attestation tokens of a profile
+		RemovedEatTrustedKey { profile: EatProfile, key_id: EatKeyId },
+		/// the platforms SGX enclaves may register from with DCAP have been restricted
+		UpdatedAllowedSgxPlatforms { policy: SgxPlatformPolicy },
 	}
 
 	#[pallet::error]
@@ -126,98 +195,51 @@ pub mod pallet {
 		MissingTcbInfoForFmspc,
 		/// Either the enclave TCB has outdated status or the onchain TCB collateral is outdated
 		TcbInfoIsOutdated,
+		/// An attestation freshness policy must allow attestations of non-zero age
+		AttestationFreshnessPolicyIsInvalid,
+		/// There is no security flags override for this fingerprint
+		SecurityFlagsOverrideNotFound,
+		/// The TCB levels of the TCB info are not in descending order
+		TcbLevelsAreNotOrdered,
+		/// The TCB info has more TCB levels than `MAX_TCB_LEVELS`
+		TooManyTcbLevels,
+		/// No AMD root key certificate has been set, SEV-SNP enclaves can not be registered
+		SnpRootCertificateIsMissing,
+		/// The key is not trusted to sign attestation tokens of this profile
+		EatTrustedKeyNotFound,
+		/// The platform stated in the PCK certificate is not allowed by `AllowedSgxPlatforms`
+		SgxPlatformIsNotAllowed,
 
-		// mapped errors from sgx_verify
 		/// An error originating in the sgx_verify crate
-		OtherSgxVerifyError(sgx_verify::Error),
-		CaVerificationFailed,
-		CertificateChainIsInvalid,
-		CertificateChainIsTooShort,
-		CpuSvnDecodingError,
-		CpuSvnLengthMismatch,
-		CpuSvnOidIsMissing,
-		DcapKeyTypeMismatch,
-		DcapQuoteDecodingError,
-		DcapQuoteIsTooLong,
-		DcapQuoteVersionMismatch,
-		DerEncodingError,
-		EnclaveIdentityDecodingError,
-		EnclaveIdentitySignatureIsInvalid,
-		FmspcDecodingError,
-		FmspcLengthMismatch,
-		FmspcOidIsMissing,
-		IntelExtensionAmbiguity,
-		IntelExtensionCertificateDecodingError,
-		IsvEnclaveReportSignatureIsInvalid,
-		KeyLengthIsInvalid,
-		LeafCertificateParsingError,
-		NetscapeDecodingError,
-		NetscapeDerError,
-		PceSvnDecodingError,
-		PceSvnLengthMismatch,
-		PceSvnOidIsMissing,
-		PckCertFormatMismatch,
-		PublicKeyIsInvalid,
-		QeHasRejectedEnclave,
-		QeReportHashMismatch,
-		QuoteBodyDecodingError,
-		QuoteBodyIsInvalid,
-		QuoteBodyMissing,
-		QuoteStatusMissing,
-		RsaSignatureIsInvalid,
-		SgxReportParsingError,
-		TcbInfoIsInvalid,
-		TimestampIsInvalid,
-		TimestampIsMissing,
+		SgxVerifyError(sgx_verify::Error),
+		/// An error originating in the snp_verify crate
+		SnpVerifyError(snp_verify::Error),
+		/// An error originating in the eat_verify crate
+		EatVerifyError(eat_verify::Error),
 	}
 
 	impl<T> From<sgx_verify::Error> for Error<T> {
 		fn from(e: sgx_verify::Error) -> Self {
-			use sgx_verify::Error as Theirs;
-			match e {
-				Theirs::CaVerificationFailed => Self::CaVerificationFailed,
-				Theirs::CertificateChainIsInvalid => Self::CertificateChainIsInvalid,
-				Theirs::CertificateChainIsTooShort => Self::CertificateChainIsTooShort,
-				Theirs::CpuSvnDecodingError => Self::CpuSvnDecodingError,
-				Theirs::CpuSvnLengthMismatch => Self::CpuSvnLengthMismatch,
-				Theirs::CpuSvnOidIsMissing => Self::CpuSvnOidIsMissing,
-				Theirs::DcapKeyTypeMismatch => Self::DcapKeyTypeMismatch,
-				Theirs::DcapQuoteDecodingError => Self::DcapQuoteDecodingError,
-				Theirs::DcapQuoteIsTooLong => Self::DcapQuoteIsTooLong,
-				Theirs::DcapQuoteVersionMismatch => Self::DcapQuoteVersionMismatch,
-				Theirs::DerEncodingError => Self::DerEncodingError,
-				Theirs::EnclaveIdentityDecodingError => Self::EnclaveIdentityDecodingError,
-				Theirs::EnclaveIdentitySignatureIsInvalid =>
-					Self::EnclaveIdentitySignatureIsInvalid,
-				Theirs::FmspcDecodingError => Self::FmspcDecodingError,
-				Theirs::FmspcLengthMismatch => Self::FmspcLengthMismatch,
-				Theirs::FmspcOidIsMissing => Self::FmspcOidIsMissing,
-				Theirs::IntelExtensionAmbiguity => Self::IntelExtensionAmbiguity,
-				Theirs::IntelExtensionCertificateDecodingError =>
-					Self::IntelExtensionCertificateDecodingError,
-				Theirs::IsvEnclaveReportSignatureIsInvalid =>
-					Self::IsvEnclaveReportSignatureIsInvalid,
-				Theirs::KeyLengthIsInvalid => Self::KeyLengthIsInvalid,
-				Theirs::LeafCertificateParsingError => Self::LeafCertificateParsingError,
-				Theirs::NetscapeDecodingError => Self::NetscapeDecodingError,
-				Theirs::NetscapeDerError => Self::NetscapeDerError,
-				Theirs::PceSvnDecodingError => Self::PceSvnDecodingError,
-				Theirs::PceSvnLengthMismatch => Self::PceSvnLengthMismatch,
-				Theirs::PceSvnOidIsMissing => Self::PceSvnOidIsMissing,
-				Theirs::PckCertFormatMismatch => Self::PckCertFormatMismatch,
-				Theirs::PublicKeyIsInvalid => Self::PublicKeyIsInvalid,
-				Theirs::QeHasRejectedEnclave => Self::QeHasRejectedEnclave,
-				Theirs::QeReportHashMismatch => Self::QeReportHashMismatch,
-				Theirs::QuoteBodyDecodingError => Self::QuoteBodyDecodingError,
-				Theirs::QuoteBodyIsInvalid => Self::QuoteBodyIsInvalid,
-				Theirs::QuoteBodyMissing => Self::QuoteBodyMissing,
-				Theirs::QuoteStatusMissing => Self::QuoteStatusMissing,
-				Theirs::RsaSignatureIsInvalid => Self::RsaSignatureIsInvalid,
-				Theirs::SgxReportParsingError => Self::SgxReportParsingError,
-				Theirs::TcbInfoIsInvalid => Self::TcbInfoIsInvalid,
-				Theirs::TimestampIsInvalid => Self::TimestampIsInvalid,
-				Theirs::TimestampIsMissing => Self::TimestampIsMissing,
-			}
+			Self::SgxVerifyError(e)
+		}
+	}
+
+	impl<T> From<snp_verify::Error> for Error<T> {
+		fn from(e: snp_verify::Error) -> Self {
+			Self::SnpVerifyError(e)
+		}
+	}
+
+	impl<T> From<eat_verify::Error> for Error<T> {
+		fn from(e: eat_verify::Error) -> Self {
+			Self::EatVerifyError(e)
+		}
+	}
+
+	/// Only the kind of the error ends up on chain. Log the error beforehand to keep its context.
+	impl<T> From<sgx_verify::VerificationError> for Error<T> {
+		fn from(e: sgx_verify::VerificationError) -> Self {
+			Self::SgxVerifyError(e.kind)
 		}
 	}
 
@@ -265,6 +287,61 @@ pub mod pallet {
 	pub type AllowSkippingAttestation<T: Config> =
 		StorageValue<_, bool, ValueQuery, DefaultAllowSkippingAttestation<T>>;
 
+	/// Overrides of the global `AllowSkippingAttestation` and `SgxAllowDebugMode` flags for
+	/// enclaves with a specific fingerprint.
+	#[pallet::storage]
+	#[pallet::getter(fn security_flags_override)]
+	pub type SecurityFlagsOverrides<T: Config> =
+		StorageMap<_, Blake2_128Concat, EnclaveFingerprint, SecurityFlags, OptionQuery>;
+
+	/// Optional floor for the ISVSVN of quoting enclaves. It applies on top of the `tcbStatus` in
+	/// Intel's QE identity when `register_quoting_enclave` is called.
+	#[pallet::storage]
+	#[pallet::getter(fn quoting_enclave_min_isvsvn)]
+	pub type QuotingEnclaveMinIsvsvn<T: Config> = StorageValue<_, u16, OptionQuery>;
+
+	/// The `tcbStatus` values, besides `UpToDate`, under which the TCB levels of Intel's QE
+	/// identity are trusted when `register_quoting_enclave` is called.
+	#[pallet::storage]
+	#[pallet::getter(fn quoting_enclave_tcb_status_policy)]
+	pub type QuotingEnclaveTcbStatusPolicy<T: Config> =
+		StorageValue<_, QeTcbStatusPolicy, ValueQuery>;
+
+	/// Restricts the SGX type and configuration of the platforms DCAP attested enclaves run on.
+	#[pallet::storage]
+	#[pallet::getter(fn allowed_sgx_platforms)]
+	pub type AllowedSgxPlatforms<T: Config> = StorageValue<_, SgxPlatformPolicy, ValueQuery>;
+
+	/// The DER encoded AMD root key (ARK) certificate SEV-SNP attestations are verified against.
+	#[pallet::storage]
+	#[pallet::getter(fn snp_root_certificate)]
+	pub type SnpRootCertificate<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+	/// Optional floor for the TCB version of SEV-SNP enclaves, compared component-wise.
+	#[pallet::storage]
+	#[pallet::getter(fn snp_min_tcb)]
+	pub type SnpMinTcb<T: Config> = StorageValue<_, SnpTcbVersion, OptionQuery>;
+
+	/// The keys trusted to sign Entity Attestation Tokens, per profile.
+	#[pallet::storage]
+	#[pallet::getter(fn eat_trusted_key)]
+	pub type EatTrustedKeys<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EatProfile,
+		Blake2_128Concat,
+		EatKeyId,
+		EatTrustedKey,
+		OptionQuery,
+	>;
+
+	/// Overrides of the default attestation freshness policy per attestation method.
+	/// Sovereign and proxied enclaves are distinguished by the `proxied` flag of the method.
+	#[pallet::storage]
+	#[pallet::getter(fn attestation_freshness_policy_override)]
+	pub type AttestationFreshnessPolicies<T: Config> =
+		StorageMap<_, Blake2_128Concat, AttestationMethod, AttestationFreshnessPolicy, OptionQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T> {
@@ -290,8 +367,11 @@ pub mod pallet {
 		<T as frame_system::Config>::Hash: From<[u8; 32]>,
 	{
 		// the integritee-service wants to register his enclave
+		//
+		// The weight assumes DCAP with the maximal number of TCB levels. The actual weight is
+		// returned, so that skipped attestations and early rejections are refunded.
 		#[pallet::call_index(0)]
-		#[pallet::weight((<T as Config>::WeightInfo::register_sgx_enclave(), DispatchClass::Normal, Pays::Yes))]
+		#[pallet::weight((<T as Config>::WeightInfo::register_sgx_enclave(proof.len() as u32, MAX_TCB_LEVELS), DispatchClass::Normal, Pays::Yes))]
 		pub fn register_sgx_enclave(
 			origin: OriginFor<T>,
 			proof: Vec<u8>,
@@ -300,22 +380,34 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			log::debug!(target: TEEREX, "called into runtime call register_sgx_enclave()");
 			let sender = ensure_signed(origin)?;
-			ensure!(proof.len() <= SGX_RA_PROOF_MAX_LEN, Error::<T>::RaProofIsTooLong);
+			// Skipping attestation is the cheapest way through this call, it is an upper bound for
+			// everything that happens before the proof is verified.
+			let early_rejection_weight = <T as Config>::WeightInfo::register_sgx_enclave_skip();
+			ensure!(
+				proof.len() <= SGX_RA_PROOF_MAX_LEN,
+				Error::<T>::RaProofIsTooLong.with_weight(early_rejection_weight)
+			);
 			if let Some(ref url) = worker_url {
-				ensure!(url.len() <= MAX_URL_LEN, Error::<T>::EnclaveUrlIsTooLong);
+				ensure!(
+					url.len() <= MAX_URL_LEN,
+					Error::<T>::EnclaveUrlIsTooLong.with_weight(early_rejection_weight)
+				);
 			}
 			log::debug!(target: TEEREX, "parameter length ok");
 
-			let enclave = match attestation_method {
+			// Verifying an IAS report is cheaper than verifying a DCAP quote, and the TCB levels
+			// are only examined after the quote has been verified.
+			let verification_weight =
+				<T as Config>::WeightInfo::register_sgx_enclave(proof.len() as u32, 1);
+
+			let (enclave, actual_weight) = match attestation_method {
 				SgxAttestationMethod::Ias => {
 					let report = sgx_verify::verify_ias_report(&proof).map_err(|e| {
-						log::info!(target: TEEREX, "verify_ias_quote failed: {:?}", e);
-						Error::<T>::from(e)
+						log::info!(target: TEEREX, "verify_ias_quote failed: {}", e);
+						Error::<T>::from(e).with_weight(verification_weight)
 					})?;
 					log::debug!(target: TEEREX, "IAS report successfully verified");
 
-					Self::ensure_timestamp_within_24_hours(report.timestamp)?;
-
 					let enclave = SgxEnclave::new(
 						report.report_data,
 						report.mr_enclave,
@@ -329,7 +421,7 @@ pub mod pallet {
 					ensure!(
 						Ok(sender.clone()) ==
 							T::AccountId::decode(&mut report.report_data.lower32().as_ref()),
-						Error::<T>::SenderIsNotAttestedEnclave
+						Error::<T>::SenderIsNotAttestedEnclave.with_weight(verification_weight)
 					);
 
 					// TODO: activate state checks as soon as we've fixed our setup #83
@@ -337,7 +429,7 @@ pub mod pallet {
 					//     "RA status is insufficient");
 					// log::info!(target: TEEREX, "status is acceptable");
 
-					enclave
+					(enclave, verification_weight)
 				},
 				SgxAttestationMethod::Dcap { proxied } => {
 					let verification_time = <pallet_timestamp::Pallet<T>>::get();
@@ -349,37 +441,55 @@ pub mod pallet {
 						&qe,
 					)
 					.map_err(|e| {
-						log::info!(target: TEEREX, "verify_dcap_quote failed: {:?}", e);
-						Error::<T>::from(e)
+						log::info!(target: TEEREX, "verify_dcap_quote failed: {}", e);
+						Error::<T>::from(e).with_weight(verification_weight)
 					})?;
 
 					if !proxied {
 						ensure!(
 							Ok(sender.clone()) ==
 								T::AccountId::decode(&mut report.report_data.lower32().as_ref()),
-							Error::<T>::SenderIsNotAttestedEnclave
+							Error::<T>::SenderIsNotAttestedEnclave.with_weight(verification_weight)
 						);
 					}
 
+					// verified DCAP reports always state the platform
+					let platform = report.platform.ok_or(
+						Error::<T>::SgxPlatformIsNotAllowed.with_weight(verification_weight),
+					)?;
+					if !<AllowedSgxPlatforms<T>>::get().accepts(&platform) {
+						log::info!(target: TEEREX, "platform is not allowed: {:?}", platform);
+						return Err(
+							Error::<T>::SgxPlatformIsNotAllowed.with_weight(verification_weight)
+						)
+					}
+
 					log::debug!(
 						target: TEEREX,
 						"DCAP quote verified. FMSPC from quote: {}",
 						hex::encode(fmspc)
 					);
-					let sgx_status = match <SgxTcbInfo<T>>::get(fmspc) {
-						Some(reference) =>
+					let (sgx_status, weight) = match <SgxTcbInfo<T>>::get(fmspc) {
+						Some(reference) => {
+							let weight = <T as Config>::WeightInfo::register_sgx_enclave(
+								proof.len() as u32,
+								reference.tcb_levels().len() as u32,
+							);
 							if let Some(status) = reference.verify_examinee(&tcb_info) {
 								log::trace!("TCB info verification passed");
-								status
+								(status, weight)
 							} else {
-								return Err(Error::<T>::TcbInfoIsOutdated.into())
-							},
+								return Err(Error::<T>::TcbInfoIsOutdated.with_weight(weight))
+							}
+						},
 						None => {
 							log::warn!(
 								"No TCB info could be found onchain for the examinee's fmspc: {}",
 								hex::encode(fmspc)
 							);
-							return Err(Error::<T>::MissingTcbInfoForFmspc.into())
+							return Err(
+								Error::<T>::MissingTcbInfoForFmspc.with_weight(verification_weight)
+							)
 						},
 					};
 
@@ -388,7 +498,7 @@ pub mod pallet {
 					//     "RA status is insufficient");
 					// log::info!(target: TEEREX, "status is acceptable");
 
-					SgxEnclave::new(
+					let enclave = SgxEnclave::new(
 						report.report_data,
 						report.mr_enclave,
 						report.mr_signer,
@@ -397,30 +507,43 @@ pub mod pallet {
 						sgx_status,
 					)
 					.with_attestation_method(SgxAttestationMethod::Dcap { proxied })
+					.with_platform(platform);
+					(enclave, weight)
 				},
 				SgxAttestationMethod::Skip { proxied } => {
-					if !Self::allow_skipping_attestation() {
+					// insert mrenclave if the ra_report represents one, otherwise insert default
+					let mr_enclave = <MrEnclave>::decode(&mut proof.as_slice()).unwrap_or_default();
+					if !Self::effective_security_flags(&EnclaveFingerprint::from(mr_enclave))
+						.allow_skipping_attestation
+					{
 						log::debug!(target: TEEREX, "skipping attestation not allowed",);
-						return Err(Error::<T>::SkippingAttestationIsNotAllowed.into())
+						return Err(Error::<T>::SkippingAttestationIsNotAllowed
+							.with_weight(early_rejection_weight))
 					}
 					log::debug!(target: TEEREX, "skipping attestation verification",);
-					SgxEnclave::new(
+					let enclave = SgxEnclave::new(
 						SgxReportData::default(),
-						// insert mrenclave if the ra_report represents one, otherwise insert default
-						<MrEnclave>::decode(&mut proof.as_slice()).unwrap_or_default(),
+						mr_enclave,
 						MrSigner::default(),
 						<pallet_timestamp::Pallet<T>>::get().saturated_into(),
 						SgxBuildMode::default(),
 						SgxStatus::Invalid,
 					)
 					.with_pubkey(sender.encode().as_ref())
-					.with_attestation_method(SgxAttestationMethod::Skip { proxied })
+					.with_attestation_method(SgxAttestationMethod::Skip { proxied });
+					(enclave, early_rejection_weight)
 				},
 			};
 
-			if !<SgxAllowDebugMode<T>>::get() && enclave.build_mode == SgxBuildMode::Debug {
+			Self::ensure_attestation_is_fresh(enclave.timestamp, enclave.attestation_method.into())
+				.map_err(|e| e.error.with_weight(actual_weight))?;
+
+			if enclave.build_mode == SgxBuildMode::Debug &&
+				!Self::effective_security_flags(&EnclaveFingerprint::from(enclave.mr_enclave))
+					.sgx_allow_debug_mode
+			{
 				log::info!(target: TEEREX, "debug mode is not allowed to attest!");
-				return Err(Error::<T>::SgxModeIsNotAllowed.into())
+				return Err(Error::<T>::SgxModeIsNotAllowed.with_weight(actual_weight))
 			}
 
 			let enclave = match worker_url {
@@ -442,7 +565,7 @@ pub mod pallet {
 				tcb_status: Some(enclave.status),
 				attestation_method: enclave.attestation_method,
 			});
-			Ok(().into())
+			Ok(Some(actual_weight).into())
 		}
 
 		#[pallet::call_index(1)]
@@ -455,15 +578,8 @@ pub mod pallet {
 			ensure_signed(origin)?;
 			let enclave = Self::sovereign_enclaves(&enclave_signer)
 				.ok_or(Error::<T>::EnclaveIsNotRegistered)?;
-			let now = <pallet_timestamp::Pallet<T>>::get();
-			let oldest_acceptable_attestation_time = now
-				.saturating_sub(T::MaxAttestationRenewalPeriod::get())
-				.saturated_into::<u64>();
-			if enclave.attestation_timestamp() < oldest_acceptable_attestation_time {
-				<SovereignEnclaves<T>>::remove(&enclave_signer);
-			} else {
-				return Err(Error::<T>::UnregisterActiveEnclaveIsNotAllowed.into())
-			}
+			Self::ensure_attestation_renewal_is_overdue(&enclave)?;
+			<SovereignEnclaves<T>>::remove(&enclave_signer);
 			log::debug!(target: TEEREX, "removed sovereign enclave {:?}", enclave_signer);
 			Self::deposit_event(Event::RemovedSovereignEnclave(enclave_signer));
 			Ok(().into())
@@ -479,15 +595,8 @@ pub mod pallet {
 			ensure_signed(origin)?;
 			let enclave =
 				Self::proxied_enclaves(&address).ok_or(Error::<T>::EnclaveIsNotRegistered)?;
-			let now = <pallet_timestamp::Pallet<T>>::get();
-			let oldest_acceptable_attestation_time = now
-				.saturating_sub(T::MaxAttestationRenewalPeriod::get())
-				.saturated_into::<u64>();
-			if enclave.attestation_timestamp() < oldest_acceptable_attestation_time {
-				<ProxiedEnclaves<T>>::remove(&address);
-			} else {
-				return Err(Error::<T>::UnregisterActiveEnclaveIsNotAllowed.into())
-			}
+			Self::ensure_attestation_renewal_is_overdue(&enclave)?;
+			<ProxiedEnclaves<T>>::remove(&address);
 			log::info!(target: TEEREX, "removed proxied enclave {:?}", address);
 			Self::deposit_event(Event::RemovedProxiedEnclave(address));
 			Ok(().into())
@@ -503,7 +612,7 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			log::debug!(target: TEEREX, "Called into runtime call register_quoting_enclave()");
 			// Quoting enclaves are registered globally and not for a specific sender
-			let _sender = ensure_signed(origin)?;
+			T::CollateralOrigin::ensure_origin(origin)?;
 			let quoting_enclave = Self::verify_quoting_enclave(
 				enclave_identity.clone(),
 				signature,
@@ -516,7 +625,7 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(4)]
-		#[pallet::weight((<T as Config>::WeightInfo::register_tcb_info(), DispatchClass::Normal, Pays::Yes))]
+		#[pallet::weight((<T as Config>::WeightInfo::register_tcb_info((tcb_info.len() + signature.len() + certificate_chain.len()) as u32), DispatchClass::Normal, Pays::Yes))]
 		pub fn register_tcb_info(
 			origin: OriginFor<T>,
 			tcb_info: Vec<u8>,
@@ -525,8 +634,8 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			log::debug!(target: TEEREX, "Called into runtime call register_tcb_info()");
 			// TCB info is registered globally and not for a specific sender
-			let _sender = ensure_signed(origin)?;
-			log::trace!(target: TEEREX, "In register_tcb_info(), origin is ensured to be signed");
+			T::CollateralOrigin::ensure_origin(origin)?;
+			log::trace!(target: TEEREX, "In register_tcb_info(), origin is ensured");
 			let (fmspc, on_chain_info) =
 				Self::verify_tcb_info(tcb_info, signature, certificate_chain)?;
 			<SgxTcbInfo<T>>::insert(fmspc, &on_chain_info);
@@ -543,7 +652,7 @@ pub mod pallet {
 			sgx_allow_debug_mode: bool,
 		) -> DispatchResultWithPostInfo {
 			log::debug!(target: TEEREX, "Called into runtime call set_security_flags()");
-			ensure_root(origin)?;
+			T::SecurityFlagsOrigin::ensure_origin(origin)?;
 			<AllowSkippingAttestation<T>>::set(allow_skipping_attestation);
 			<SgxAllowDebugMode<T>>::set(sgx_allow_debug_mode);
 			log::info!(target: TEEREX, "set security flags");
@@ -553,6 +662,373 @@ pub mod pallet {
 			});
 			Ok(().into())
 		}
+
+		/// Override the freshness policy for one attestation method. Passing `None` restores the
+		/// defaults `MomentsPerDay` and `MaxAttestationRenewalPeriod`.
+		///
+		/// `Dcap`, `Skip` and `AmdSevSnp` enclaves are stamped with the chain time when they
+		/// register, so for these methods only the `renewal_period` of the policy has an effect.
+		#[pallet::call_index(6)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_attestation_freshness_policy(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_attestation_freshness_policy(
+			origin: OriginFor<T>,
+			attestation_method: AttestationMethod,
+			policy: Option<AttestationFreshnessPolicy>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(
+				target: TEEREX,
+				"Called into runtime call set_attestation_freshness_policy()"
+			);
+			T::AttestationPolicyOrigin::ensure_origin(origin)?;
+			if let Some(p) = policy {
+				ensure!(p.max_attestation_age > 0, Error::<T>::AttestationFreshnessPolicyIsInvalid);
+			}
+			<AttestationFreshnessPolicies<T>>::set(attestation_method, policy);
+			log::info!(
+				target: TEEREX,
+				"set attestation freshness policy for {:?}: {:?}",
+				attestation_method,
+				policy
+			);
+			Self::deposit_event(Event::UpdatedAttestationFreshnessPolicy {
+				attestation_method,
+				policy,
+			});
+			Ok(().into())
+		}
+
+		/// Override the global security flags for enclaves with the given fingerprint.
+		#[pallet::call_index(7)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_security_flags_override(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_security_flags_override(
+			origin: OriginFor<T>,
+			fingerprint: EnclaveFingerprint,
+			allow_skipping_attestation: bool,
+			sgx_allow_debug_mode: bool,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call set_security_flags_override()");
+			T::SecurityFlagsOrigin::ensure_origin(origin)?;
+			<SecurityFlagsOverrides<T>>::insert(
+				fingerprint,
+				SecurityFlags::new(allow_skipping_attestation, sgx_allow_debug_mode),
+			);
+			log::info!(target: TEEREX, "set security flags override for {:?}", fingerprint);
+			Self::deposit_event(Event::UpdatedSecurityFlagsOverride {
+				fingerprint,
+				allow_skipping_attestation,
+				sgx_allow_debug_mode,
+			});
+			Ok(().into())
+		}
+
+		/// Remove the security flags override for the given fingerprint, so that the global
+		/// flags apply again.
+		#[pallet::call_index(8)]
+		#[pallet::weight((<T as Config>::WeightInfo::clear_security_flags_override(), DispatchClass::Normal, Pays::Yes))]
+		pub fn clear_security_flags_override(
+			origin: OriginFor<T>,
+			fingerprint: EnclaveFingerprint,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call clear_security_flags_override()");
+			T::SecurityFlagsOrigin::ensure_origin(origin)?;
+			ensure!(
+				<SecurityFlagsOverrides<T>>::contains_key(fingerprint),
+				Error::<T>::SecurityFlagsOverrideNotFound
+			);
+			<SecurityFlagsOverrides<T>>::remove(fingerprint);
+			log::info!(target: TEEREX, "cleared security flags override for {:?}", fingerprint);
+			Self::deposit_event(Event::ClearedSecurityFlagsOverride { fingerprint });
+			Ok(().into())
+		}
+
+		/// Set a floor for the ISVSVN of quoting enclaves. Passing `None` removes it, so that only
+		/// the `tcbStatus` of Intel's QE identity decides. Takes effect with the next
+		/// `register_quoting_enclave`.
+		#[pallet::call_index(9)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_quoting_enclave_min_isvsvn(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_quoting_enclave_min_isvsvn(
+			origin: OriginFor<T>,
+			min_isvsvn: Option<u16>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call set_quoting_enclave_min_isvsvn()");
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			<QuotingEnclaveMinIsvsvn<T>>::set(min_isvsvn);
+			log::info!(target: TEEREX, "set quoting enclave min isvsvn to {:?}", min_isvsvn);
+			Self::deposit_event(Event::UpdatedQuotingEnclaveMinIsvsvn { min_isvsvn });
+			Ok(().into())
+		}
+
+		/// Register an AMD SEV-SNP enclave. The report must be signed by the `vcek`, whose chain
+		/// ends in the root certificate set with `set_snp_root_certificate`. The first 32 bytes of
+		/// the report data must be the sender's public key.
+		///
+		/// The actual weight is returned, so that early rejections are refunded.
+		#[pallet::call_index(10)]
+		#[pallet::weight((<T as Config>::WeightInfo::register_snp_enclave(), DispatchClass::Normal, Pays::Yes))]
+		pub fn register_snp_enclave(
+			origin: OriginFor<T>,
+			report: Vec<u8>,
+			vcek: Vec<u8>,
+			ask: Vec<u8>,
+			worker_url: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "called into runtime call register_snp_enclave()");
+			let sender = ensure_signed(origin)?;
+			// Skipping SGX attestation doesn't verify anything either, it is an upper bound for
+			// everything that happens before the report is verified.
+			let early_rejection_weight = <T as Config>::WeightInfo::register_sgx_enclave_skip();
+			ensure!(
+				report.len() <= SNP_PROOF_MAX_LEN &&
+					vcek.len() <= SNP_PROOF_MAX_LEN &&
+					ask.len() <= SNP_PROOF_MAX_LEN,
+				Error::<T>::RaProofIsTooLong.with_weight(early_rejection_weight)
+			);
+			if let Some(ref url) = worker_url {
+				ensure!(
+					url.len() <= MAX_URL_LEN,
+					Error::<T>::EnclaveUrlIsTooLong.with_weight(early_rejection_weight)
+				);
+			}
+			let ark = Self::snp_root_certificate().ok_or(
+				Error::<T>::SnpRootCertificateIsMissing.with_weight(early_rejection_weight),
+			)?;
+			let verification_weight = <T as Config>::WeightInfo::register_snp_enclave();
+
+			// SEV-SNP reports carry no timestamp, the registration time takes its place.
+			let now: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
+			let report =
+				snp_verify::verify_snp_report(&report, &vcek, &ask, &ark, now, Self::snp_min_tcb())
+					.map_err(|e| {
+						log::info!(target: TEEREX, "verify_snp_report failed: {:?}", e);
+						Error::<T>::from(e)
+					})?;
+			log::debug!(target: TEEREX, "SEV-SNP report successfully verified");
+
+			ensure!(
+				Ok(sender.clone()) == T::AccountId::decode(&mut &report.report_data[..32]),
+				Error::<T>::SenderIsNotAttestedEnclave
+			);
+
+			let enclave = SnpEnclave::new(
+				report.report_data,
+				report.measurement,
+				report.id_key_digest,
+				report.guest_svn,
+				report.policy,
+				report.reported_tcb,
+				now,
+			);
+			let fingerprint = enclave.fingerprint();
+			// A guest which can be debugged by the hypervisor is no better than an SGX debug enclave.
+			if report.debug_allowed &&
+				!Self::effective_security_flags(&fingerprint).sgx_allow_debug_mode
+			{
+				log::info!(target: TEEREX, "debug mode is not allowed to attest!");
+				return Err(Error::<T>::SgxModeIsNotAllowed.into())
+			}
+
+			let enclave = match worker_url {
+				Some(ref url) => enclave.with_url(url.clone()),
+				None => enclave,
+			};
+
+			Self::add_enclave(&sender, MultiEnclave::from(enclave))?;
+
+			log::info!(target: TEEREX, "registered snp enclave. sender: {:?}", sender);
+			Self::deposit_event(Event::AddedSnpEnclave {
+				registered_by: sender,
+				worker_url,
+				fingerprint,
+				reported_tcb: report.reported_tcb,
+			});
+			Ok(Some(verification_weight).into())
+		}
+
+		/// Set the DER encoded AMD root key (ARK) certificate of the product line SEV-SNP enclaves
+		/// run on. It must be self-signed and currently valid.
+		#[pallet::call_index(11)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_snp_root_certificate(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_snp_root_certificate(
+			origin: OriginFor<T>,
+			certificate: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call set_snp_root_certificate()");
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			ensure!(certificate.len() <= SNP_PROOF_MAX_LEN, Error::<T>::RaProofIsTooLong);
+			let now: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
+			snp_verify::verify_root_certificate(&certificate, now).map_err(Error::<T>::from)?;
+			<SnpRootCertificate<T>>::put(certificate);
+			log::info!(target: TEEREX, "set snp root certificate");
+			Self::deposit_event(Event::UpdatedSnpRootCertificate);
+			Ok(().into())
+		}
+
+		/// Set a floor for the TCB version of SEV-SNP enclaves. Passing `None` removes it.
+		/// Registered enclaves are not affected until they re-register.
+		#[pallet::call_index(12)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_snp_min_tcb(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_snp_min_tcb(
+			origin: OriginFor<T>,
+			min_tcb: Option<SnpTcbVersion>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call set_snp_min_tcb()");
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			<SnpMinTcb<T>>::set(min_tcb);
+			log::info!(target: TEEREX, "set snp min tcb to {:?}", min_tcb);
+			Self::deposit_event(Event::UpdatedSnpMinTcb { min_tcb });
+			Ok(().into())
+		}
+
+		/// Register an enclave attested by an Entity Attestation Token of `profile`, which has
+		/// been signed by the trusted key `key_id`. The nonce claim must be the sender's public key.
+		///
+		/// The actual weight is returned, so that early rejections are refunded.
+		#[pallet::call_index(13)]
+		#[pallet::weight((<T as Config>::WeightInfo::register_eat_enclave(), DispatchClass::Normal, Pays::Yes))]
+		pub fn register_eat_enclave(
+			origin: OriginFor<T>,
+			profile: EatProfile,
+			key_id: EatKeyId,
+			token: Vec<u8>,
+			worker_url: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "called into runtime call register_eat_enclave()");
+			let sender = ensure_signed(origin)?;
+			// Skipping SGX attestation doesn't verify anything either, it is an upper bound for
+			// everything that happens before the token is verified.
+			let early_rejection_weight = <T as Config>::WeightInfo::register_sgx_enclave_skip();
+			ensure!(
+				token.len() <= EAT_TOKEN_MAX_LEN,
+				Error::<T>::RaProofIsTooLong.with_weight(early_rejection_weight)
+			);
+			if let Some(ref url) = worker_url {
+				ensure!(
+					url.len() <= MAX_URL_LEN,
+					Error::<T>::EnclaveUrlIsTooLong.with_weight(early_rejection_weight)
+				);
+			}
+			let trusted_key = Self::eat_trusted_key(&profile, key_id)
+				.ok_or(Error::<T>::EatTrustedKeyNotFound.with_weight(early_rejection_weight))?;
+			let verification_weight = <T as Config>::WeightInfo::register_eat_enclave();
+
+			let verified = eat_verify::verify_eat_token(
+				&token,
+				trusted_key.algorithm,
+				&trusted_key.public_key,
+				&profile,
+				trusted_key.measurement_claim,
+			)
+			.map_err(|e| {
+				log::info!(target: TEEREX, "verify_eat_token failed: {:?}", e);
+				Error::<T>::from(e)
+			})?;
+			log::debug!(target: TEEREX, "EAT successfully verified");
+
+			ensure!(verified.nonce == sender.encode(), Error::<T>::SenderIsNotAttestedEnclave);
+			let nonce: [u8; 32] =
+				verified.nonce.try_into().map_err(|_| Error::<T>::SenderIsNotAttestedEnclave)?;
+			let measurement = EatMeasurement::try_from(verified.measurement)
+				.map_err(|_| Error::<T>::from(eat_verify::Error::MeasurementClaimIsInvalid))?;
+			// The `iat` claim is optional, without it the token is as fresh as the registration.
+			let timestamp = match verified.issued_at {
+				Some(iat) => iat.saturating_mul(1000),
+				None => <pallet_timestamp::Pallet<T>>::get().saturated_into(),
+			};
+			Self::ensure_attestation_is_fresh(timestamp, AttestationMethod::Eat)?;
+
+			let enclave = EatEnclave::new(profile.clone(), key_id, measurement, nonce, timestamp);
+			let fingerprint = enclave.fingerprint();
+			let enclave = match worker_url {
+				Some(ref url) => enclave.with_url(url.clone()),
+				None => enclave,
+			};
+
+			Self::add_enclave(&sender, MultiEnclave::from(enclave))?;
+
+			log::info!(target: TEEREX, "registered eat enclave. sender: {:?}", sender);
+			Self::deposit_event(Event::AddedEatEnclave {
+				registered_by: sender,
+				worker_url,
+				profile,
+				fingerprint,
+			});
+			Ok(Some(verification_weight).into())
+		}
+
+		/// Trust `key` to sign attestation tokens of `profile`. Adding a key with the same public
+		/// key replaces it.
+		#[pallet::call_index(14)]
+		#[pallet::weight((<T as Config>::WeightInfo::add_eat_trusted_key(), DispatchClass::Normal, Pays::Yes))]
+		pub fn add_eat_trusted_key(
+			origin: OriginFor<T>,
+			profile: EatProfile,
+			key: EatTrustedKey,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call add_eat_trusted_key()");
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			let key_id = key.key_id();
+			<EatTrustedKeys<T>>::insert(&profile, key_id, key);
+			log::info!(target: TEEREX, "added eat trusted key {:?}", key_id);
+			Self::deposit_event(Event::AddedEatTrustedKey { profile, key_id });
+			Ok(().into())
+		}
+
+		/// Stop trusting a key. Enclaves it attested stay registered until their renewal is
+		/// overdue.
+		#[pallet::call_index(15)]
+		#[pallet::weight((<T as Config>::WeightInfo::remove_eat_trusted_key(), DispatchClass::Normal, Pays::Yes))]
+		pub fn remove_eat_trusted_key(
+			origin: OriginFor<T>,
+			profile: EatProfile,
+			key_id: EatKeyId,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call remove_eat_trusted_key()");
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			ensure!(
+				<EatTrustedKeys<T>>::contains_key(&profile, key_id),
+				Error::<T>::EatTrustedKeyNotFound
+			);
+			<EatTrustedKeys<T>>::remove(&profile, key_id);
+			log::info!(target: TEEREX, "removed eat trusted key {:?}", key_id);
+			Self::deposit_event(Event::RemovedEatTrustedKey { profile, key_id });
+			Ok(().into())
+		}
+
+		/// Restrict the platforms SGX enclaves may register from with DCAP. Enclaves which are
+		/// registered already are only affected when they re-register.
+		#[pallet::call_index(16)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_allowed_sgx_platforms(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_allowed_sgx_platforms(
+			origin: OriginFor<T>,
+			policy: SgxPlatformPolicy,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(target: TEEREX, "Called into runtime call set_allowed_sgx_platforms()");
+			T::AttestationPolicyOrigin::ensure_origin(origin)?;
+			<AllowedSgxPlatforms<T>>::put(policy);
+			log::info!(target: TEEREX, "set allowed sgx platforms to {:?}", policy);
+			Self::deposit_event(Event::UpdatedAllowedSgxPlatforms { policy });
+			Ok(().into())
+		}
+
+		/// Set the `tcbStatus` values of Intel's QE identity, besides `UpToDate`, under which
+		/// quoting enclaves are trusted. The default only trusts `UpToDate` levels. Takes effect
+		/// with the next `register_quoting_enclave`.
+		#[pallet::call_index(17)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_quoting_enclave_tcb_status_policy(), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_quoting_enclave_tcb_status_policy(
+			origin: OriginFor<T>,
+			policy: QeTcbStatusPolicy,
+		) -> DispatchResultWithPostInfo {
+			log::debug!(
+				target: TEEREX,
+				"Called into runtime call set_quoting_enclave_tcb_status_policy()"
+			);
+			T::TrustAnchorOrigin::ensure_origin(origin)?;
+			<QuotingEnclaveTcbStatusPolicy<T>>::put(policy);
+			log::info!(target: TEEREX, "set quoting enclave tcb status policy to {:?}", policy);
+			Self::deposit_event(Event::UpdatedQuotingEnclaveTcbStatusPolicy { policy });
+			Ok(().into())
+		}
 	}
 }
 
@@ -588,22 +1064,21 @@ impl<T: Config> Pallet<T> {
 		certificate_chain: Vec<u8>,
 	) -> Result<SgxQuotingEnclave, DispatchErrorWithPostInfo> {
 		let verification_time: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
-		let certs = extract_certs(&certificate_chain);
-		ensure!(certs.len() >= 2, Error::<T>::CertificateChainIsTooShort);
-		let intermediate_slices: Vec<webpki::types::CertificateDer> =
-			certs[1..].iter().map(|c| c.as_slice().into()).collect();
-		let leaf_cert_der = webpki::types::CertificateDer::from(certs[0].as_slice());
+		let chain = CertificateChain::decode(&certificate_chain).map_err(Error::<T>::from)?;
+		let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
 		let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
-			.map_err(|_| Error::<T>::LeafCertificateParsingError)?;
-		verify_certificate_chain(&leaf_cert, &intermediate_slices, verification_time)
+			.map_err(|_| Error::<T>::from(sgx_verify::Error::LeafCertificateParsingError))?;
+		verify_certificate_chain(&leaf_cert, &chain.intermediates(), verification_time)
 			.map_err(Error::<T>::from)?;
 
 		let enclave_identity =
 			deserialize_enclave_identity(&enclave_identity, &signature, &leaf_cert)
 				.map_err(Error::<T>::from)?;
 
-		if enclave_identity.is_valid(verification_time.try_into().unwrap()) {
-			Ok(enclave_identity.to_quoting_enclave())
+		let statuses = Self::quoting_enclave_tcb_status_policy();
+		let min_isvsvn = Self::quoting_enclave_min_isvsvn();
+		if enclave_identity.is_valid(verification_time.try_into().unwrap(), statuses, min_isvsvn) {
+			Ok(enclave_identity.to_quoting_enclave(statuses, min_isvsvn))
 		} else {
 			Err(Error::<T>::CollateralIsInvalid.into())
 		}
@@ -615,42 +1090,84 @@ impl<T: Config> Pallet<T> {
 		certificate_chain: Vec<u8>,
 	) -> Result<(Fmspc, SgxTcbInfoOnChain), DispatchErrorWithPostInfo> {
 		let verification_time: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
-		let certs = extract_certs(&certificate_chain);
-		ensure!(certs.len() >= 2, Error::<T>::CertificateChainIsTooShort);
+		let chain = CertificateChain::decode(&certificate_chain).map_err(Error::<T>::from)?;
 		log::trace!(target: TEEREX, "Self::verify_tcb_info, certs len is >= 2.");
-		let intermediate_slices: Vec<webpki::types::CertificateDer> =
-			certs[1..].iter().map(|c| c.as_slice().into()).collect();
-		let leaf_cert_der = webpki::types::CertificateDer::from(certs[0].as_slice());
+		let leaf_cert_der = webpki::types::CertificateDer::from(chain.leaf());
 		let leaf_cert = webpki::EndEntityCert::try_from(&leaf_cert_der)
-			.map_err(|_| Error::<T>::LeafCertificateParsingError)?;
-		verify_certificate_chain(&leaf_cert, &intermediate_slices, verification_time)
+			.map_err(|_| Error::<T>::from(sgx_verify::Error::LeafCertificateParsingError))?;
+		verify_certificate_chain(&leaf_cert, &chain.intermediates(), verification_time)
 			.map_err(Error::<T>::from)?;
 		let tcb_info =
 			deserialize_tcb_info(&tcb_info, &signature, &leaf_cert).map_err(Error::<T>::from)?;
 		log::trace!(target: TEEREX, "Self::deserialize_tcb_info succeded.");
-		if tcb_info.is_valid(verification_time.try_into().unwrap()) {
-			Ok(tcb_info.to_chain_tcb_info())
-		} else {
-			Err(Error::<T>::CollateralIsInvalid.into())
-		}
+		ensure!(
+			tcb_info.is_valid(verification_time.try_into().unwrap()),
+			Error::<T>::CollateralIsInvalid
+		);
+		let (fmspc, on_chain_info) = tcb_info.to_chain_tcb_info();
+		// `verify_examinee` relies on the levels being ordered like Intel lists them
+		ensure!(on_chain_info.tcb_levels_are_ordered(), Error::<T>::TcbLevelsAreNotOrdered);
+		ensure!(
+			on_chain_info.tcb_levels().len() <= MAX_TCB_LEVELS as usize,
+			Error::<T>::TooManyTcbLevels
+		);
+		Ok((fmspc, on_chain_info))
 	}
 
-	fn ensure_timestamp_within_24_hours(report_timestamp: u64) -> DispatchResultWithPostInfo {
-		use sp_runtime::traits::CheckedSub;
+	/// The security flags that apply to enclaves with `fingerprint`: the override for this
+	/// fingerprint if there is one, the global flags otherwise.
+	pub fn effective_security_flags(fingerprint: &EnclaveFingerprint) -> SecurityFlags {
+		Self::security_flags_override(fingerprint).unwrap_or_else(|| {
+			SecurityFlags::new(Self::allow_skipping_attestation(), Self::allow_sgx_debug_mode())
+		})
+	}
+
+	/// The freshness policy for `attestation_method`. Falls back to `MomentsPerDay` and
+	/// `MaxAttestationRenewalPeriod` if governance has not set an override.
+	pub fn attestation_freshness_policy(
+		attestation_method: AttestationMethod,
+	) -> AttestationFreshnessPolicy {
+		Self::attestation_freshness_policy_override(attestation_method).unwrap_or_else(|| {
+			AttestationFreshnessPolicy::new(
+				T::MomentsPerDay::get().saturated_into(),
+				T::MaxAttestationRenewalPeriod::get().saturated_into(),
+			)
+		})
+	}
 
-		let elapsed_time = <pallet_timestamp::Pallet<T>>::get()
-			.checked_sub(&T::Moment::saturated_from(report_timestamp))
+	fn ensure_attestation_is_fresh(
+		attestation_timestamp: u64,
+		attestation_method: AttestationMethod,
+	) -> DispatchResultWithPostInfo {
+		let now: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
+		let elapsed_time = now
+			.checked_sub(attestation_timestamp)
 			.ok_or("Underflow while calculating elapsed time since report creation")?;
 
-		if elapsed_time < T::MomentsPerDay::get() {
+		if elapsed_time < Self::attestation_freshness_policy(attestation_method).max_attestation_age
+		{
 			Ok(().into())
 		} else {
 			Err(Error::<T>::RemoteAttestationIsTooOld.into())
 		}
 	}
+
+	fn ensure_attestation_renewal_is_overdue(
+		enclave: &MultiEnclave<Vec<u8>>,
+	) -> DispatchResultWithPostInfo {
+		let now: u64 = <pallet_timestamp::Pallet<T>>::get().saturated_into();
+		let oldest_acceptable_attestation_time = now.saturating_sub(
+			Self::attestation_freshness_policy(enclave.attestation_method()).renewal_period,
+		);
+		ensure!(
+			enclave.attestation_timestamp() < oldest_acceptable_attestation_time,
+			Error::<T>::UnregisterActiveEnclaveIsNotAllowed
+		);
+		Ok(().into())
+	}
 }
 
-#[cfg(any(test, feature = "runtime-benchmarks"))]
+#[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 #[cfg(test)]
 mod mock;
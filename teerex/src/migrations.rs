@@ -131,14 +131,61 @@ pub mod v2 {
 	/// The log target.
 	const TARGET: &str = "teerex::migration::v2";
 
+	/// `SgxEnclave` up to v2, which did not record the platform
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+	pub struct SgxEnclaveV2<Url> {
+		pub report_data: SgxReportData,
+		pub mr_enclave: MrEnclave,
+		pub mr_signer: MrSigner,
+		pub timestamp: u64,   // unix epoch in milliseconds
+		pub url: Option<Url>, // utf8 encoded url
+		pub build_mode: SgxBuildMode,
+		pub attestation_method: SgxAttestationMethod,
+		pub status: SgxStatus,
+	}
+
+	impl<Url> From<SgxEnclaveV2<Url>> for SgxEnclave<Url> {
+		/// The platform of enclaves registered before v3 is unknown.
+		fn from(enclave: SgxEnclaveV2<Url>) -> Self {
+			SgxEnclave {
+				report_data: enclave.report_data,
+				mr_enclave: enclave.mr_enclave,
+				mr_signer: enclave.mr_signer,
+				timestamp: enclave.timestamp,
+				url: enclave.url,
+				build_mode: enclave.build_mode,
+				attestation_method: enclave.attestation_method,
+				status: enclave.status,
+				platform: None,
+			}
+		}
+	}
+
+	/// `MultiEnclave` up to v2. Only the encoding of SGX enclaves changed since.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_core::RuntimeDebug, TypeInfo)]
+	pub enum MultiEnclaveV2<Url> {
+		Sgx(SgxEnclaveV2<Url>),
+		Snp(SnpEnclave<Url>),
+		Eat(EatEnclave<Url>),
+	}
+
+	impl<Url> From<MultiEnclaveV2<Url>> for MultiEnclave<Url> {
+		fn from(enclave: MultiEnclaveV2<Url>) -> Self {
+			match enclave {
+				MultiEnclaveV2::Sgx(enclave) => MultiEnclave::Sgx(enclave.into()),
+				MultiEnclaveV2::Snp(enclave) => MultiEnclave::Snp(enclave),
+				MultiEnclaveV2::Eat(enclave) => MultiEnclave::Eat(enclave),
+			}
+		}
+	}
+
 	pub struct MigrateV1toV2<T>(sp_std::marker::PhantomData<T>);
 
 	impl<T: Config + frame_system::Config> OnRuntimeUpgrade for MigrateV1toV2<T> {
 		#[cfg(feature = "try-runtime")]
 		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
-			let current_version = Pallet::<T>::in_code_storage_version();
 			let onchain_version = Pallet::<T>::on_chain_storage_version();
-			ensure!(onchain_version == 1 && current_version == 2, "only migration from v1 to v2");
+			ensure!(onchain_version == 1, "only migration from v1 to v2");
 
 			let tcb_info_count = v1::SgxTcbInfo::<T>::iter_keys().count() as u64;
 			log::info!(
@@ -151,7 +198,7 @@ pub mod v2 {
 
 		/// we simply purge the enclave registry as it renews within 24h anyway
 		fn on_runtime_upgrade() -> Weight {
-			let current_version = Pallet::<T>::in_code_storage_version();
+			let current_version = StorageVersion::new(2);
 			let onchain_version = Pallet::<T>::on_chain_storage_version();
 
 			log::info!(
@@ -190,6 +237,89 @@ pub mod v2 {
 	}
 }
 
+pub mod v3 {
+	use super::*;
+	/// The log target.
+	const TARGET: &str = "teerex::migration::v3";
+
+	/// SGX enclaves record the platform they run on since v3. Registered SGX enclaves are
+	/// translated with an unknown platform, which is recorded when they re-register. SEV-SNP and
+	/// EAT enclaves are left untouched.
+	pub struct MigrateV2toV3<T>(sp_std::marker::PhantomData<T>);
+
+	impl<T: Config + frame_system::Config> OnRuntimeUpgrade for MigrateV2toV3<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			let current_version = Pallet::<T>::in_code_storage_version();
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+			ensure!(onchain_version == 2 && current_version == 3, "only migration from v2 to v3");
+
+			let sovereign_count = crate::SovereignEnclaves::<T>::iter_keys().count() as u64;
+			let proxied_count = crate::ProxiedEnclaves::<T>::iter_keys().count() as u64;
+			log::info!(
+				target: TARGET,
+				"teerexV3: {} sovereign and {} proxied enclaves will be migrated",
+				sovereign_count,
+				proxied_count
+			);
+			Ok((sovereign_count, proxied_count).encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let current_version = StorageVersion::new(3);
+			let onchain_version = Pallet::<T>::on_chain_storage_version();
+
+			log::info!(
+				target: TARGET,
+				"teerexV3: Running migration with current storage version {:?} / onchain {:?}",
+				current_version,
+				onchain_version
+			);
+
+			let mut translated_keys = 0u64;
+			if onchain_version >= current_version {
+				log::warn!(
+					target: TARGET,
+					"teerexV3: skipping on_runtime_upgrade: executed on wrong storage version."
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			crate::SovereignEnclaves::<T>::translate::<MultiEnclaveV2<Vec<u8>>, _>(|_, enclave| {
+				translated_keys += 1;
+				Some(enclave.into())
+			});
+			crate::ProxiedEnclaves::<T>::translate::<MultiEnclaveV2<Vec<u8>>, _>(|_, enclave| {
+				translated_keys += 1;
+				Some(enclave.into())
+			});
+			log::info!(target: TARGET, "teerexV3: translated {} enclaves", translated_keys);
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(translated_keys + 1, translated_keys + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 3, "must upgrade");
+
+			let (sovereign_count, proxied_count): (u64, u64) =
+				Decode::decode(&mut &state[..]).expect("pre_upgrade provides a valid state; qed");
+			assert_eq!(
+				crate::SovereignEnclaves::<T>::iter_values().count() as u64,
+				sovereign_count,
+				"must keep all sovereign enclaves"
+			);
+			assert_eq!(
+				crate::ProxiedEnclaves::<T>::iter_values().count() as u64,
+				proxied_count,
+				"must keep all proxied enclaves"
+			);
+			Ok(())
+		}
+	}
+}
+
 #[cfg(test)]
 #[cfg(feature = "try-runtime")]
 mod test {
@@ -257,6 +387,7 @@ mod test {
 			assert_eq!(v1::SgxTcbInfo::<TestRuntime>::iter_keys().count(), 0);
 		});
 	}
+
 	#[allow(deprecated)]
 	#[test]
 	fn migration_v1_to_v1_is_noop() {
@@ -286,3 +417,74 @@ mod test {
 		});
 	}
 }
+
+#[cfg(test)]
+mod test_v3 {
+	use super::*;
+	use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade};
+	use mock::{new_test_ext, Test as TestRuntime};
+
+	#[allow(deprecated)]
+	#[test]
+	fn migration_v2_to_v3_keeps_enclaves() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(2).put::<Pallet<TestRuntime>>();
+
+			// SGX enclaves registered with v2 lack the platform
+			let sgx_v2 = v2::SgxEnclaveV2::<Vec<u8>> {
+				mr_enclave: [1u8; 32],
+				timestamp: 1,
+				url: Some(b"wss://example.com".to_vec()),
+				attestation_method: SgxAttestationMethod::Dcap { proxied: false },
+				status: SgxStatus::Ok,
+				..Default::default()
+			};
+			let sgx_signer = AccountId::<TestRuntime>::from([0u8; 32]);
+			unhashed::put(
+				&crate::SovereignEnclaves::<TestRuntime>::hashed_key_for(&sgx_signer),
+				&v2::MultiEnclaveV2::Sgx(sgx_v2.clone()),
+			);
+			let address = EnclaveInstanceAddress {
+				fingerprint: EnclaveFingerprint::from([1u8; 32]),
+				registrar: sgx_signer.clone(),
+				signer: AnySigner::default(),
+			};
+			unhashed::put(
+				&crate::ProxiedEnclaves::<TestRuntime>::hashed_key_for(&address),
+				&v2::MultiEnclaveV2::Sgx(sgx_v2.clone()),
+			);
+			// the old encoding is too short for the v3 type
+			assert_eq!(crate::SovereignEnclaves::<TestRuntime>::get(&sgx_signer), None);
+
+			// the encoding of SEV-SNP enclaves did not change
+			let snp = MultiEnclave::Snp(SnpEnclave::<Vec<u8>> {
+				report_data: [2u8; 64],
+				measurement: [3u8; 48],
+				id_key_digest: [4u8; 48],
+				guest_svn: 1,
+				policy: 0,
+				reported_tcb: SnpTcbVersion::default(),
+				timestamp: 1,
+				url: None,
+			});
+			let snp_signer = AccountId::<TestRuntime>::from([2u8; 32]);
+			crate::SovereignEnclaves::<TestRuntime>::insert(&snp_signer, &snp);
+
+			#[cfg(feature = "try-runtime")]
+			let state = v3::MigrateV2toV3::<TestRuntime>::pre_upgrade().unwrap();
+			let _weight = v3::MigrateV2toV3::<TestRuntime>::on_runtime_upgrade();
+			#[cfg(feature = "try-runtime")]
+			v3::MigrateV2toV3::<TestRuntime>::post_upgrade(state).unwrap();
+
+			let sgx_v3 = MultiEnclave::Sgx(SgxEnclave::from(sgx_v2));
+			assert!(matches!(&sgx_v3, MultiEnclave::Sgx(enclave) if enclave.platform.is_none()));
+			assert_eq!(
+				crate::SovereignEnclaves::<TestRuntime>::get(&sgx_signer),
+				Some(sgx_v3.clone())
+			);
+			assert_eq!(crate::ProxiedEnclaves::<TestRuntime>::get(&address), Some(sgx_v3));
+			assert_eq!(crate::SovereignEnclaves::<TestRuntime>::get(&snp_signer), Some(snp));
+			assert_eq!(Pallet::<TestRuntime>::on_chain_storage_version(), 3);
+		});
+	}
+}
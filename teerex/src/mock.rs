@@ -17,7 +17,7 @@
 
 // Creating mock runtime here
 use crate as pallet_teerex;
-use frame_support::{derive_impl, parameter_types};
+use frame_support::{derive_impl, parameter_types, traits::EnsureOrigin};
 use frame_system as system;
 use pallet_teerex::Config;
 use sp_core::H256;
@@ -125,6 +125,33 @@ impl pallet_timestamp::Config for Test {
 parameter_types! {
 	pub const MomentsPerDay: u64 = 86_400_000; // [ms/d]
 	pub const MaxAttestationRenewalPeriod: u64 = 172_800_000; // 48h
+	/// Restricts collateral registration to root, like a runtime with a technical committee.
+	pub static RestrictCollateralOrigin: bool = false;
+}
+
+/// `EnsureSigned`, or `EnsureRoot` if `RestrictCollateralOrigin` is set. Allows testing both
+/// wirings of `CollateralOrigin` with one mock runtime.
+pub struct EnsureCollateralOrigin;
+
+impl EnsureOrigin<RuntimeOrigin> for EnsureCollateralOrigin {
+	type Success = ();
+
+	fn try_origin(o: RuntimeOrigin) -> Result<Self::Success, RuntimeOrigin> {
+		if RestrictCollateralOrigin::get() {
+			frame_system::EnsureRoot::<AccountId>::try_origin(o)
+		} else {
+			frame_system::EnsureSigned::<AccountId>::try_origin(o).map(|_| ())
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		if RestrictCollateralOrigin::get() {
+			Ok(RuntimeOrigin::root())
+		} else {
+			Ok(RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id()))
+		}
+	}
 }
 
 impl Config for Test {
@@ -132,6 +159,10 @@ impl Config for Test {
 	type MomentsPerDay = MomentsPerDay;
 	type MaxAttestationRenewalPeriod = MaxAttestationRenewalPeriod;
 	type WeightInfo = ();
+	type SecurityFlagsOrigin = frame_system::EnsureRoot<AccountId>;
+	type CollateralOrigin = EnsureCollateralOrigin;
+	type TrustAnchorOrigin = frame_system::EnsureRoot<AccountId>;
+	type AttestationPolicyOrigin = frame_system::EnsureRoot<AccountId>;
 }
 
 // This function basically just builds a genesis storage key/value store according to
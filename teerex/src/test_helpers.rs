@@ -17,17 +17,22 @@
 
 use crate::{Config, Pallet, SgxTcbInfo};
 use frame_support::assert_ok;
-use frame_system::RawOrigin;
 use sgx_verify::test_data::dcap::{
 	QE_IDENTITY_ISSUER_CHAIN, QUOTING_ENCLAVE, QUOTING_ENCLAVE_SIGNATURE, TCB_INFO,
 	TCB_INFO_CERTIFICATE_CHAIN, TCB_INFO_FMSPC, TCB_INFO_SIGNATURE,
 };
-use teerex_primitives::SgxTcbInfoOnChain;
+use sp_std::prelude::*;
+use teerex_primitives::{SgxTcbInfoOnChain, TcbStatus, TcbVersionStatus};
+
+/// Offsets of the signature data length and the certification data size in a DCAP quote with
+/// 32 bytes of QE authentication data.
+const DCAP_SIGNATURE_DATA_LEN_OFFSET: usize = 432;
+const DCAP_CERTIFICATION_DATA_SIZE_OFFSET: usize = 1048;
 
 /// Registers a predefined quoting enclave.
 ///
-/// This can be done by any account.
-pub fn register_test_quoting_enclave<T>(account: T::AccountId)
+/// `origin` must satisfy `T::CollateralOrigin`.
+pub fn register_test_quoting_enclave<T>(origin: T::RuntimeOrigin)
 where
 	T: Config,
 	<T as frame_system::Config>::Hash: From<[u8; 32]>,
@@ -37,7 +42,7 @@ where
 	let certificate_chain = QE_IDENTITY_ISSUER_CHAIN;
 
 	assert_ok!(Pallet::<T>::register_quoting_enclave(
-		RawOrigin::Signed(account).into(),
+		origin,
 		quoting_enclave.to_vec(),
 		signature.to_vec(),
 		certificate_chain.to_vec(),
@@ -46,8 +51,8 @@ where
 
 /// Registers a predefined TCB-Info.
 ///
-/// This can be done by any account.
-pub fn register_test_tcb_info<T>(account: T::AccountId)
+/// `origin` must satisfy `T::CollateralOrigin`.
+pub fn register_test_tcb_info<T>(origin: T::RuntimeOrigin)
 where
 	T: Config,
 	<T as frame_system::Config>::Hash: From<[u8; 32]>,
@@ -57,7 +62,7 @@ where
 	let certificate_chain = TCB_INFO_CERTIFICATE_CHAIN;
 
 	assert_ok!(Pallet::<T>::register_tcb_info(
-		RawOrigin::Signed(account).into(),
+		origin,
 		tcb_info.to_vec(),
 		signature.to_vec(),
 		certificate_chain.to_vec(),
@@ -72,3 +77,42 @@ where
 {
 	SgxTcbInfo::<T>::get(TCB_INFO_FMSPC).unwrap()
 }
+
+/// Appends newlines to the PEM certificate chain at the end of `quote`, until it is `len` bytes
+/// long. The chain is still valid, but it takes as long to parse as a longer chain.
+pub fn pad_dcap_quote(quote: &[u8], len: usize) -> Vec<u8> {
+	let padding = len.checked_sub(quote.len()).expect("the quote is not longer than `len`");
+	let mut padded = quote.to_vec();
+	padded.resize(len, b'\n');
+	for offset in [DCAP_SIGNATURE_DATA_LEN_OFFSET, DCAP_CERTIFICATION_DATA_SIZE_OFFSET] {
+		let field = &mut padded[offset..offset + 4];
+		let value = u32::from_le_bytes(field.try_into().unwrap()) + padding as u32;
+		field.copy_from_slice(&value.to_le_bytes());
+	}
+	padded
+}
+
+/// Appends newlines to a PEM certificate chain, which are ignored when it is parsed.
+pub fn pad_certificate_chain(chain: &[u8], len: usize) -> Vec<u8> {
+	let mut padded = chain.to_vec();
+	padded.resize(len.max(chain.len()), b'\n');
+	padded
+}
+
+/// Replaces the TCB levels of the test TCB info with `count` levels, of which only the last one
+/// can be satisfied. This is the worst case for `verify_examinee`.
+pub fn insert_test_tcb_levels<T>(count: u32)
+where
+	T: Config,
+	<T as frame_system::Config>::Hash: From<[u8; 32]>,
+{
+	let tcb_info = get_test_tcb_info::<T>();
+	let mut levels: Vec<TcbVersionStatus> = (1..count)
+		.map(|i| TcbVersionStatus::new([u8::MAX; 16], u16::MAX - i as u16, TcbStatus::UpToDate))
+		.collect();
+	levels.push(TcbVersionStatus::new([0; 16], 0, TcbStatus::UpToDate));
+	SgxTcbInfo::<T>::insert(
+		TCB_INFO_FMSPC,
+		SgxTcbInfoOnChain::new(tcb_info.issue_date, tcb_info.next_update, levels),
+	);
+}
@@ -17,28 +17,42 @@
 
 use crate::{
 	mock::*,
-	test_helpers::{register_test_quoting_enclave, register_test_tcb_info},
-	AllowSkippingAttestation, Error, Event as TeerexEvent, ProxiedEnclaves, SgxAllowDebugMode,
-	SgxEnclave, SovereignEnclaves,
+	test_helpers::{
+		get_test_tcb_info, pad_dcap_quote, register_test_quoting_enclave, register_test_tcb_info,
+	},
+	weights::WeightInfo,
+	AllowSkippingAttestation, AllowedSgxPlatforms, AttestationFreshnessPolicies, EatTrustedKeys,
+	Error, Event as TeerexEvent, ProxiedEnclaves, QuotingEnclaveMinIsvsvn, SecurityFlagsOverrides,
+	SgxAllowDebugMode, SgxEnclave, SnpMinTcb, SnpRootCertificate, SovereignEnclaves,
 };
-use frame_support::{assert_err, assert_ok};
+use frame_support::{assert_err, assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo};
 use hex_literal::hex;
 use sgx_verify::{
 	collateral::EnclaveIdentity,
 	test_data::dcap::{
-		QUOTING_ENCLAVE, TEST1_DCAP_QUOTE_MRENCLAVE, TEST1_DCAP_QUOTE_SIGNER, TEST2_DCAP_QUOTE_HEX,
+		QE_IDENTITY_ISSUER_CHAIN, QUOTING_ENCLAVE, QUOTING_ENCLAVE_SIGNATURE, TCB_INFO,
+		TCB_INFO_CERTIFICATE_CHAIN, TCB_INFO_SIGNATURE, TEST1_DCAP_QUOTE_MRENCLAVE,
+		TEST1_DCAP_QUOTE_SIGNER, TEST2_DCAP_QUOTE_HEX,
 	},
 	verify_dcap_quote,
 };
 use sp_keyring::AccountKeyring;
+use sp_runtime::{traits::BadOrigin, MultiSigner};
 
 use teerex_primitives::{
-	AnySigner, EnclaveInstanceAddress, MultiEnclave, SgxAttestationMethod, SgxBuildMode,
-	SgxReportData, SgxStatus, SgxTcbInfoOnChain, TcbStatus, TcbVersionStatus,
+	AnySigner, AttestationFreshnessPolicy, AttestationMethod, EatProfile, EatSignatureAlgorithm,
+	EatTrustedKey, EnclaveFingerprint, EnclaveInstanceAddress, MultiEnclave, QeTcb,
+	QeTcbStatusPolicy, SecurityFlags, SgxAttestationMethod, SgxBuildMode, SgxPlatformPolicy,
+	SgxReportData, SgxStatus, SgxTcbInfoOnChain, SgxType, SnpTcbVersion, TcbStatus,
+	TcbVersionStatus,
 };
-use test_utils::test_data::{
-	consts::*,
-	dcap::{TEST1_DCAP_QUOTE, TEST_VALID_COLLATERAL_TIMESTAMP},
+use test_utils::{
+	eat_test_data::*,
+	snp_test_data::*,
+	test_data::{
+		consts::*,
+		dcap::{TEST1_DCAP_QUOTE, TEST_VALID_COLLATERAL_TIMESTAMP},
+	},
 };
 
 fn list_sovereign_enclaves() -> Vec<(AccountId, MultiEnclave<Vec<u8>>)> {
@@ -55,6 +69,11 @@ fn get_signer(pubkey: &[u8; 32]) -> AccountId {
 	test_utils::get_signer(pubkey)
 }
 
+// collateral registration is permissionless unless a test restricts it
+fn collateral_origin() -> RuntimeOrigin {
+	RuntimeOrigin::signed(AccountKeyring::Alice.to_account_id())
+}
+
 #[test]
 fn set_security_flags_works() {
 	new_test_ext().execute_with(|| {
@@ -77,14 +96,259 @@ fn set_security_flags_as_non_root_fails() {
 	})
 }
 
+#[test]
+fn set_and_clear_security_flags_override_works() {
+	new_test_ext().execute_with(|| {
+		let fingerprint = EnclaveFingerprint::from(TEST4_MRENCLAVE);
+		assert_ok!(Teerex::set_security_flags_override(
+			RuntimeOrigin::root(),
+			fingerprint,
+			false,
+			false
+		));
+		assert_eq!(
+			Teerex::security_flags_override(fingerprint),
+			Some(SecurityFlags::new(false, false))
+		);
+		assert_eq!(
+			Teerex::effective_security_flags(&fingerprint),
+			SecurityFlags::new(false, false)
+		);
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::UpdatedSecurityFlagsOverride {
+			fingerprint,
+			allow_skipping_attestation: false,
+			sgx_allow_debug_mode: false,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(Teerex::clear_security_flags_override(RuntimeOrigin::root(), fingerprint));
+		assert!(!<SecurityFlagsOverrides<Test>>::contains_key(fingerprint));
+		// the global flags of the test setup apply again
+		assert_eq!(Teerex::effective_security_flags(&fingerprint), SecurityFlags::new(true, true));
+		let expected_event =
+			RuntimeEvent::Teerex(TeerexEvent::ClearedSecurityFlagsOverride { fingerprint });
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn set_security_flags_override_as_non_root_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::set_security_flags_override(
+				RuntimeOrigin::signed(alice),
+				EnclaveFingerprint::from(TEST4_MRENCLAVE),
+				true,
+				true
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn clear_missing_security_flags_override_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Teerex::clear_security_flags_override(
+				RuntimeOrigin::root(),
+				EnclaveFingerprint::from(TEST4_MRENCLAVE)
+			),
+			Error::<Test>::SecurityFlagsOverrideNotFound
+		);
+	})
+}
+
+#[test]
+fn set_attestation_freshness_policy_works() {
+	new_test_ext().execute_with(|| {
+		let method = AttestationMethod::from(SgxAttestationMethod::Dcap { proxied: true });
+		let policy = AttestationFreshnessPolicy::new(1_000, 2_000);
+		assert_eq!(
+			Teerex::attestation_freshness_policy(method),
+			AttestationFreshnessPolicy::new(
+				<MomentsPerDay>::get(),
+				<MaxAttestationRenewalPeriod>::get()
+			)
+		);
+
+		assert_ok!(Teerex::set_attestation_freshness_policy(
+			RuntimeOrigin::root(),
+			method,
+			Some(policy)
+		));
+		assert_eq!(<AttestationFreshnessPolicies<Test>>::get(method), Some(policy));
+		assert_eq!(Teerex::attestation_freshness_policy(method), policy);
+		// the sovereign variant is not affected
+		assert_eq!(
+			Teerex::attestation_freshness_policy(
+				SgxAttestationMethod::Dcap { proxied: false }.into()
+			)
+			.renewal_period,
+			<MaxAttestationRenewalPeriod>::get()
+		);
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::UpdatedAttestationFreshnessPolicy {
+			attestation_method: method,
+			policy: Some(policy),
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(Teerex::set_attestation_freshness_policy(RuntimeOrigin::root(), method, None));
+		assert_eq!(<AttestationFreshnessPolicies<Test>>::get(method), None);
+	})
+}
+
+#[test]
+fn set_attestation_freshness_policy_as_non_root_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert!(Teerex::set_attestation_freshness_policy(
+			RuntimeOrigin::signed(alice),
+			SgxAttestationMethod::Ias.into(),
+			Some(AttestationFreshnessPolicy::new(1_000, 2_000))
+		)
+		.is_err());
+	})
+}
+
+#[test]
+fn set_attestation_freshness_policy_with_zero_age_fails() {
+	new_test_ext().execute_with(|| {
+		assert_err!(
+			Teerex::set_attestation_freshness_policy(
+				RuntimeOrigin::root(),
+				SgxAttestationMethod::Ias.into(),
+				Some(AttestationFreshnessPolicy::new(0, 2_000))
+			),
+			Error::<Test>::AttestationFreshnessPolicyIsInvalid
+		);
+	})
+}
+
+#[test]
+fn register_ias_enclave_respects_attestation_freshness_policy() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP + TWENTY_FOUR_HOURS + 1);
+		let signer = get_signer(TEST7_SIGNER_PUB);
+		assert_ok!(Teerex::set_attestation_freshness_policy(
+			RuntimeOrigin::root(),
+			SgxAttestationMethod::Ias.into(),
+			Some(AttestationFreshnessPolicy::new(
+				2 * TWENTY_FOUR_HOURS,
+				<MaxAttestationRenewalPeriod>::get()
+			))
+		));
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer),
+			TEST7_CERT.to_vec(),
+			Some(URL.to_vec()),
+			SgxAttestationMethod::Ias
+		));
+	})
+}
+
+#[test]
+fn attestation_freshness_policy_only_changes_renewal_period_of_skip_enclaves() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		let alice = AccountKeyring::Alice.to_account_id();
+		let renewal_period = 1_000;
+		assert_ok!(Teerex::set_attestation_freshness_policy(
+			RuntimeOrigin::root(),
+			SgxAttestationMethod::Skip { proxied: false }.into(),
+			Some(AttestationFreshnessPolicy::new(1, renewal_period))
+		));
+
+		// the enclave is stamped with the registration time, it is never too old
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(alice.clone()),
+			TEST1_DCAP_QUOTE.to_vec(),
+			Some(URL.to_vec()),
+			SgxAttestationMethod::Skip { proxied: false }
+		));
+		assert_eq!(
+			Teerex::sovereign_enclaves(&alice).unwrap().attestation_timestamp(),
+			TEST_VALID_COLLATERAL_TIMESTAMP
+		);
+
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP + renewal_period);
+		assert_err!(
+			Teerex::unregister_sovereign_enclave(
+				RuntimeOrigin::signed(alice.clone()),
+				alice.clone()
+			),
+			Error::<Test>::UnregisterActiveEnclaveIsNotAllowed
+		);
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP + renewal_period + 1);
+		assert_ok!(Teerex::unregister_sovereign_enclave(
+			RuntimeOrigin::signed(alice.clone()),
+			alice
+		));
+	})
+}
+
+#[test]
+fn proxied_and_sovereign_enclaves_have_separate_renewal_periods() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+
+		let alice = AccountKeyring::Alice.to_account_id();
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
+
+		let short_renewal_period = <MaxAttestationRenewalPeriod>::get() / 4;
+		assert_ok!(Teerex::set_attestation_freshness_policy(
+			RuntimeOrigin::root(),
+			SgxAttestationMethod::Dcap { proxied: true }.into(),
+			Some(AttestationFreshnessPolicy::new(<MomentsPerDay>::get(), short_renewal_period))
+		));
+
+		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer.clone()),
+			TEST1_DCAP_QUOTE.to_vec(),
+			None,
+			SgxAttestationMethod::Dcap { proxied: false }
+		));
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(alice.clone()),
+			TEST1_DCAP_QUOTE.to_vec(),
+			None,
+			SgxAttestationMethod::Dcap { proxied: true }
+		));
+		let instance_address = EnclaveInstanceAddress {
+			fingerprint: TEST1_DCAP_QUOTE_MRENCLAVE.into(),
+			registrar: alice.clone(),
+			signer: AnySigner::try_from(TEST1_DCAP_QUOTE_SIGNER).unwrap(),
+		};
+
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP + short_renewal_period + 1);
+
+		assert_err!(
+			Teerex::unregister_sovereign_enclave(
+				RuntimeOrigin::signed(alice.clone()),
+				signer.clone()
+			),
+			Error::<Test>::UnregisterActiveEnclaveIsNotAllowed
+		);
+		assert_ok!(Teerex::unregister_proxied_enclave(
+			RuntimeOrigin::signed(alice.clone()),
+			instance_address.clone()
+		));
+		assert!(<SovereignEnclaves<Test>>::contains_key(&signer));
+		assert!(!<ProxiedEnclaves<Test>>::contains_key(&instance_address));
+	})
+}
+
 #[test]
 fn add_and_remove_dcap_enclave_works() {
 	new_test_ext().execute_with(|| {
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
 
 		let alice = AccountKeyring::Alice.to_account_id();
-		register_test_quoting_enclave::<Test>(alice.clone());
-		register_test_tcb_info::<Test>(alice.clone());
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
 
 		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
 		assert_ok!(Teerex::register_sgx_enclave(
@@ -116,8 +380,8 @@ fn add_and_remove_dcap_proxied_enclave_works() {
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
 
 		let alice = AccountKeyring::Alice.to_account_id();
-		register_test_quoting_enclave::<Test>(alice.clone());
-		register_test_tcb_info::<Test>(alice.clone());
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
 
 		let instance_address = EnclaveInstanceAddress {
 			fingerprint: TEST1_DCAP_QUOTE_MRENCLAVE.into(),
@@ -157,7 +421,7 @@ fn outdated_tcb_status_is_reported_correctly() {
 		)],
 	);
 	let qe_identity: EnclaveIdentity = serde_json::from_slice(QUOTING_ENCLAVE).unwrap();
-	let quoting_enclave = qe_identity.to_quoting_enclave();
+	let quoting_enclave = qe_identity.to_quoting_enclave(Default::default(), None);
 
 	let quote_bytes = hex::decode(TEST2_DCAP_QUOTE_HEX.trim()).unwrap();
 	let (_fmspc, tcb_info, _report) =
@@ -243,13 +507,47 @@ fn skip_attestation_add_proxied_enclave_works_if_allowed() {
 	})
 }
 
+#[test]
+fn skip_attestation_respects_security_flags_override() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		<AllowSkippingAttestation<Test>>::set(false);
+		assert_ok!(Teerex::set_security_flags_override(
+			RuntimeOrigin::root(),
+			TEST1_DCAP_QUOTE_MRENCLAVE.into(),
+			true,
+			false
+		));
+		let alice = AccountKeyring::Alice.to_account_id();
+
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(alice.clone()),
+			TEST1_DCAP_QUOTE_MRENCLAVE.to_vec(),
+			None,
+			SgxAttestationMethod::Skip { proxied: true }
+		));
+		assert_eq!(list_proxied_enclaves().len(), 1);
+
+		// any other fingerprint is still subject to the global flag
+		assert_err!(
+			Teerex::register_sgx_enclave(
+				RuntimeOrigin::signed(alice),
+				TEST4_MRENCLAVE.to_vec(),
+				None,
+				SgxAttestationMethod::Skip { proxied: true }
+			),
+			Error::<Test>::SkippingAttestationIsNotAllowed
+		);
+	})
+}
+
 #[test]
 fn unregister_active_sovereign_enclave_fails() {
 	new_test_ext().execute_with(|| {
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
 		let alice = AccountKeyring::Alice.to_account_id();
-		register_test_quoting_enclave::<Test>(alice.clone());
-		register_test_tcb_info::<Test>(alice.clone());
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
 
 		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
 		assert_ok!(Teerex::register_sgx_enclave(
@@ -281,8 +579,8 @@ fn unregister_active_proxied_enclave_fails() {
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
 
 		let alice = AccountKeyring::Alice.to_account_id();
-		register_test_quoting_enclave::<Test>(alice.clone());
-		register_test_tcb_info::<Test>(alice.clone());
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
 
 		let instance_address = EnclaveInstanceAddress {
 			fingerprint: TEST1_DCAP_QUOTE_MRENCLAVE.into(),
@@ -316,14 +614,15 @@ fn unregister_active_proxied_enclave_fails() {
 #[test]
 fn register_quoting_enclave_works() {
 	new_test_ext().execute_with(|| {
-		let alice = AccountKeyring::Alice.to_account_id();
 		let qe = Teerex::quoting_enclave();
 		assert_eq!(qe.mrsigner, [0u8; 32]);
 		assert_eq!(qe.isvprodid, 0);
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
-		register_test_quoting_enclave::<Test>(alice);
+		register_test_quoting_enclave::<Test>(collateral_origin());
 		let qe = Teerex::quoting_enclave();
 		assert_eq!(qe.isvprodid, 1);
+		// only the `UpToDate` level of the QE identity is kept
+		assert_eq!(qe.tcb, vec![QeTcb::new(6)]);
 
 		let expected_event =
 			RuntimeEvent::Teerex(TeerexEvent::SgxQuotingEnclaveRegistered { quoting_enclave: qe });
@@ -331,12 +630,176 @@ fn register_quoting_enclave_works() {
 	})
 }
 
+#[test]
+fn register_collateral_with_unsigned_origin_fails() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		assert_noop!(
+			Teerex::register_quoting_enclave(
+				RuntimeOrigin::none(),
+				QUOTING_ENCLAVE.to_vec(),
+				QUOTING_ENCLAVE_SIGNATURE.to_vec(),
+				QE_IDENTITY_ISSUER_CHAIN.to_vec(),
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Teerex::register_tcb_info(
+				RuntimeOrigin::none(),
+				TCB_INFO.to_vec(),
+				TCB_INFO_SIGNATURE.to_vec(),
+				TCB_INFO_CERTIFICATE_CHAIN.to_vec(),
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_quoting_enclave_with_short_chain_fails_with_sgx_verify_error() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		let alice = AccountKeyring::Alice.to_account_id();
+		let end_marker = b"-----END CERTIFICATE-----";
+		let leaf_end = QE_IDENTITY_ISSUER_CHAIN
+			.windows(end_marker.len())
+			.position(|w| w == end_marker)
+			.unwrap() + end_marker.len();
+		let leaf_only = QE_IDENTITY_ISSUER_CHAIN[..leaf_end].to_vec();
+		assert_err!(
+			Teerex::register_quoting_enclave(
+				RuntimeOrigin::signed(alice),
+				QUOTING_ENCLAVE.to_vec(),
+				QUOTING_ENCLAVE_SIGNATURE.to_vec(),
+				leaf_only,
+			),
+			Error::<Test>::SgxVerifyError(sgx_verify::Error::CertificateChainIsTooShort)
+		);
+	})
+}
+
+#[test]
+fn set_quoting_enclave_min_isvsvn_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Teerex::quoting_enclave_min_isvsvn(), None);
+		assert_ok!(Teerex::set_quoting_enclave_min_isvsvn(RuntimeOrigin::root(), Some(6)));
+		assert_eq!(<QuotingEnclaveMinIsvsvn<Test>>::get(), Some(6));
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::UpdatedQuotingEnclaveMinIsvsvn {
+			min_isvsvn: Some(6),
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(Teerex::set_quoting_enclave_min_isvsvn(RuntimeOrigin::root(), None));
+		assert_eq!(Teerex::quoting_enclave_min_isvsvn(), None);
+	})
+}
+
+#[test]
+fn set_quoting_enclave_min_isvsvn_with_non_root_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::set_quoting_enclave_min_isvsvn(RuntimeOrigin::signed(alice), Some(6)),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_quoting_enclave_below_min_isvsvn_fails() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		let alice = AccountKeyring::Alice.to_account_id();
+		// the test QE identity is up to date with isvsvn 6
+		assert_ok!(Teerex::set_quoting_enclave_min_isvsvn(RuntimeOrigin::root(), Some(7)));
+		assert_err!(
+			Teerex::register_quoting_enclave(
+				RuntimeOrigin::signed(alice),
+				QUOTING_ENCLAVE.to_vec(),
+				QUOTING_ENCLAVE_SIGNATURE.to_vec(),
+				QE_IDENTITY_ISSUER_CHAIN.to_vec(),
+			),
+			Error::<Test>::CollateralIsInvalid
+		);
+
+		assert_ok!(Teerex::set_quoting_enclave_min_isvsvn(RuntimeOrigin::root(), Some(6)));
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		assert_eq!(Teerex::quoting_enclave().tcb, vec![QeTcb::new(6)]);
+	})
+}
+
+#[test]
+fn register_quoting_enclave_only_keeps_out_of_date_levels_if_allowed() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		// the `OutOfDate` levels of the test QE identity used to be registered, too
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		assert_eq!(Teerex::quoting_enclave().tcb, vec![QeTcb::new(6)]);
+
+		let policy = QeTcbStatusPolicy { accept_out_of_date: true, ..Default::default() };
+		assert_ok!(Teerex::set_quoting_enclave_tcb_status_policy(RuntimeOrigin::root(), policy));
+		let expected_event =
+			RuntimeEvent::Teerex(TeerexEvent::UpdatedQuotingEnclaveTcbStatusPolicy { policy });
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		assert_eq!(
+			Teerex::quoting_enclave().tcb,
+			vec![QeTcb::new(6), QeTcb::new(5), QeTcb::new(4), QeTcb::new(2), QeTcb::new(1)]
+		);
+	})
+}
+
+#[test]
+fn set_quoting_enclave_tcb_status_policy_with_non_root_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::set_quoting_enclave_tcb_status_policy(
+				RuntimeOrigin::signed(alice),
+				QeTcbStatusPolicy { accept_out_of_date: true, ..Default::default() }
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_collateral_with_signed_origin_fails_if_restricted() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		RestrictCollateralOrigin::set(true);
+		assert_noop!(
+			Teerex::register_quoting_enclave(
+				collateral_origin(),
+				QUOTING_ENCLAVE.to_vec(),
+				QUOTING_ENCLAVE_SIGNATURE.to_vec(),
+				QE_IDENTITY_ISSUER_CHAIN.to_vec(),
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Teerex::register_tcb_info(
+				collateral_origin(),
+				TCB_INFO.to_vec(),
+				TCB_INFO_SIGNATURE.to_vec(),
+				TCB_INFO_CERTIFICATE_CHAIN.to_vec(),
+			),
+			BadOrigin
+		);
+
+		register_test_quoting_enclave::<Test>(RuntimeOrigin::root());
+		register_test_tcb_info::<Test>(RuntimeOrigin::root());
+		assert!(Teerex::tcb_info(hex!("00906EA10000")).is_some());
+	})
+}
+
 #[test]
 fn register_tcb_info_works() {
 	new_test_ext().execute_with(|| {
 		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
 
-		register_test_tcb_info::<Test>(AccountKeyring::Alice.to_account_id());
+		register_test_tcb_info::<Test>(collateral_origin());
 		let fmspc = hex!("00906EA10000");
 		let tcb_info = Teerex::tcb_info(fmspc).unwrap();
 		// This is the date that the is registered in register_tcb_info and represents the date 2023-04-16T12:45:32Z
@@ -419,6 +882,7 @@ fn list_enclaves_works() {
 			mr_signer: TEST4_MRSIGNER,
 			attestation_method: SgxAttestationMethod::Ias,
 			status: SgxStatus::ConfigurationNeeded,
+			platform: None,
 		};
 		assert_ok!(Teerex::register_sgx_enclave(
 			RuntimeOrigin::signed(signer.clone()),
@@ -496,6 +960,7 @@ fn update_enclave_url_works() {
 			mr_signer: TEST4_MRSIGNER,
 			attestation_method: SgxAttestationMethod::Ias,
 			status: SgxStatus::ConfigurationNeeded,
+			platform: None,
 		};
 
 		assert_ok!(Teerex::register_sgx_enclave(
@@ -533,6 +998,7 @@ fn debug_mode_enclave_attest_works_when_sgx_debug_mode_is_allowed() {
 			mr_signer: TEST4_MRSIGNER,
 			attestation_method: SgxAttestationMethod::Ias,
 			status: SgxStatus::ConfigurationNeeded,
+			platform: None,
 		};
 
 		//Register an enclave compiled in debug mode
@@ -563,6 +1029,7 @@ fn production_mode_enclave_attest_works_when_sgx_debug_mode_is_allowed() {
 				mr_signer: TEST8_MRSIGNER,
 				attestation_method: SgxAttestationMethod::Ias,
 				status: SgxStatus::Invalid,
+				platform: None,
 			};
 
 			//Register an enclave compiled in production mode
@@ -611,6 +1078,7 @@ fn production_mode_enclave_attest_works_when_sgx_debug_mode_not_allowed() {
 			mr_signer: TEST8_MRSIGNER,
 			attestation_method: SgxAttestationMethod::Ias,
 			status: SgxStatus::Invalid,
+			platform: None,
 		};
 
 		//Register an enclave compiled in production mode
@@ -625,3 +1093,520 @@ fn production_mode_enclave_attest_works_when_sgx_debug_mode_not_allowed() {
 		assert!(enclaves.contains(&(signer8, MultiEnclave::from(e_0))));
 	})
 }
+
+#[test]
+fn debug_mode_enclave_attest_works_with_security_flags_override() {
+	new_test_production_ext().execute_with(|| {
+		set_timestamp(TEST4_TIMESTAMP);
+		let signer4 = get_signer(TEST4_SIGNER_PUB);
+		assert_ok!(Teerex::set_security_flags_override(
+			RuntimeOrigin::root(),
+			TEST4_MRENCLAVE.into(),
+			false,
+			true
+		));
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer4.clone()),
+			TEST4_CERT.to_vec(),
+			Some(URL.to_vec()),
+			SgxAttestationMethod::Ias
+		));
+		assert!(<SovereignEnclaves<Test>>::contains_key(&signer4));
+	})
+}
+
+fn register_test_snp_root_certificate() {
+	assert_ok!(Teerex::set_snp_root_certificate(RuntimeOrigin::root(), TEST_SNP_ARK.to_vec()));
+}
+
+fn register_test_snp_enclave(signer: AccountId) -> DispatchResultWithPostInfo {
+	Teerex::register_snp_enclave(
+		RuntimeOrigin::signed(signer),
+		TEST_SNP_REPORT.to_vec(),
+		TEST_SNP_VCEK.to_vec(),
+		TEST_SNP_ASK.to_vec(),
+		Some(URL.to_vec()),
+	)
+}
+
+#[test]
+fn register_snp_enclave_works() {
+	new_test_production_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		let signer = get_signer(&TEST_SNP_REPORT_SIGNER);
+		register_test_snp_root_certificate();
+		assert_ok!(Teerex::set_snp_min_tcb(RuntimeOrigin::root(), Some(TEST_SNP_TCB)));
+
+		assert_ok!(register_test_snp_enclave(signer.clone()));
+
+		let enclave = Teerex::sovereign_enclaves(&signer).unwrap();
+		let fingerprint =
+			EnclaveFingerprint::from(sp_core::hashing::blake2_256(&TEST_SNP_MEASUREMENT));
+		assert_eq!(enclave.fingerprint(), fingerprint);
+		assert_eq!(
+			enclave.instance_signer(),
+			AnySigner::from(MultiSigner::from(sp_core::ed25519::Public::from_raw(
+				TEST_SNP_REPORT_SIGNER
+			)))
+		);
+		assert_eq!(enclave.instance_url(), Some(URL.to_vec()));
+		assert_eq!(enclave.attestation_timestamp(), TEST_SNP_TIMESTAMP);
+		assert_eq!(enclave.attestation_method(), AttestationMethod::AmdSevSnp);
+		if let MultiEnclave::Snp(snp_enclave) = enclave {
+			assert_eq!(snp_enclave.id_key_digest, TEST_SNP_ID_KEY_DIGEST);
+			assert_eq!(snp_enclave.reported_tcb, TEST_SNP_TCB);
+		} else {
+			panic!("registered enclave is not an SEV-SNP enclave")
+		}
+
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::AddedSnpEnclave {
+			registered_by: signer,
+			worker_url: Some(URL.to_vec()),
+			fingerprint,
+			reported_tcb: TEST_SNP_TCB,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn register_snp_enclave_with_different_signer_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		register_test_snp_root_certificate();
+		assert_noop!(
+			register_test_snp_enclave(AccountKeyring::Bob.to_account_id()),
+			Error::<Test>::SenderIsNotAttestedEnclave
+		);
+	})
+}
+
+#[test]
+fn register_snp_enclave_without_root_certificate_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		assert_noop!(
+			register_test_snp_enclave(get_signer(&TEST_SNP_REPORT_SIGNER)).map_err(|e| e.error),
+			Error::<Test>::SnpRootCertificateIsMissing
+		);
+	})
+}
+
+#[test]
+fn early_rejected_snp_enclave_registration_is_refunded() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		let early_rejection_weight = Some(<() as WeightInfo>::register_sgx_enclave_skip());
+
+		let err = register_test_snp_enclave(get_signer(&TEST_SNP_REPORT_SIGNER)).unwrap_err();
+		assert_eq!(err.error, Error::<Test>::SnpRootCertificateIsMissing.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+
+		register_test_snp_root_certificate();
+		let err = Teerex::register_snp_enclave(
+			RuntimeOrigin::signed(get_signer(&TEST_SNP_REPORT_SIGNER)),
+			vec![0; crate::SNP_PROOF_MAX_LEN + 1],
+			TEST_SNP_VCEK.to_vec(),
+			TEST_SNP_ASK.to_vec(),
+			None,
+		)
+		.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::RaProofIsTooLong.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+	})
+}
+
+#[test]
+fn register_snp_enclave_below_min_tcb_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		register_test_snp_root_certificate();
+		let min_tcb = SnpTcbVersion { microcode: TEST_SNP_TCB.microcode + 1, ..TEST_SNP_TCB };
+		assert_ok!(Teerex::set_snp_min_tcb(RuntimeOrigin::root(), Some(min_tcb)));
+		assert_noop!(
+			register_test_snp_enclave(get_signer(&TEST_SNP_REPORT_SIGNER)),
+			Error::<Test>::SnpVerifyError(snp_verify::Error::TcbVersionIsTooLow)
+		);
+	})
+}
+
+#[test]
+fn set_snp_root_certificate_works() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		register_test_snp_root_certificate();
+		assert_eq!(<SnpRootCertificate<Test>>::get(), Some(TEST_SNP_ARK.to_vec()));
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::UpdatedSnpRootCertificate);
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn set_snp_root_certificate_with_non_root_certificate_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		assert_noop!(
+			Teerex::set_snp_root_certificate(RuntimeOrigin::root(), TEST_SNP_ASK.to_vec()),
+			Error::<Test>::SnpVerifyError(snp_verify::Error::CertificateIssuerMismatch)
+		);
+	})
+}
+
+#[test]
+fn snp_governance_calls_fail_for_non_root() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_SNP_TIMESTAMP);
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::set_snp_root_certificate(
+				RuntimeOrigin::signed(alice.clone()),
+				TEST_SNP_ARK.to_vec()
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Teerex::set_snp_min_tcb(RuntimeOrigin::signed(alice), Some(TEST_SNP_TCB)),
+			BadOrigin
+		);
+		assert_eq!(<SnpMinTcb<Test>>::get(), None);
+	})
+}
+
+fn test_eat_profile() -> EatProfile {
+	EatProfile::try_from(TEST_EAT_PROFILE.to_vec()).unwrap()
+}
+
+fn test_eat_trusted_key() -> EatTrustedKey {
+	EatTrustedKey {
+		algorithm: EatSignatureAlgorithm::Es256,
+		public_key: TEST_EAT_PUBLIC_KEY.to_vec().try_into().unwrap(),
+		measurement_claim: TEST_EAT_MEASUREMENT_CLAIM,
+	}
+}
+
+fn add_test_eat_trusted_key(profile: EatProfile) {
+	assert_ok!(Teerex::add_eat_trusted_key(RuntimeOrigin::root(), profile, test_eat_trusted_key()));
+}
+
+fn register_test_eat_enclave(signer: AccountId, profile: EatProfile) -> DispatchResultWithPostInfo {
+	Teerex::register_eat_enclave(
+		RuntimeOrigin::signed(signer),
+		profile,
+		test_eat_trusted_key().key_id(),
+		TEST_EAT_TOKEN.to_vec(),
+		Some(URL.to_vec()),
+	)
+}
+
+#[test]
+fn register_eat_enclave_works() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP + 1000);
+		let signer = get_signer(&TEST_EAT_SIGNER);
+		add_test_eat_trusted_key(test_eat_profile());
+
+		assert_ok!(register_test_eat_enclave(signer.clone(), test_eat_profile()));
+
+		let enclave = Teerex::sovereign_enclaves(&signer).unwrap();
+		let fingerprint =
+			EnclaveFingerprint::from(sp_core::hashing::blake2_256(&TEST_EAT_MEASUREMENT));
+		assert_eq!(enclave.fingerprint(), fingerprint);
+		assert_eq!(
+			enclave.instance_signer(),
+			AnySigner::from(MultiSigner::from(sp_core::ed25519::Public::from_raw(TEST_EAT_SIGNER)))
+		);
+		assert_eq!(enclave.attestation_timestamp(), TEST_EAT_TIMESTAMP);
+		assert_eq!(enclave.attestation_method(), AttestationMethod::Eat);
+		if let MultiEnclave::Eat(eat_enclave) = enclave {
+			assert_eq!(eat_enclave.profile, test_eat_profile());
+			assert_eq!(eat_enclave.key_id, test_eat_trusted_key().key_id());
+		} else {
+			panic!("registered enclave is not an EAT enclave")
+		}
+
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::AddedEatEnclave {
+			registered_by: signer,
+			worker_url: Some(URL.to_vec()),
+			profile: test_eat_profile(),
+			fingerprint,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn register_eat_enclave_with_different_signer_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP);
+		add_test_eat_trusted_key(test_eat_profile());
+		assert_noop!(
+			register_test_eat_enclave(AccountKeyring::Bob.to_account_id(), test_eat_profile()),
+			Error::<Test>::SenderIsNotAttestedEnclave
+		);
+	})
+}
+
+#[test]
+fn register_eat_enclave_with_untrusted_key_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP);
+		assert_noop!(
+			register_test_eat_enclave(get_signer(&TEST_EAT_SIGNER), test_eat_profile())
+				.map_err(|e| e.error),
+			Error::<Test>::EatTrustedKeyNotFound
+		);
+	})
+}
+
+#[test]
+fn early_rejected_eat_enclave_registration_is_refunded() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP);
+		let early_rejection_weight = Some(<() as WeightInfo>::register_sgx_enclave_skip());
+
+		let err = register_test_eat_enclave(get_signer(&TEST_EAT_SIGNER), test_eat_profile())
+			.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::EatTrustedKeyNotFound.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+
+		add_test_eat_trusted_key(test_eat_profile());
+		let err = Teerex::register_eat_enclave(
+			RuntimeOrigin::signed(get_signer(&TEST_EAT_SIGNER)),
+			test_eat_profile(),
+			test_eat_trusted_key().key_id(),
+			vec![0; crate::EAT_TOKEN_MAX_LEN + 1],
+			None,
+		)
+		.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::RaProofIsTooLong.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+	})
+}
+
+#[test]
+fn register_eat_enclave_of_other_profile_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP);
+		let other_profile = EatProfile::try_from(b"tag:arm.com,2023:realm#1.0.0".to_vec()).unwrap();
+		add_test_eat_trusted_key(other_profile.clone());
+		assert_noop!(
+			register_test_eat_enclave(get_signer(&TEST_EAT_SIGNER), other_profile),
+			Error::<Test>::EatVerifyError(eat_verify::Error::ProfileMismatch)
+		);
+	})
+}
+
+#[test]
+fn register_eat_enclave_with_too_old_token_fails() {
+	new_test_ext().execute_with(|| {
+		set_timestamp(TEST_EAT_TIMESTAMP + 2 * 24 * 60 * 60 * 1000);
+		add_test_eat_trusted_key(test_eat_profile());
+		assert_noop!(
+			register_test_eat_enclave(get_signer(&TEST_EAT_SIGNER), test_eat_profile()),
+			Error::<Test>::RemoteAttestationIsTooOld
+		);
+	})
+}
+
+#[test]
+fn add_and_remove_eat_trusted_key_works() {
+	new_test_ext().execute_with(|| {
+		let key_id = test_eat_trusted_key().key_id();
+		add_test_eat_trusted_key(test_eat_profile());
+		assert_eq!(
+			<EatTrustedKeys<Test>>::get(test_eat_profile(), key_id),
+			Some(test_eat_trusted_key())
+		);
+
+		assert_ok!(Teerex::remove_eat_trusted_key(
+			RuntimeOrigin::root(),
+			test_eat_profile(),
+			key_id
+		));
+		assert!(!<EatTrustedKeys<Test>>::contains_key(test_eat_profile(), key_id));
+		let expected_event = RuntimeEvent::Teerex(TeerexEvent::RemovedEatTrustedKey {
+			profile: test_eat_profile(),
+			key_id,
+		});
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(
+			Teerex::remove_eat_trusted_key(RuntimeOrigin::root(), test_eat_profile(), key_id),
+			Error::<Test>::EatTrustedKeyNotFound
+		);
+	})
+}
+
+#[test]
+fn eat_trusted_key_calls_fail_for_non_root() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::add_eat_trusted_key(
+				RuntimeOrigin::signed(alice.clone()),
+				test_eat_profile(),
+				test_eat_trusted_key()
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Teerex::remove_eat_trusted_key(
+				RuntimeOrigin::signed(alice),
+				test_eat_profile(),
+				test_eat_trusted_key().key_id()
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn register_sgx_enclave_returns_actual_weight() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
+		let tcb_levels = get_test_tcb_info::<Test>().tcb_levels().len() as u32;
+
+		let post_info = Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(get_signer(&TEST1_DCAP_QUOTE_SIGNER)),
+			TEST1_DCAP_QUOTE.to_vec(),
+			None,
+			SgxAttestationMethod::Dcap { proxied: false },
+		)
+		.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<() as WeightInfo>::register_sgx_enclave(
+				TEST1_DCAP_QUOTE.len() as u32,
+				tcb_levels
+			))
+		);
+	})
+}
+
+#[test]
+fn register_sgx_enclave_with_padded_quote_works() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
+		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer.clone()),
+			pad_dcap_quote(TEST1_DCAP_QUOTE, crate::SGX_RA_PROOF_MAX_LEN),
+			None,
+			SgxAttestationMethod::Dcap { proxied: false }
+		));
+		assert!(<SovereignEnclaves<Test>>::contains_key(&signer));
+	})
+}
+
+#[test]
+fn early_rejected_sgx_enclave_registration_is_refunded() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+		let early_rejection_weight = Some(<() as WeightInfo>::register_sgx_enclave_skip());
+
+		let err = Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer.clone()),
+			vec![0; crate::SGX_RA_PROOF_MAX_LEN + 1],
+			None,
+			SgxAttestationMethod::Dcap { proxied: false },
+		)
+		.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::RaProofIsTooLong.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+
+		let err = Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer),
+			TEST1_DCAP_QUOTE_MRENCLAVE.to_vec(),
+			None,
+			SgxAttestationMethod::Skip { proxied: false },
+		)
+		.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::SkippingAttestationIsNotAllowed.into());
+		assert_eq!(err.post_info.actual_weight, early_rejection_weight);
+	})
+}
+
+#[test]
+fn failed_dcap_verification_is_refunded_to_the_proof_length() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		// no quoting enclave registered
+		let err = Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(get_signer(&TEST1_DCAP_QUOTE_SIGNER)),
+			TEST1_DCAP_QUOTE.to_vec(),
+			None,
+			SgxAttestationMethod::Dcap { proxied: false },
+		)
+		.unwrap_err();
+		assert_eq!(
+			err.post_info.actual_weight,
+			Some(<() as WeightInfo>::register_sgx_enclave(TEST1_DCAP_QUOTE.len() as u32, 1))
+		);
+	})
+}
+
+#[test]
+fn set_allowed_sgx_platforms_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Teerex::allowed_sgx_platforms(), SgxPlatformPolicy::default());
+		let policy = SgxPlatformPolicy { allow_smt: false, ..Default::default() };
+		assert_ok!(Teerex::set_allowed_sgx_platforms(RuntimeOrigin::root(), policy));
+		assert_eq!(<AllowedSgxPlatforms<Test>>::get(), policy);
+		let expected_event =
+			RuntimeEvent::Teerex(TeerexEvent::UpdatedAllowedSgxPlatforms { policy });
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn set_allowed_sgx_platforms_with_non_root_fails() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_noop!(
+			Teerex::set_allowed_sgx_platforms(
+				RuntimeOrigin::signed(alice),
+				SgxPlatformPolicy::default()
+			),
+			BadOrigin
+		);
+	})
+}
+
+#[test]
+fn dcap_enclave_records_its_platform() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST_VALID_COLLATERAL_TIMESTAMP);
+		register_test_quoting_enclave::<Test>(collateral_origin());
+		register_test_tcb_info::<Test>(collateral_origin());
+		// The test platform is a single-package one with standard SGX, it states no configuration
+		let strict = SgxPlatformPolicy {
+			allow_scalable_without_integrity: false,
+			allow_dynamic_platform: false,
+			allow_cached_keys: false,
+			allow_smt: false,
+		};
+		assert_ok!(Teerex::set_allowed_sgx_platforms(RuntimeOrigin::root(), strict));
+
+		let signer = get_signer(&TEST1_DCAP_QUOTE_SIGNER);
+		assert_ok!(Teerex::register_sgx_enclave(
+			RuntimeOrigin::signed(signer.clone()),
+			TEST1_DCAP_QUOTE.to_vec(),
+			None,
+			SgxAttestationMethod::Dcap { proxied: false }
+		));
+		if let MultiEnclave::Sgx(sgx_enclave) = Teerex::sovereign_enclaves(&signer).unwrap() {
+			let platform = sgx_enclave.platform.unwrap();
+			assert_eq!(platform.sgx_type, SgxType::Standard);
+			assert_eq!(platform.platform_instance_id, None);
+			assert_eq!(platform.configuration, None);
+		} else {
+			panic!("wrong enclave type")
+		}
+	})
+}
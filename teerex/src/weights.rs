@@ -50,37 +50,38 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_teerex.
 pub trait WeightInfo {
-	fn register_sgx_enclave() -> Weight;
+	fn register_sgx_enclave(p: u32, l: u32) -> Weight;
+	fn register_sgx_enclave_skip() -> Weight;
 	fn register_quoting_enclave() -> Weight;
-	fn register_tcb_info() -> Weight;
+	fn register_tcb_info(x: u32) -> Weight;
 	fn unregister_sovereign_enclave() -> Weight;
 	fn unregister_proxied_enclave() -> Weight;
 	fn set_security_flags() -> Weight;
+	fn set_attestation_freshness_policy() -> Weight;
+	fn set_security_flags_override() -> Weight;
+	fn clear_security_flags_override() -> Weight;
+	fn set_quoting_enclave_min_isvsvn() -> Weight;
+	fn set_quoting_enclave_tcb_status_policy() -> Weight;
+	fn register_snp_enclave() -> Weight;
+	fn set_snp_root_certificate() -> Weight;
+	fn set_snp_min_tcb() -> Weight;
+	fn register_eat_enclave() -> Weight;
+	fn add_eat_trusted_key() -> Weight;
+	fn remove_eat_trusted_key() -> Weight;
+	fn set_allowed_sgx_platforms() -> Weight;
 }
 
 /// Weights for pallet_teerex using the Integritee parachain node and recommended hardware.
 pub struct IntegriteeWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
-	/// Storage: Timestamp Now (r:1 w:0)
-	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: Teerex SgxQuotingEnclaveRegistry (r:1 w:0)
-	/// Proof Skipped: Teerex SgxQuotingEnclaveRegistry (max_values: Some(1), max_size: None, mode: Measured)
-	/// Storage: Teerex SgxTcbInfo (r:1 w:0)
-	/// Proof Skipped: Teerex SgxTcbInfo (max_values: None, max_size: None, mode: Measured)
-	/// Storage: Teerex SgxAllowDebugMode (r:1 w:0)
-	/// Proof Skipped: Teerex SgxAllowDebugMode (max_values: Some(1), max_size: None, mode: Measured)
-	/// Storage: Teerex SovereignEnclaves (r:0 w:1)
-	/// Proof Skipped: Teerex SovereignEnclaves (max_values: None, max_size: None, mode: Measured)
-	fn register_sgx_enclave() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `394`
-		//  Estimated: `3859`
-		// Minimum execution time: 2_017_551_000 picoseconds.
-		Weight::from_parts(2_049_028_000, 0)
-			.saturating_add(Weight::from_parts(0, 3859))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(1))
+	fn register_sgx_enclave(p: u32, l: u32) -> Weight {
+		estimates::register_sgx_enclave(T::DbWeight::get(), p, l)
+	}
+
+	fn register_sgx_enclave_skip() -> Weight {
+		estimates::register_sgx_enclave_skip(T::DbWeight::get())
 	}
+
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: Teerex SgxQuotingEnclaveRegistry (r:0 w:1)
@@ -95,20 +96,11 @@ impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
-	/// Storage: Timestamp Now (r:1 w:0)
-	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: Teerex SgxTcbInfo (r:0 w:1)
-	/// Proof Skipped: Teerex SgxTcbInfo (max_values: None, max_size: None, mode: Measured)
-	fn register_tcb_info() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `95`
-		//  Estimated: `1493`
-		// Minimum execution time: 1_120_761_000 picoseconds.
-		Weight::from_parts(1_128_361_000, 0)
-			.saturating_add(Weight::from_parts(0, 1493))
-			.saturating_add(T::DbWeight::get().reads(1))
-			.saturating_add(T::DbWeight::get().writes(1))
+
+	fn register_tcb_info(x: u32) -> Weight {
+		estimates::register_tcb_info(T::DbWeight::get(), x)
 	}
+
 	/// Storage: Teerex SovereignEnclaves (r:1 w:1)
 	/// Proof Skipped: Teerex SovereignEnclaves (max_values: None, max_size: None, mode: Measured)
 	/// Storage: Timestamp Now (r:1 w:0)
@@ -140,42 +132,66 @@ impl<T: frame_system::Config> WeightInfo for IntegriteeWeight<T> {
 	fn set_security_flags() -> Weight {
 		Weight::from_parts(46_200_000, 0u64)
 	}
+
+	fn set_attestation_freshness_policy() -> Weight {
+		estimates::set_attestation_freshness_policy(T::DbWeight::get())
+	}
+
+	fn set_security_flags_override() -> Weight {
+		estimates::set_security_flags_override(T::DbWeight::get())
+	}
+
+	fn clear_security_flags_override() -> Weight {
+		estimates::clear_security_flags_override(T::DbWeight::get())
+	}
+
+	fn set_quoting_enclave_min_isvsvn() -> Weight {
+		estimates::set_quoting_enclave_min_isvsvn(T::DbWeight::get())
+	}
+
+	fn set_quoting_enclave_tcb_status_policy() -> Weight {
+		estimates::set_quoting_enclave_tcb_status_policy(T::DbWeight::get())
+	}
+
+	fn register_snp_enclave() -> Weight {
+		estimates::register_snp_enclave(T::DbWeight::get())
+	}
+
+	fn set_snp_root_certificate() -> Weight {
+		estimates::set_snp_root_certificate(T::DbWeight::get())
+	}
+
+	fn set_snp_min_tcb() -> Weight {
+		estimates::set_snp_min_tcb(T::DbWeight::get())
+	}
+
+	fn register_eat_enclave() -> Weight {
+		estimates::register_eat_enclave(T::DbWeight::get())
+	}
+
+	fn add_eat_trusted_key() -> Weight {
+		estimates::add_eat_trusted_key(T::DbWeight::get())
+	}
+
+	fn remove_eat_trusted_key() -> Weight {
+		estimates::remove_eat_trusted_key(T::DbWeight::get())
+	}
+
+	fn set_allowed_sgx_platforms() -> Weight {
+		estimates::set_allowed_sgx_platforms(T::DbWeight::get())
+	}
 }
 
 /// For tests, weights have been generated with the integritee-node.
 impl WeightInfo for () {
-	/// Storage: Timestamp Now (r:1 w:0)
-	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: Teerex AllowSGXDebugMode (r:1 w:0)
-	/// Proof Skipped: Teerex AllowSGXDebugMode (max_values: Some(1), max_size: None, mode: Measured)
-	/// Storage: Teerex EnclaveIndex (r:1 w:0)
-	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
-	/// Storage: Teerex EnclaveRegistry (r:0 w:1)
-	/// Proof Skipped: Teerex EnclaveRegistry (max_values: None, max_size: None, mode: Measured)
-	fn register_sgx_enclave() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `299`
-		//  Estimated: `4370`
-		// Minimum execution time: 1_512_300 nanoseconds.
-		Weight::from_parts(2_591_400_000, 0u64)
-			.saturating_add(Weight::from_parts(0u64, 4370))
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().writes(1))
+	fn register_sgx_enclave(p: u32, l: u32) -> Weight {
+		estimates::register_sgx_enclave(RocksDbWeight::get(), p, l)
 	}
-	/// Storage: Timestamp Now (r:1 w:0)
-	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	/// Storage: Teerex TcbInfo (r:0 w:1)
-	/// Proof Skipped: Teerex TcbInfo (max_values: None, max_size: None, mode: Measured)
-	fn register_tcb_info() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `57`
-		//  Estimated: `560`
-		// Minimum execution time: 1_816_099 nanoseconds.
-		Weight::from_parts(3_147_800_000, 0u64)
-			.saturating_add(Weight::from_parts(0u64, 560))
-			.saturating_add(RocksDbWeight::get().reads(1))
-			.saturating_add(RocksDbWeight::get().writes(1))
+
+	fn register_sgx_enclave_skip() -> Weight {
+		estimates::register_sgx_enclave_skip(RocksDbWeight::get())
 	}
+
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
 	/// Storage: Teerex QuotingEnclaveRegistry (r:0 w:1)
@@ -190,6 +206,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
+
+	fn register_tcb_info(x: u32) -> Weight {
+		estimates::register_tcb_info(RocksDbWeight::get(), x)
+	}
+
 	/// Storage: Teerex SovereignEnclaves (r:1 w:1)
 	/// Proof Skipped: Teerex SovereignEnclaves (max_values: None, max_size: None, mode: Measured)
 	/// Storage: Timestamp Now (r:1 w:0)
@@ -221,4 +242,150 @@ impl WeightInfo for () {
 	fn set_security_flags() -> Weight {
 		Weight::from_parts(46_200_000, 0u64)
 	}
+
+	fn set_attestation_freshness_policy() -> Weight {
+		estimates::set_attestation_freshness_policy(RocksDbWeight::get())
+	}
+
+	fn set_security_flags_override() -> Weight {
+		estimates::set_security_flags_override(RocksDbWeight::get())
+	}
+
+	fn clear_security_flags_override() -> Weight {
+		estimates::clear_security_flags_override(RocksDbWeight::get())
+	}
+
+	fn set_quoting_enclave_min_isvsvn() -> Weight {
+		estimates::set_quoting_enclave_min_isvsvn(RocksDbWeight::get())
+	}
+
+	fn set_quoting_enclave_tcb_status_policy() -> Weight {
+		estimates::set_quoting_enclave_tcb_status_policy(RocksDbWeight::get())
+	}
+
+	fn register_snp_enclave() -> Weight {
+		estimates::register_snp_enclave(RocksDbWeight::get())
+	}
+
+	fn set_snp_root_certificate() -> Weight {
+		estimates::set_snp_root_certificate(RocksDbWeight::get())
+	}
+
+	fn set_snp_min_tcb() -> Weight {
+		estimates::set_snp_min_tcb(RocksDbWeight::get())
+	}
+
+	fn register_eat_enclave() -> Weight {
+		estimates::register_eat_enclave(RocksDbWeight::get())
+	}
+
+	fn add_eat_trusted_key() -> Weight {
+		estimates::add_eat_trusted_key(RocksDbWeight::get())
+	}
+
+	fn remove_eat_trusted_key() -> Weight {
+		estimates::remove_eat_trusted_key(RocksDbWeight::get())
+	}
+
+	fn set_allowed_sgx_platforms() -> Weight {
+		estimates::set_allowed_sgx_platforms(RocksDbWeight::get())
+	}
+}
+
+/// Hand-written estimates for the calls which haven't been benchmarked yet. They are NOT
+/// generated and must be replaced by the output of running the benchmarks of these calls.
+///
+/// Signature verification dominates the registrations, so they are estimated by the measured
+/// weights of generated calls verifying at least as much. The components of
+/// `register_sgx_enclave` and `register_tcb_info` are ignored until their slopes are measured.
+/// Setters are estimated like `set_security_flags`. The reads and writes are counted from the
+/// worst case of each call.
+mod estimates {
+	use frame_support::weights::{RuntimeDbWeight, Weight};
+
+	/// The last measurement with the test quote. The timestamp, the quoting enclave, the allowed
+	/// platforms, the TCB info, the freshness policy and the security flags are read.
+	pub fn register_sgx_enclave(db: RuntimeDbWeight, _p: u32, _l: u32) -> Weight {
+		Weight::from_parts(2_049_028_000, 3859)
+			.saturating_add(db.reads(8))
+			.saturating_add(db.writes(1))
+	}
+
+	/// Like `unregister_sovereign_enclave`. The security flags, the timestamp and the freshness
+	/// policy are read.
+	pub fn register_sgx_enclave_skip(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(25_102_000, 3938)
+			.saturating_add(db.reads(5))
+			.saturating_add(db.writes(1))
+	}
+
+	/// The last measurement with the test collateral.
+	pub fn register_tcb_info(db: RuntimeDbWeight, _x: u32) -> Weight {
+		Weight::from_parts(1_128_361_000, 1493)
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(1))
+	}
+
+	pub fn set_attestation_freshness_policy(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	pub fn set_security_flags_override(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	pub fn clear_security_flags_override(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0)
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(1))
+	}
+
+	pub fn set_quoting_enclave_min_isvsvn(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	pub fn set_quoting_enclave_tcb_status_policy(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	/// Like `register_sgx_enclave`, as both verify a certificate chain and a report signature.
+	/// The root certificate, the timestamp, the minimal TCB and the security flags are read.
+	pub fn register_snp_enclave(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(2_049_028_000, 3859)
+			.saturating_add(db.reads(6))
+			.saturating_add(db.writes(1))
+	}
+
+	/// Like `register_quoting_enclave`, which verifies a whole certificate chain.
+	pub fn set_snp_root_certificate(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(1_031_990_000, 1493)
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(1))
+	}
+
+	pub fn set_snp_min_tcb(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	/// Like `register_quoting_enclave`, which verifies a whole certificate chain rather than a
+	/// single signature. The trusted key, the timestamp and the freshness policy are read.
+	pub fn register_eat_enclave(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(1_031_990_000, 3859)
+			.saturating_add(db.reads(3))
+			.saturating_add(db.writes(1))
+	}
+
+	pub fn add_eat_trusted_key(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
+
+	pub fn remove_eat_trusted_key(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0)
+			.saturating_add(db.reads(1))
+			.saturating_add(db.writes(1))
+	}
+
+	pub fn set_allowed_sgx_platforms(db: RuntimeDbWeight) -> Weight {
+		Weight::from_parts(46_200_000, 0).saturating_add(db.writes(1))
+	}
 }
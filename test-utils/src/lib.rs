@@ -17,7 +17,9 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use eat_verify::test_data as eat_test_data;
 pub use sgx_verify::test_data;
+pub use snp_verify::test_data as snp_test_data;
 pub use teerex_primitives::{MrEnclave, SgxEnclave};
 
 pub fn get_signer<AccountId: From<[u8; 32]>>(pubkey: &[u8; 32]) -> AccountId {